@@ -45,6 +45,8 @@ impl Hash for VoteBufferKey {
 pub struct VoteBufferValue {
     pub accumulated_votes: u64,
     pub last_click_ts: i64,
+    /// Sum of the per-click max costs in the key's token mint; u64::MAX once any click had no limit
+    pub max_cost_in_token: u64,
 }
 
 /// Application state shared across all handlers
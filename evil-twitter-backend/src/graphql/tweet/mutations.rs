@@ -5,7 +5,7 @@ use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use hex;
-use opinions_market::state::Side;
+use opinions_market::state::{CurveKind, PayoutWeighting, Side, VoteMode};
 use solana_sdk::pubkey::Pubkey;
 use std::sync::Arc;
 
@@ -52,6 +52,8 @@ pub struct TweetVoteInput {
     pub tweet_id: ID,
     pub side: String,               // "pump" or "smack"
     pub token_mint: Option<String>, // Optional, defaults to BLING
+    /// Most the user accepts to pay for this vote, in token_mint base units. Optional, no limit
+    pub max_cost_in_token: Option<String>,
 }
 
 #[derive(InputObject)]
@@ -295,7 +297,14 @@ pub async fn tweet_create_resolver(
         // Call create_post on-chain (backend signs transaction)
         match app_state
             .solana_service
-            .create_post(user_wallet, post_id_hash, None)
+            .create_post(
+                user_wallet,
+                post_id_hash,
+                None,
+                CurveKind::Linear,
+                VoteMode::Open,
+                PayoutWeighting::PerVote,
+            )
             .await
         {
             Ok(signature) => {
@@ -413,7 +422,14 @@ pub async fn tweet_reply_resolver(
             // Call create_post on-chain with parent_post_pda
             match app_state
                 .solana_service
-                .create_post(user_wallet, post_id_hash, Some(parent_post_pda))
+                .create_post(
+                    user_wallet,
+                    post_id_hash,
+                    Some(parent_post_pda),
+                    CurveKind::Linear,
+                    VoteMode::Open,
+                    PayoutWeighting::PerVote,
+                )
                 .await
             {
                 Ok(signature) => {
@@ -509,7 +525,14 @@ pub async fn tweet_quote_resolver(
             // Call create_post on-chain with parent_post_pda
             match app_state
                 .solana_service
-                .create_post(user_wallet, post_id_hash, Some(parent_post_pda))
+                .create_post(
+                    user_wallet,
+                    post_id_hash,
+                    Some(parent_post_pda),
+                    CurveKind::Linear,
+                    VoteMode::Open,
+                    PayoutWeighting::PerVote,
+                )
                 .await
             {
                 Ok(signature) => {
@@ -610,6 +633,14 @@ pub async fn tweet_vote_resolver(
         *app_state.solana_service.get_bling_mint()
     };
 
+    // Slippage guard: votes are batched, so the curve can move between the quote and the flush
+    let max_cost_in_token = match input.max_cost_in_token {
+        Some(max_cost) => max_cost
+            .parse::<u64>()
+            .map_err(|e| async_graphql::Error::new(format!("Invalid max_cost_in_token: {}", e)))?,
+        None => u64::MAX,
+    };
+
     // Write vote to buffer (will be batched and sent by background flush task)
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -634,10 +665,12 @@ pub async fn tweet_vote_resolver(
         .and_modify(|v| {
             v.accumulated_votes += 1;
             v.last_click_ts = now;
+            v.max_cost_in_token = v.max_cost_in_token.saturating_add(max_cost_in_token);
         })
         .or_insert_with(|| VoteBufferValue {
             accumulated_votes: 1,
             last_click_ts: now,
+            max_cost_in_token,
         });
 
     // Vote is queued, return immediate success
@@ -781,6 +814,19 @@ pub async fn settle_post_resolver(
         if let Some(parent_ixs) = parent_ixs_opt {
             all_instructions.extend(parent_ixs);
         }
+
+        // Build distribute ancestor post share instructions (grandparent and up)
+        let ancestor_ixs = app_state
+            .solana_service
+            .build_distribute_ancestor_post_share_instructions(post_id_hash, token_mint)
+            .await
+            .map_err(|e| {
+                async_graphql::Error::new(format!(
+                    "Failed to build distribute ancestor post share instructions for token {}: {}",
+                    token_mint, e
+                ))
+            })?;
+        all_instructions.extend(ancestor_ixs);
     }
 
     if all_instructions.is_empty() {
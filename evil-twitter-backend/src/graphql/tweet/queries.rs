@@ -49,6 +49,18 @@ impl TweetQuery {
     ) -> Result<Vec<crate::graphql::tweet::types::ClaimableRewardNode>> {
         claimable_rewards_resolver(ctx).await
     }
+
+    /// Quote what the authenticated user would pay to vote on a tweet right now
+    async fn vote_quote(
+        &self,
+        ctx: &Context<'_>,
+        tweet_id: ID,
+        side: String,
+        #[graphql(default = 1)] votes: u64,
+        token_mint: Option<String>,
+    ) -> Result<crate::graphql::tweet::types::VoteQuoteNode> {
+        vote_quote_resolver(ctx, tweet_id, side, votes, token_mint).await
+    }
 }
 
 // ============================================================================
@@ -163,3 +175,91 @@ pub async fn claimable_rewards_resolver(
 
     Ok(vec![])
 }
+
+/// Quote a vote for the authenticated user by simulating the program's quote_vote
+pub async fn vote_quote_resolver(
+    ctx: &Context<'_>,
+    tweet_id: ID,
+    side: String,
+    votes: u64,
+    token_mint: Option<String>,
+) -> Result<crate::graphql::tweet::types::VoteQuoteNode> {
+    use crate::graphql::tweet::types::VoteQuoteNode;
+    use axum::http::HeaderMap;
+    use opinions_market::state::Side;
+
+    let app_state = ctx.data::<Arc<AppState>>()?;
+    let headers = ctx
+        .data::<HeaderMap>()
+        .map_err(|_| async_graphql::Error::new("Failed to get headers from context"))?;
+
+    let user = crate::utils::auth::get_authenticated_user(
+        &app_state.mongo_service,
+        &app_state.privy_service,
+        headers,
+    )
+    .await
+    .map_err(|(status, json)| {
+        let error_msg = json
+            .0
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Authentication failed");
+        async_graphql::Error::new(format!("{} (status {})", error_msg, status))
+    })?;
+
+    let user_wallet = Pubkey::from_str(&user.wallet)
+        .map_err(|e| async_graphql::Error::new(format!("Invalid user wallet: {}", e)))?;
+
+    let tweet = app_state
+        .mongo_service
+        .tweets
+        .get_tweet_by_id(parse_object_id(&tweet_id)?)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("Tweet not found"))?;
+
+    let post_id_hash_hex = tweet.post_id_hash.ok_or_else(|| {
+        async_graphql::Error::new("Tweet does not have a post_id_hash (not an original tweet)")
+    })?;
+    let post_id_hash: [u8; 32] = hex::decode(&post_id_hash_hex)
+        .map_err(|e| async_graphql::Error::new(format!("Invalid post_id_hash: {}", e)))?
+        .try_into()
+        .map_err(|_| async_graphql::Error::new("post_id_hash must be 32 bytes"))?;
+
+    let side = match side.to_lowercase().as_str() {
+        "pump" => Side::Pump,
+        "smack" => Side::Smack,
+        _ => return Err(async_graphql::Error::new("side must be 'pump' or 'smack'")),
+    };
+
+    // Same default as tweetVote: explicit mint, then the user's default, then BLING
+    let token_mint = if let Some(mint_str) = token_mint {
+        Pubkey::from_str(&mint_str)
+            .map_err(|e| async_graphql::Error::new(format!("Invalid token_mint: {}", e)))?
+    } else if let Some(ref default_mint_str) = user.default_payment_token {
+        Pubkey::from_str(default_mint_str).map_err(|e| {
+            async_graphql::Error::new(format!("Invalid default_payment_token: {}", e))
+        })?
+    } else {
+        *app_state.solana_service.get_bling_mint()
+    };
+
+    let quote = app_state
+        .solana_service
+        .quote_vote(&user_wallet, post_id_hash, side, &token_mint, votes)
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("Failed to quote vote: {}", e)))?;
+
+    Ok(VoteQuoteNode {
+        votes: quote.votes.to_string(),
+        cost_bling: quote.cost_bling.to_string(),
+        protocol_fee: quote.protocol_fee.to_string(),
+        creator_pump_fee: quote.creator_pump_fee.to_string(),
+        pot_increment: quote.pot_increment.to_string(),
+        token_mint: token_mint.to_string(),
+        protocol_fee_token: quote.protocol_fee_token.to_string(),
+        creator_pump_fee_token: quote.creator_pump_fee_token.to_string(),
+        pot_increment_token: quote.pot_increment_token.to_string(),
+        cost_in_token: quote.cost_in_token.to_string(),
+    })
+}
@@ -208,6 +208,7 @@ impl TweetNode {
                     let state_str = match post_account.state {
                         opinions_market::state::PostState::Open => "Open".to_string(),
                         opinions_market::state::PostState::Settled => "Settled".to_string(),
+                        opinions_market::state::PostState::Cancelled => "Cancelled".to_string(),
                     };
 
                     let winning_side = post_account.winning_side.map(|side| match side {
@@ -389,6 +390,7 @@ impl TweetNode {
                                 protocol_fee: payout.protocol_fee.to_string(),
                                 mother_fee: payout.mother_fee.to_string(),
                                 total_payout: payout.total_payout.to_string(),
+                                policy: format!("{:?}", payout.policy),
                             })
                         } else {
                             None
@@ -504,6 +506,8 @@ pub struct PostMintPayoutNode {
     pub protocol_fee: String,
     pub mother_fee: String,
     pub total_payout: String,
+    /// "Winners", "ParentRollover" or "TreasurySweep"
+    pub policy: String,
 }
 
 // ============================================================================
@@ -610,3 +614,22 @@ pub struct ClaimableRewardNode {
     pub amount: String,      // Amount as string (in token units)
     pub reward_type: String, // "creator" or "voter"
 }
+
+// ============================================================================
+// Vote Quote Node
+// ============================================================================
+
+/// What a vote would cost right now, as computed by the program's quote_vote
+#[derive(SimpleObject, Clone)]
+pub struct VoteQuoteNode {
+    pub votes: String,
+    pub cost_bling: String,
+    pub protocol_fee: String,
+    pub creator_pump_fee: String,
+    pub pot_increment: String,
+    pub token_mint: String,
+    pub protocol_fee_token: String,
+    pub creator_pump_fee_token: String,
+    pub pot_increment_token: String,
+    pub cost_in_token: String, // Total charged in token_mint; basis for maxCostInToken
+}
@@ -16,8 +16,10 @@ use crate::{app_state::AppState, graphql::user::types::UserNode, utils::auth};
 
 #[derive(InputObject)]
 pub struct RenewSessionInput {
-    // Backend generates session key (payer pubkey), so only signature is needed
+    // Backend generates session key (payer pubkey)
     pub session_signature: String,
+    // Base64 message from the sessionMessage query that the signature covers
+    pub session_message: String,
 }
 
 #[derive(SimpleObject)]
@@ -25,6 +27,19 @@ pub struct RenewSessionPayload {
     pub session: SessionInfo,
 }
 
+#[derive(InputObject)]
+pub struct RevokeSessionInput {
+    // Signature over the revokeSessionMessage query result for the backend session key
+    pub session_signature: String,
+    // Base64 message from the revokeSessionMessage query that the signature covers
+    pub session_message: String,
+}
+
+#[derive(SimpleObject)]
+pub struct RevokeSessionPayload {
+    pub signature: String,
+}
+
 #[derive(Default)]
 pub struct UserMutation;
 
@@ -52,6 +67,15 @@ impl UserMutation {
         renew_session_resolver(ctx, input).await
     }
 
+    /// Revoke the delegated session before it expires
+    async fn revoke_session(
+        &self,
+        ctx: &Context<'_>,
+        input: RevokeSessionInput,
+    ) -> Result<RevokeSessionPayload> {
+        revoke_session_resolver(ctx, input).await
+    }
+
     /// Update user profile (handle, displayName, bio, avatarUrl)
     async fn update_profile(
         &self,
@@ -80,6 +104,7 @@ impl UserMutation {
 pub struct OnboardUserInput {
     // Session registration - backend generates session key (payer pubkey)
     pub session_signature: String, // base58/base64/hex-encoded 64 bytes signature
+    pub session_message: String,   // base64 message from the sessionMessage query
     // Optional profile fields - can be set during onboarding
     pub handle: Option<String>,
     pub display_name: Option<String>,
@@ -126,6 +151,18 @@ pub struct UserCreatePayload {
 // Mutation Resolvers
 // ============================================================================
 
+/// Decode a base64 session message and read the expiry it commits to
+fn parse_session_message(message_str: &str) -> Result<(Vec<u8>, i64)> {
+    let message_bytes = general_purpose::STANDARD
+        .decode(message_str)
+        .map_err(|_| async_graphql::Error::new("Invalid session message: must be base64"))?;
+    let expires_at =
+        opinions_market::middleware::session::session_message_expires_at(&message_bytes)
+            .ok_or_else(|| async_graphql::Error::new("Invalid session message format"))?;
+
+    Ok((message_bytes, expires_at))
+}
+
 /// Parse signature from base58, base64, or hex format into bytes
 /// Note: This is format conversion only - verification happens on-chain
 fn parse_signature_bytes(signature_str: &str) -> Result<[u8; 64]> {
@@ -305,9 +342,9 @@ pub async fn onboard_user_resolver(
     // The session key is the backend's session key (for now same as payer, but will be different in production)
     let session_key = app_state.solana_service.session_key_pubkey();
 
-    // Construct the message that should have been signed: SESSION:{session_key}
-    let message = format!("SESSION:{}", session_key);
-    let message_bytes = message.as_bytes().to_vec();
+    // The message that was signed commits to the session key, expiry and privileges;
+    // the program rebuilds it and rejects any mismatch
+    let (message_bytes, expires_at) = parse_session_message(&input.session_message)?;
 
     let signature_bytes = parse_signature_bytes(&input.session_signature)?;
 
@@ -320,15 +357,10 @@ pub async fn onboard_user_resolver(
         .map_err(|e| async_graphql::Error::new(format!("Failed to onboard user: {}", e)))?;
     eprintln!("onboard_user: User onboarded successfully!");
 
-    // Derive session authority PDA and calculate expires_at
+    // Derive session authority PDA
     let program_id = app_state.solana_service.opinions_market_program().id();
     let (session_authority_pda, _) =
         get_session_authority_pda(&program_id, &wallet_pubkey, &session_key);
-    let expires_at = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64
-        + (60 * 60 * 24 * 30); // 30 days
 
     let session_info = SessionInfo {
         session_authority_pda: session_authority_pda.to_string(),
@@ -542,9 +574,8 @@ pub async fn renew_session_resolver(
     // Backend generates session key (payer pubkey)
     let session_key = app_state.solana_service.payer_pubkey();
 
-    // Construct the message that should have been signed: SESSION:{session_key}
-    let message = format!("SESSION:{}", session_key);
-    let message_bytes = message.as_bytes().to_vec();
+    // The message that was signed commits to the session key, expiry and privileges
+    let (message_bytes, expires_at) = parse_session_message(&input.session_message)?;
 
     // Parse signature (format conversion only - verification happens on-chain)
     let signature_bytes = parse_signature_bytes(&input.session_signature)?;
@@ -556,15 +587,10 @@ pub async fn renew_session_resolver(
         .await
         .map_err(|e| async_graphql::Error::new(format!("Failed to renew session: {}", e)))?;
 
-    // Derive session authority PDA and calculate expires_at
+    // Derive session authority PDA
     let program_id = app_state.solana_service.opinions_market_program().id();
     let (session_authority_pda, _) =
         get_session_authority_pda(&program_id, &wallet_pubkey, &session_key);
-    let expires_at = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64
-        + (60 * 60 * 24 * 30); // 30 days
 
     Ok(RenewSessionPayload {
         session: SessionInfo {
@@ -576,6 +602,59 @@ pub async fn renew_session_resolver(
     })
 }
 
+/// Revoke the backend session for a user (closes the on-chain SessionAuthority)
+pub async fn revoke_session_resolver(
+    ctx: &Context<'_>,
+    input: RevokeSessionInput,
+) -> Result<RevokeSessionPayload> {
+    let app_state = ctx.data::<Arc<AppState>>()?;
+    let headers = ctx
+        .data::<HeaderMap>()
+        .map_err(|_| async_graphql::Error::new("Failed to get headers from context"))?;
+
+    // Verify Privy token and get Privy ID
+    let privy_id = auth::get_privy_id_from_header(&app_state.privy_service, headers)
+        .await
+        .map_err(|(status, json)| {
+            let error_msg = json
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Authentication failed");
+            async_graphql::Error::new(format!("{} (status {})", error_msg, status))
+        })?;
+
+    // Get user from Mongo
+    let user = app_state
+        .mongo_service
+        .users
+        .get_user_by_privy_id(&privy_id)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("User not found in database"))?;
+
+    // Parse wallet pubkey
+    let wallet_pubkey = Pubkey::from_str(&user.wallet)
+        .map_err(|e| async_graphql::Error::new(format!("Invalid wallet pubkey: {}", e)))?;
+
+    // Same session key renew_session registers
+    let session_key = app_state.solana_service.payer_pubkey();
+
+    // The message that was signed commits to the session's expiry and privileges
+    let (message_bytes, _) = parse_session_message(&input.session_message)?;
+
+    // Parse signature (format conversion only - verification happens on-chain)
+    let signature_bytes = parse_signature_bytes(&input.session_signature)?;
+
+    let signature = app_state
+        .solana_service
+        .revoke_session(wallet_pubkey, session_key, signature_bytes, message_bytes)
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("Failed to revoke session: {}", e)))?;
+
+    Ok(RevokeSessionPayload {
+        signature: signature.to_string(),
+    })
+}
+
 /// Update user profile (handle, displayName, bio, avatarUrl)
 pub async fn update_profile_resolver(
     ctx: &Context<'_>,
@@ -67,14 +67,17 @@ impl UserQuery {
     }
 
     /// Get the complete message bytes ready to sign for session registration
-    /// Returns base64-encoded message bytes: SESSION:{payer_pubkey}
+    /// Returns base64-encoded message bytes:
+    /// SESSION:{program_id}:{session_key}:{expires_at}:{privileges_hash}:{nonce}
     async fn session_message(&self, ctx: &Context<'_>) -> Result<String> {
-        let app_state = ctx.data::<Arc<AppState>>()?;
-        let session_key = app_state.solana_service.session_key_pubkey();
-        let message = format!("SESSION:{}", session_key);
-        // Return base64-encoded message bytes
-        let message_bytes = message.as_bytes();
-        Ok(STANDARD.encode(message_bytes))
+        session_message_resolver(ctx).await
+    }
+
+    /// Get the message bytes to sign for revoking the backend session
+    /// Returns base64-encoded message bytes:
+    /// REVOKE:{program_id}:{session_key}:{expires_at}:{privileges_hash}:{nonce}
+    async fn revoke_session_message(&self, ctx: &Context<'_>) -> Result<String> {
+        revoke_session_message_resolver(ctx).await
     }
 
     /// Get canonical vote cost for the authenticated user
@@ -229,7 +232,76 @@ pub async fn discover_users_resolver(
     Ok(results)
 }
 
+pub async fn session_message_resolver(ctx: &Context<'_>) -> Result<String> {
+    let app_state = ctx.data::<Arc<AppState>>()?;
+
+    // Known users sign over their on-chain session nonce; users who have not
+    // onboarded yet have no user account, so their message commits to nonce 0
+    let mut user_wallet = None;
+    if let Some(headers) = ctx.data_opt::<HeaderMap>() {
+        if let Ok(privy_id) =
+            auth::get_privy_id_from_header(&app_state.privy_service, headers).await
+        {
+            user_wallet = app_state
+                .mongo_service
+                .users
+                .get_user_by_privy_id(&privy_id)
+                .await?
+                .and_then(|user| solana_sdk::pubkey::Pubkey::from_str(&user.wallet).ok());
+        }
+    }
+
+    let session_key = app_state.solana_service.session_key_pubkey();
+    let message_bytes = app_state
+        .solana_service
+        .session_registration_message(user_wallet.as_ref(), &session_key)
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("Failed to build message: {}", e)))?;
+
+    // Return base64-encoded message bytes
+    Ok(STANDARD.encode(message_bytes))
+}
+
 /// Get canonical vote cost for the authenticated user
+pub async fn revoke_session_message_resolver(ctx: &Context<'_>) -> Result<String> {
+    let app_state = ctx.data::<Arc<AppState>>()?;
+    let headers = ctx
+        .data::<HeaderMap>()
+        .map_err(|_| async_graphql::Error::new("Failed to get headers from context"))?;
+
+    // Get authenticated user
+    let privy_id = auth::get_privy_id_from_header(&app_state.privy_service, headers)
+        .await
+        .map_err(|(status, json)| {
+            let error_msg = json
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Authentication failed");
+            async_graphql::Error::new(format!("{} (status {})", error_msg, status))
+        })?;
+
+    let user = app_state
+        .mongo_service
+        .users
+        .get_user_by_privy_id(&privy_id)
+        .await?
+        .ok_or_else(|| async_graphql::Error::new("User not found"))?;
+
+    // Parse wallet pubkey
+    let wallet_pubkey = solana_sdk::pubkey::Pubkey::from_str(&user.wallet)
+        .map_err(|e| async_graphql::Error::new(format!("Invalid wallet pubkey: {}", e)))?;
+
+    // Same session key renew_session registers
+    let session_key = app_state.solana_service.payer_pubkey();
+    let message_bytes = app_state
+        .solana_service
+        .session_revoke_message(&wallet_pubkey, &session_key)
+        .await
+        .map_err(|e| async_graphql::Error::new(format!("Failed to build message: {}", e)))?;
+
+    Ok(STANDARD.encode(message_bytes))
+}
+
 pub async fn canonical_vote_cost_resolver(ctx: &Context<'_>, side: String) -> Result<u64> {
     let app_state = ctx.data::<Arc<AppState>>()?;
     let headers = ctx
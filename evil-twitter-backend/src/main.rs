@@ -75,7 +75,7 @@ async fn vote_flush_loop(app_state: Arc<AppState>) {
         sleep(Duration::from_millis(interval_ms)).await;
 
         // Collect entries to flush (to avoid holding lock during async operations)
-        let entries_to_flush: Vec<(VoteBufferKey, u64)> = {
+        let entries_to_flush: Vec<(VoteBufferKey, u64, u64)> = {
             let mut buffer = match app_state.vote_buffer.lock() {
                 Ok(b) => b,
                 Err(e) => {
@@ -99,7 +99,7 @@ async fn vote_flush_loop(app_state: Arc<AppState>) {
 
             for key in keys {
                 if let Some(value) = buffer.remove(&key) {
-                    entries.push((key, value.accumulated_votes));
+                    entries.push((key, value.accumulated_votes, value.max_cost_in_token));
                 }
             }
 
@@ -107,7 +107,7 @@ async fn vote_flush_loop(app_state: Arc<AppState>) {
         };
 
         // Flush each entry
-        for (key, votes) in entries_to_flush {
+        for (key, votes, max_cost_in_token) in entries_to_flush {
             let result = app_state
                 .solana_service
                 .vote_on_post(
@@ -116,6 +116,7 @@ async fn vote_flush_loop(app_state: Arc<AppState>) {
                     key.side,
                     &key.token_mint,
                     votes,
+                    max_cost_in_token,
                 )
                 .await;
 
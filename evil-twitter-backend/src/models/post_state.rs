@@ -17,7 +17,7 @@ pub struct PostState {
     #[schema(example = "7xKXtg2CZ3QZ4Z3J3J3J3J3J3J3J3J3J3J3J3J3")]
     pub post_pda: String,
 
-    /// Post state: "Open" or "Settled"
+    /// Post state: "Open", "Settled" or "Cancelled"
     #[schema(example = "Open")]
     pub state: String,
 
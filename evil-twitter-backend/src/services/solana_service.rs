@@ -8,7 +8,7 @@ use anchor_spl::{
     associated_token::spl_associated_token_account::{self},
     token::spl_token,
 };
-use opinions_market::state::Side;
+use opinions_market::state::{CurveKind, PayoutWeighting, Side, VoteMode};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     ed25519_instruction,
@@ -48,6 +48,9 @@ use crate::solana::{
     get_user_vault_token_account_pda, get_valid_payment_pda, get_vault_authority_pda,
 };
 
+/// BLING a backend-held session may spend on votes before the user has to re-register it
+const SESSION_MAX_BLING_SPEND: u64 = 10_000 * LAMPORTS_PER_SOL;
+
 pub struct SolanaService {
     rpc: Arc<RpcClient>,
     session_key: Keypair,
@@ -105,6 +108,93 @@ impl SolanaService {
         self.session_key.pubkey()
     }
 
+    /// Privileges granted to the backend session key at registration:
+    /// posting, voting and claiming with any accepted mint, up to SESSION_MAX_BLING_SPEND
+    pub fn session_privileges(&self) -> opinions_market::state::SessionPrivileges {
+        use opinions_market::constants::{
+            MAX_SESSION_ALLOWED_MINTS, SESSION_PRIVILEGE_CLAIM_POST_REWARD,
+            SESSION_PRIVILEGE_CREATE_POST, SESSION_PRIVILEGE_VOTE_ON_POST,
+        };
+
+        opinions_market::state::SessionPrivileges {
+            allowed_instructions: SESSION_PRIVILEGE_CREATE_POST
+                | SESSION_PRIVILEGE_VOTE_ON_POST
+                | SESSION_PRIVILEGE_CLAIM_POST_REWARD,
+            max_bling_spend: SESSION_MAX_BLING_SPEND,
+            allowed_mints: [Pubkey::default(); MAX_SESSION_ALLOWED_MINTS],
+        }
+    }
+
+    /// The user's current session nonce, which every signed session message commits to.
+    /// Zero until the user account exists (onboarding creates it in the same transaction).
+    pub async fn get_session_nonce(&self, user_wallet: &Pubkey) -> anyhow::Result<u64> {
+        Ok(self
+            .get_user_account(user_wallet)
+            .await?
+            .map_or(0, |user_account| user_account.session_nonce))
+    }
+
+    /// Message the user signs to register `session_key` with the backend's session privileges.
+    /// Expiry is the longest the program allows, less a minute of slack for chain clock drift.
+    /// Without a wallet (user not onboarded yet) the message commits to nonce 0.
+    pub async fn session_registration_message(
+        &self,
+        user_wallet: Option<&Pubkey>,
+        session_key: &Pubkey,
+    ) -> anyhow::Result<Vec<u8>> {
+        use opinions_market::constants::MAX_SESSION_DURATION_SECS;
+        use opinions_market::middleware::session::{SESSION_ACTION_REGISTER, session_message};
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let expires_at = now + MAX_SESSION_DURATION_SECS - 60;
+        let nonce = match user_wallet {
+            Some(user_wallet) => self.get_session_nonce(user_wallet).await?,
+            None => 0,
+        };
+
+        Ok(session_message(
+            SESSION_ACTION_REGISTER,
+            &self.program_id,
+            session_key,
+            expires_at,
+            &self.session_privileges().hash(),
+            nonce,
+        )
+        .into_bytes())
+    }
+
+    /// Message the user signs to revoke their session for `session_key`.
+    /// Commits to the expiry and privileges stored on the SessionAuthority, and the user's nonce.
+    pub async fn session_revoke_message(
+        &self,
+        user_wallet: &Pubkey,
+        session_key: &Pubkey,
+    ) -> anyhow::Result<Vec<u8>> {
+        use opinions_market::middleware::session::{SESSION_ACTION_REVOKE, session_message};
+
+        let program = self.opinions_market_program();
+        let (session_authority_pda, _) =
+            get_session_authority_pda(&self.program_id, user_wallet, session_key);
+        let session_authority = program
+            .account::<opinions_market::state::SessionAuthority>(session_authority_pda)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch session authority: {}", e))?;
+        let nonce = self.get_session_nonce(user_wallet).await?;
+
+        Ok(session_message(
+            SESSION_ACTION_REVOKE,
+            &self.program_id,
+            session_key,
+            session_authority.expires_at,
+            &session_authority.privileges_hash,
+            nonce,
+        )
+        .into_bytes())
+    }
+
     /// Send a fully-signed transaction (signed by backend and/or session)
     pub async fn send_signed_tx(&self, tx: &VersionedTransaction) -> anyhow::Result<Signature> {
         let result = self.rpc.send_and_confirm_transaction(tx).await;
@@ -161,6 +251,28 @@ impl SolanaService {
         Ok(tx)
     }
 
+    /// Builds a transaction signed by the payer and the backend's session key.
+    /// Session-delegated instructions only act for the user when the session key signs.
+    pub async fn build_session_signed_tx(
+        &self,
+        mut ixs: Vec<Instruction>,
+    ) -> anyhow::Result<VersionedTransaction> {
+        let session_key = self.session_key.pubkey();
+        for meta in ixs.iter_mut().flat_map(|ix| ix.accounts.iter_mut()) {
+            if meta.pubkey == session_key {
+                meta.is_signer = true;
+            }
+        }
+
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let message = Message::try_compile(&self.payer.pubkey(), &ixs, &[], blockhash)?;
+        let v0_message = VersionedMessage::V0(message);
+        let signers: [&Keypair; 2] = [self.payer.as_ref(), &self.session_key];
+        let tx = VersionedTransaction::try_new(v0_message, &signers)?;
+
+        Ok(tx)
+    }
+
     pub async fn send_tx<T: Signers + ?Sized>(
         &self,
         ixs: Vec<Instruction>,
@@ -428,11 +540,15 @@ impl SolanaService {
                 payer: self.payer.pubkey(),
                 user: user_wallet,
                 session_key,
+                user_account: user_account_pda,
                 session_authority: session_authority_pda,
                 instructions_sysvar: INSTRUCTIONS_SYSVAR_ID,
                 system_program: solana_sdk::system_program::ID,
             })
-            .args(opinions_market::instruction::RegisterSession { expected_index: 1 })
+            .args(opinions_market::instruction::RegisterSession {
+                expected_index: 1,
+                privileges: self.session_privileges(),
+            })
             .instructions()
             .map_err(|e| {
                 eprintln!(
@@ -604,7 +720,8 @@ impl SolanaService {
             program_id
         );
 
-        // Derive session authority PDA
+        // Derive PDAs
+        let (user_account_pda, _) = get_user_account_pda(&program_id, &user_wallet);
         let (session_authority_pda, _) =
             get_session_authority_pda(&program_id, &user_wallet, &session_key);
         println!(
@@ -629,11 +746,15 @@ impl SolanaService {
                 payer: self.payer.pubkey(),
                 user: user_wallet,
                 session_key,
+                user_account: user_account_pda,
                 session_authority: session_authority_pda,
                 instructions_sysvar: INSTRUCTIONS_SYSVAR_ID,
                 system_program: solana_sdk::system_program::ID,
             })
-            .args(opinions_market::instruction::RegisterSession { expected_index: 0 })
+            .args(opinions_market::instruction::RegisterSession {
+                expected_index: 0,
+                privileges: self.session_privileges(),
+            })
             .instructions()
             .map_err(|e| {
                 eprintln!(
@@ -682,12 +803,120 @@ impl SolanaService {
         Ok(signature)
     }
 
+    /// Revoke a user's session before it expires, closing the SessionAuthority PDA.
+    ///
+    /// Mirrors renew_session: the user signs a message off-chain, the backend relays it as an
+    /// ed25519 instruction (index 0) followed by revoke_session. Rent goes back to the account's
+    /// original payer.
+    pub async fn revoke_session(
+        &self,
+        user_wallet: Pubkey,
+        session_key: Pubkey,
+        signature_bytes: [u8; 64],
+        message_bytes: Vec<u8>,
+    ) -> anyhow::Result<Signature> {
+        println!(
+            "  🔧 SolanaService::revoke_session: Starting for user {}, session_key {}",
+            user_wallet, session_key
+        );
+
+        let program = self.opinions_market_program();
+        let program_id = program.id();
+
+        // Derive PDAs
+        let (user_account_pda, _) = get_user_account_pda(&program_id, &user_wallet);
+        let (session_authority_pda, _) =
+            get_session_authority_pda(&program_id, &user_wallet, &session_key);
+        println!(
+            "  📍 SolanaService::revoke_session: Session Authority PDA: {}",
+            session_authority_pda
+        );
+
+        // Fetch session authority to find who gets the rent back
+        let session_authority = program
+            .account::<opinions_market::state::SessionAuthority>(session_authority_pda)
+            .await
+            .map_err(|e| {
+                eprintln!(
+                    "  ❌ SolanaService::revoke_session: Failed to fetch session authority: {}",
+                    e
+                );
+                anyhow::anyhow!("Failed to fetch session authority: {}", e)
+            })?;
+
+        // Create ed25519 instruction (must be first in transaction, index 0)
+        println!("  🔨 SolanaService::revoke_session: Building ed25519 instruction...");
+        let ed25519_ix = ed25519_instruction::new_ed25519_instruction_with_signature(
+            &message_bytes,
+            &signature_bytes,
+            &user_wallet.to_bytes(),
+        );
+
+        // Create revoke_session instruction
+        println!("  🔨 SolanaService::revoke_session: Building RevokeSession instruction...");
+        let revoke_session_ix = program
+            .request()
+            .accounts(opinions_market::accounts::RevokeSession {
+                payer: self.payer.pubkey(),
+                user: user_wallet,
+                session_key,
+                user_account: user_account_pda,
+                session_authority: session_authority_pda,
+                rent_payer: session_authority.rent_payer,
+                instructions_sysvar: INSTRUCTIONS_SYSVAR_ID,
+            })
+            .args(opinions_market::instruction::RevokeSession {
+                expected_index: Some(0),
+            })
+            .instructions()
+            .map_err(|e| {
+                eprintln!(
+                    "  ❌ SolanaService::revoke_session: Failed to build instruction: {}",
+                    e
+                );
+                anyhow::anyhow!("Failed to build RevokeSession instruction: {}", e)
+            })?;
+
+        // Build transaction with ed25519 first, then revoke_session
+        let mut instructions = vec![ed25519_ix];
+        instructions.extend(revoke_session_ix);
+
+        let tx = self
+            .build_partial_signed_tx(instructions)
+            .await
+            .map_err(|e| {
+                eprintln!(
+                    "  ❌ SolanaService::revoke_session: Failed to build transaction: {}",
+                    e
+                );
+                e
+            })?;
+
+        println!("  📡 SolanaService::revoke_session: Sending transaction to network...");
+        let signature = self.send_signed_tx(&tx).await.map_err(|e| {
+            eprintln!(
+                "  ❌ SolanaService::revoke_session: Failed to send transaction: {}",
+                e
+            );
+            e
+        })?;
+
+        println!(
+            "  ✅ SolanaService::revoke_session: Transaction confirmed! Signature: {}",
+            signature
+        );
+        Ok(signature)
+    }
+
     /// Create a post account on-chain, signed by backend payer only
     pub async fn create_post(
         &self,
         user_wallet: Pubkey,
         post_id_hash: [u8; 32],
         parent_post_pda: Option<Pubkey>,
+        curve: CurveKind,
+        vote_mode: VoteMode,
+        payout_weighting: PayoutWeighting,
     ) -> anyhow::Result<Signature> {
         // Log post type prominently
         if let Some(parent_pda) = parent_post_pda {
@@ -780,6 +1009,8 @@ impl SolanaService {
             ));
         }
 
+        let (post_pot_authority_pda, _) = get_post_pot_authority_pda(&program_id, &post_pda);
+
         // Build CreatePost instruction
         println!("  🔨 SolanaService::create_post: Building CreatePost instruction...");
 
@@ -793,11 +1024,20 @@ impl SolanaService {
                 session_authority: session_authority_pda,
                 user_account: user_account_pda,
                 post: post_pda,
+                parent_post: parent_post_pda,
+                post_pot_authority: post_pot_authority_pda,
+                pump_share_mint: None,
+                smack_share_mint: None,
+                token_program: spl_token::ID,
                 system_program: solana_sdk::system_program::ID,
             })
             .args(opinions_market::instruction::CreatePost {
                 post_id_hash,
-                parent_post_pda,
+                curve,
+                vote_mode,
+                // Share claims need the holder's signature, which the payer-signed flow can't give
+                tokenized: false,
+                payout_weighting,
             })
             .instructions()
             .map_err(|e| {
@@ -810,11 +1050,11 @@ impl SolanaService {
 
         println!("  ✅ SolanaService::create_post: Instruction built successfully");
 
-        // Build transaction signed by payer only
+        // Build transaction signed by payer and session key
         println!(
-            "  ✍️  SolanaService::create_post: Building and signing transaction with payer..."
+            "  ✍️  SolanaService::create_post: Building and signing transaction with payer and session key..."
         );
-        let tx = self.build_partial_signed_tx(ixs).await.map_err(|e| {
+        let tx = self.build_session_signed_tx(ixs).await.map_err(|e| {
             eprintln!(
                 "  ❌ SolanaService::create_post: Failed to build transaction: {}",
                 e
@@ -855,6 +1095,8 @@ impl SolanaService {
         Ok(signature)
     }
 
+    /// Vote on a post. The program rejects the vote with SlippageExceeded if the total it
+    /// would charge in `token_mint` is above `max_cost_in_token` (u64::MAX = no limit).
     pub async fn vote_on_post(
         &self,
         voter_wallet: &Pubkey,
@@ -862,14 +1104,16 @@ impl SolanaService {
         side: Side,
         token_mint: &Pubkey,
         votes: u64,
+        max_cost_in_token: u64,
     ) -> anyhow::Result<Signature> {
         println!(
-            "  🔧 SolanaService::vote_on_post: Starting for user {}, post_id_hash: {}, side: {:?}, votes: {}, token_mint: {}",
+            "  🔧 SolanaService::vote_on_post: Starting for user {}, post_id_hash: {}, side: {:?}, votes: {}, token_mint: {}, max_cost_in_token: {}",
             voter_wallet,
             hex::encode(post_id_hash),
             side,
             votes,
-            token_mint
+            token_mint,
+            max_cost_in_token
         );
 
         let program = self.opinions_market_program();
@@ -910,6 +1154,19 @@ impl SolanaService {
         let (creator_vault_token_account_pda, _) =
             get_user_vault_token_account_pda(&program_id, &creator_user, token_mint);
 
+        // Mints priced by a feed need the feed account passed alongside ValidPayment
+        let price_feed = program
+            .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+            .await
+            .map_err(|e| {
+                eprintln!(
+                    "  ❌ SolanaService::vote_on_post: Failed to fetch valid payment account: {}",
+                    e
+                );
+                anyhow::anyhow!("Failed to fetch valid payment account: {}", e)
+            })?
+            .price_feed;
+
         println!(
             "  📍 SolanaService::vote_on_post: Config PDA: {}",
             config_pda
@@ -985,6 +1242,9 @@ impl SolanaService {
                 protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
                 creator_vault_token_account: creator_vault_token_account_pda,
                 valid_payment: valid_payment_pda,
+                price_feed,
+                share_mint: None,
+                voter_share_token_account: None,
                 token_mint: *token_mint,
                 token_program: spl_token::ID,
                 system_program: solana_sdk::system_program::ID,
@@ -993,6 +1253,7 @@ impl SolanaService {
                 post_id_hash,
                 side,
                 votes,
+                max_cost_in_token,
             })
             .instructions()
             .map_err(|e| {
@@ -1005,11 +1266,11 @@ impl SolanaService {
 
         println!("  ✅ SolanaService::vote_on_post: Instruction built successfully");
 
-        // Build transaction signed by payer only
+        // Build transaction signed by payer and session key
         println!(
-            "  ✍️  SolanaService::vote_on_post: Building and signing transaction with payer..."
+            "  ✍️  SolanaService::vote_on_post: Building and signing transaction with payer and session key..."
         );
-        let tx = self.build_partial_signed_tx(ixs).await.map_err(|e| {
+        let tx = self.build_session_signed_tx(ixs).await.map_err(|e| {
             eprintln!(
                 "  ❌ SolanaService::vote_on_post: Failed to build transaction: {}",
                 e
@@ -1064,10 +1325,17 @@ impl SolanaService {
                 )
             })?;
 
+        // Fetch Config for the on-chain base cost per vote
+        let (config_pda, _) = get_config_pda(&program_id);
+        let config: opinions_market::state::Config = program
+            .account(config_pda)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch Config PDA {}: {}", config_pda, e))?;
+
         // Compute canonical cost directly on UserAccount
         // This is a pure user attribute - no Vote struct needed
         let cost = user_account
-            .canonical_cost(side)
+            .canonical_cost(side, &config)
             .map_err(|e| anyhow::anyhow!("Canonical cost compute error: {:?}", e))?;
 
         println!(
@@ -1078,6 +1346,85 @@ impl SolanaService {
         Ok(cost)
     }
 
+    /// Quote a vote with the program's own math by simulating quote_vote.
+    /// The breakdown comes back as the instruction's return data, nothing is sent on-chain.
+    pub async fn quote_vote(
+        &self,
+        voter_wallet: &Pubkey,
+        post_id_hash: [u8; 32],
+        side: Side,
+        token_mint: &Pubkey,
+        votes: u64,
+    ) -> anyhow::Result<opinions_market::state::VoteQuote> {
+        println!(
+            "  🔧 SolanaService::quote_vote: Starting for user {}, post_id_hash: {}, side: {:?}, votes: {}, token_mint: {}",
+            voter_wallet,
+            hex::encode(post_id_hash),
+            side,
+            votes,
+            token_mint
+        );
+
+        let program = self.opinions_market_program();
+        let program_id = program.id();
+
+        let (config_pda, _) = get_config_pda(&program_id);
+        let (voter_user_account_pda, _) = get_user_account_pda(&program_id, voter_wallet);
+        let (post_pda, _) = get_post_pda(&program_id, &post_id_hash);
+        let (position_pda, _) = get_position_pda(&program_id, &post_pda, voter_wallet);
+        let (valid_payment_pda, _) = get_valid_payment_pda(&program_id, token_mint);
+
+        // No position before the voter's first vote on this post
+        let position = self
+            .get_user_position(voter_wallet, &post_id_hash)
+            .await?
+            .map(|_| position_pda);
+
+        // Mints priced by a feed need the feed account passed alongside ValidPayment
+        let price_feed = program
+            .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch valid payment account: {}", e))?
+            .price_feed;
+
+        let ixs = program
+            .request()
+            .accounts(opinions_market::accounts::QuoteVote {
+                config: config_pda,
+                voter: *voter_wallet,
+                post: post_pda,
+                voter_user_account: voter_user_account_pda,
+                position,
+                valid_payment: valid_payment_pda,
+                price_feed,
+                token_mint: *token_mint,
+            })
+            .args(opinions_market::instruction::QuoteVote {
+                side,
+                votes,
+                _post_id_hash: post_id_hash,
+            })
+            .instructions()
+            .map_err(|e| anyhow::anyhow!("Failed to build QuoteVote instruction: {}", e))?;
+
+        let tx = self.build_partial_signed_tx(ixs).await?;
+        let simulation = self.rpc.simulate_transaction(&tx).await?.value;
+        if let Some(err) = simulation.err {
+            return Err(anyhow::anyhow!("quote_vote simulation failed: {:?}", err));
+        }
+
+        let return_data = simulation
+            .return_data
+            .ok_or_else(|| anyhow::anyhow!("quote_vote returned no data"))?;
+        let bytes = general_purpose::STANDARD.decode(return_data.data.0)?;
+        let quote = opinions_market::state::VoteQuote::try_from_slice(&bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to decode VoteQuote: {}", e))?;
+
+        println!("  ✅ SolanaService::quote_vote: {:?}", quote);
+
+        Ok(quote)
+    }
+
     /// Convert a price in BLING lamports to another token using ValidPayment account
     /// Returns the equivalent amount in the target token's lamports
     ///
@@ -1140,7 +1487,20 @@ impl SolanaService {
         // 3. Convert to token lamports: token_lamports = token_base * 10^target_decimals
         // Combined: token_lamports = (bling_lamports * 10^target_decimals) / (price_in_bling * 10^bling_decimals)
 
-        let price_in_bling = valid_payment.price_in_bling;
+        // With a price feed attached, the program prices votes off the feed, so mirror that here
+        let price_in_bling = match valid_payment.price_feed {
+            Some(price_feed) => {
+                let feed_account = self.rpc.get_account(&price_feed).await.map_err(|e| {
+                    anyhow::anyhow!("Failed to fetch price feed {}: {}", price_feed, e)
+                })?;
+                opinions_market::oracle::FeedPrice::read(&feed_account.owner, &feed_account.data)
+                    .and_then(|price| price.price_in_bling(valid_payment.bling_per_feed_unit))
+                    .map_err(|e| {
+                        anyhow::anyhow!("Failed to read price feed {}: {}", price_feed, e)
+                    })?
+            }
+            None => valid_payment.price_in_bling,
+        };
 
         // Use checked arithmetic to avoid overflow
         let numerator = bling_lamports
@@ -1211,36 +1571,21 @@ impl SolanaService {
             .account::<opinions_market::state::PostAccount>(post_pda)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to fetch post account: {}", e))?;
+        let (creator_user_account_pda, _) =
+            get_user_account_pda(&program_id, &post_account.creator_user);
 
-        // Handle parent post if this is a child post
-        // NOTE: The Solana program has a bug in SettlePost instruction where parent_post_pot_token_account
-        // and parent_post_pot_authority are derived using post.key() (current post) instead of parent_post.key().
-        // This means we must always pass accounts derived from the CURRENT post, not the parent post,
-        // to satisfy the seed constraint checks.
-        let (parent_post_pda, parent_post_pot_token_account_pda, parent_post_pot_authority_pda) =
-            match post_account.post_type {
-                opinions_market::state::PostType::Child { parent } => {
-                    // BUG WORKAROUND: Derive from current post (post_pda) not parent
-                    // The program's seeds use post.key() so we must match that
-                    let parent_pot_token_account =
-                        get_post_pot_token_account_pda(&program_id, &post_pda, token_mint);
-                    let parent_pot_authority = get_post_pot_authority_pda(&program_id, &post_pda);
-                    (
-                        Some(parent),                     // Pass actual parent PDA for program logic
-                        Some(parent_pot_token_account.0), // But derive token account from current post
-                        parent_pot_authority.0,           // And derive authority from current post
-                    )
-                }
-                opinions_market::state::PostType::Original => {
-                    // For original posts, pass current post's accounts to satisfy constraint
-                    // The program won't use them since it checks post_type == Original first
-                    (
-                        None,
-                        Some(post_pot_token_account_pda),
-                        post_pot_authority_pda,
-                    )
-                }
-            };
+        // Child posts must pass their recorded parent, settle reads whether it is still open
+        let parent_post_pda = match post_account.post_type {
+            opinions_market::state::PostType::Child { parent } => Some(parent),
+            opinions_market::state::PostType::Original => None,
+        };
+
+        // Ancestors above the parent, grandparent first, for the mother fees further up
+        let ancestor_accounts: Vec<AccountMeta> = post_account.ancestors[1..]
+            .iter()
+            .filter(|ancestor| **ancestor != Pubkey::default())
+            .map(|ancestor| AccountMeta::new_readonly(*ancestor, false))
+            .collect();
 
         println!("  🔨 SolanaService::settle_post_for_mint: Building SettlePost instruction...");
 
@@ -1249,18 +1594,18 @@ impl SolanaService {
             .accounts(opinions_market::accounts::SettlePost {
                 payer: self.payer.pubkey(),
                 post: post_pda,
+                creator_user_account: creator_user_account_pda,
                 post_pot_token_account: post_pot_token_account_pda,
                 post_pot_authority: post_pot_authority_pda,
                 post_mint_payout: post_mint_payout_pda,
                 protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
                 parent_post: parent_post_pda,
-                parent_post_pot_token_account: parent_post_pot_token_account_pda,
-                parent_post_pot_authority: parent_post_pot_authority_pda,
                 config: config_pda,
                 token_mint: *token_mint,
                 token_program: spl_token::ID,
                 system_program: solana_sdk::system_program::ID,
             })
+            .accounts(ancestor_accounts)
             .args(opinions_market::instruction::SettlePost { post_id_hash })
             .instructions()
             .map_err(|e| {
@@ -1330,9 +1675,12 @@ impl SolanaService {
             get_user_vault_token_account_pda(&program_id, &post_account.creator_user, token_mint);
         let (vault_authority_pda, _) = get_vault_authority_pda(&program_id);
 
+        let (config_pda, _) = get_config_pda(&program_id);
+
         let ixs = program
             .request()
             .accounts(opinions_market::accounts::DistributeCreatorReward {
+                config: config_pda,
                 payer: self.payer.pubkey(),
                 post: post_pda,
                 post_pot_token_account: post_pot_token_account_pda,
@@ -1443,28 +1791,25 @@ impl SolanaService {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to fetch post account: {}", e))?;
 
-        let (parent_post_pda, parent_post_pot_token_account_pda, parent_post_pot_authority_pda) =
-            match post_account.post_type {
-                opinions_market::state::PostType::Child { parent } => {
-                    let parent_pot_token_account =
-                        get_post_pot_token_account_pda(&program_id, &parent, token_mint);
-                    let parent_pot_authority = get_post_pot_authority_pda(&program_id, &parent);
-                    (
-                        Some(parent),
-                        Some(parent_pot_token_account.0),
-                        Some(parent_pot_authority.0),
-                    )
-                }
-                opinions_market::state::PostType::Original => {
-                    return Err(anyhow::anyhow!(
-                        "Post is not a child post, cannot distribute to parent"
-                    ));
-                }
-            };
+        let (parent_post_pda, parent_post_pot_token_account_pda) = match post_account.post_type {
+            opinions_market::state::PostType::Child { parent } => {
+                let parent_pot_token_account =
+                    get_post_pot_token_account_pda(&program_id, &parent, token_mint);
+                (parent, parent_pot_token_account.0)
+            }
+            opinions_market::state::PostType::Original => {
+                return Err(anyhow::anyhow!(
+                    "Post is not a child post, cannot distribute to parent"
+                ));
+            }
+        };
+
+        let (config_pda, _) = get_config_pda(&program_id);
 
         let ixs = program
             .request()
             .accounts(opinions_market::accounts::DistributeParentPostShare {
+                config: config_pda,
                 payer: self.payer.pubkey(),
                 post: post_pda,
                 post_pot_token_account: post_pot_token_account_pda,
@@ -1472,7 +1817,6 @@ impl SolanaService {
                 post_mint_payout: post_mint_payout_pda,
                 parent_post: parent_post_pda,
                 parent_post_pot_token_account: parent_post_pot_token_account_pda,
-                parent_post_pot_authority: parent_post_pot_authority_pda,
                 token_mint: *token_mint,
                 token_program: spl_token::ID,
             })
@@ -1520,54 +1864,39 @@ impl SolanaService {
             .account::<opinions_market::state::PostAccount>(post_pda)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to fetch post account: {}", e))?;
+        let (creator_user_account_pda, _) =
+            get_user_account_pda(&program_id, &post_account.creator_user);
 
-        // Handle parent post if this is a child post
-        // NOTE: The Solana program has a bug in SettlePost instruction where parent_post_pot_token_account
-        // and parent_post_pot_authority are derived using post.key() (current post) instead of parent_post.key().
-        // This means we must always pass accounts derived from the CURRENT post, not the parent post,
-        // to satisfy the seed constraint checks.
-        let (parent_post_pda, parent_post_pot_token_account_pda, parent_post_pot_authority_pda) =
-            match post_account.post_type {
-                opinions_market::state::PostType::Child { parent } => {
-                    // BUG WORKAROUND: Derive from current post (post_pda) not parent
-                    // The program's seeds use post.key() so we must match that
-                    let parent_pot_token_account =
-                        get_post_pot_token_account_pda(&program_id, &post_pda, token_mint);
-                    let parent_pot_authority = get_post_pot_authority_pda(&program_id, &post_pda);
-                    (
-                        Some(parent),                     // Pass actual parent PDA for program logic
-                        Some(parent_pot_token_account.0), // But derive token account from current post
-                        parent_pot_authority.0,           // And derive authority from current post
-                    )
-                }
-                opinions_market::state::PostType::Original => {
-                    // For original posts, pass current post's accounts to satisfy constraint
-                    // The program won't use them since it checks post_type == Original first
-                    (
-                        None,
-                        Some(post_pot_token_account_pda),
-                        post_pot_authority_pda,
-                    )
-                }
-            };
+        // Child posts must pass their recorded parent, settle reads whether it is still open
+        let parent_post_pda = match post_account.post_type {
+            opinions_market::state::PostType::Child { parent } => Some(parent),
+            opinions_market::state::PostType::Original => None,
+        };
+
+        // Ancestors above the parent, grandparent first, for the mother fees further up
+        let ancestor_accounts: Vec<AccountMeta> = post_account.ancestors[1..]
+            .iter()
+            .filter(|ancestor| **ancestor != Pubkey::default())
+            .map(|ancestor| AccountMeta::new_readonly(*ancestor, false))
+            .collect();
 
         let ixs = program
             .request()
             .accounts(opinions_market::accounts::SettlePost {
                 payer: self.payer.pubkey(),
                 post: post_pda,
+                creator_user_account: creator_user_account_pda,
                 post_pot_token_account: post_pot_token_account_pda,
                 post_pot_authority: post_pot_authority_pda,
                 post_mint_payout: post_mint_payout_pda,
                 protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
                 parent_post: parent_post_pda,
-                parent_post_pot_token_account: parent_post_pot_token_account_pda,
-                parent_post_pot_authority: parent_post_pot_authority_pda,
                 config: config_pda,
                 token_mint: *token_mint,
                 token_program: spl_token::ID,
                 system_program: solana_sdk::system_program::ID,
             })
+            .accounts(ancestor_accounts)
             .args(opinions_market::instruction::SettlePost { post_id_hash })
             .instructions()
             .map_err(|e| anyhow::anyhow!("Failed to build SettlePost instruction: {}", e))?;
@@ -1602,9 +1931,12 @@ impl SolanaService {
             get_user_vault_token_account_pda(&program_id, &post_account.creator_user, token_mint);
         let (vault_authority_pda, _) = get_vault_authority_pda(&program_id);
 
+        let (config_pda, _) = get_config_pda(&program_id);
+
         let ixs = program
             .request()
             .accounts(opinions_market::accounts::DistributeCreatorReward {
+                config: config_pda,
                 payer: self.payer.pubkey(),
                 post: post_pda,
                 post_pot_token_account: post_pot_token_account_pda,
@@ -1689,27 +2021,24 @@ impl SolanaService {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to fetch post account: {}", e))?;
 
-        let (parent_post_pda, parent_post_pot_token_account_pda, parent_post_pot_authority_pda) =
-            match post_account.post_type {
-                opinions_market::state::PostType::Child { parent } => {
-                    let parent_pot_token_account =
-                        get_post_pot_token_account_pda(&program_id, &parent, token_mint);
-                    let parent_pot_authority = get_post_pot_authority_pda(&program_id, &parent);
-                    (
-                        Some(parent),
-                        Some(parent_pot_token_account.0),
-                        Some(parent_pot_authority.0),
-                    )
-                }
-                opinions_market::state::PostType::Original => {
-                    // Not a child post, return None
-                    return Ok(None);
-                }
-            };
+        let (parent_post_pda, parent_post_pot_token_account_pda) = match post_account.post_type {
+            opinions_market::state::PostType::Child { parent } => {
+                let parent_pot_token_account =
+                    get_post_pot_token_account_pda(&program_id, &parent, token_mint);
+                (parent, parent_pot_token_account.0)
+            }
+            opinions_market::state::PostType::Original => {
+                // Not a child post, return None
+                return Ok(None);
+            }
+        };
+
+        let (config_pda, _) = get_config_pda(&program_id);
 
         let ixs = program
             .request()
             .accounts(opinions_market::accounts::DistributeParentPostShare {
+                config: config_pda,
                 payer: self.payer.pubkey(),
                 post: post_pda,
                 post_pot_token_account: post_pot_token_account_pda,
@@ -1717,7 +2046,6 @@ impl SolanaService {
                 post_mint_payout: post_mint_payout_pda,
                 parent_post: parent_post_pda,
                 parent_post_pot_token_account: parent_post_pot_token_account_pda,
-                parent_post_pot_authority: parent_post_pot_authority_pda,
                 token_mint: *token_mint,
                 token_program: spl_token::ID,
             })
@@ -1733,6 +2061,78 @@ impl SolanaService {
         Ok(Some(ixs))
     }
 
+    /// Build DistributeAncestorPostShare instructions for the ancestors above the parent
+    /// (returns instructions, doesn't send). Ancestors without a pot in `token_mint` are
+    /// skipped, their share stays in the post's pot until it is swept.
+    pub async fn build_distribute_ancestor_post_share_instructions(
+        &self,
+        post_id_hash: [u8; 32],
+        token_mint: &Pubkey,
+    ) -> anyhow::Result<Vec<Instruction>> {
+        let program = self.opinions_market_program();
+        let program_id = program.id();
+
+        // Derive PDAs
+        let (config_pda, _) = get_config_pda(&program_id);
+        let (post_pda, _) = get_post_pda(&program_id, &post_id_hash);
+        let (post_pot_token_account_pda, _) =
+            get_post_pot_token_account_pda(&program_id, &post_pda, token_mint);
+        let (post_pot_authority_pda, _) = get_post_pot_authority_pda(&program_id, &post_pda);
+        let (post_mint_payout_pda, _) =
+            get_post_mint_payout_pda(&program_id, &post_pda, token_mint);
+
+        let post_account = program
+            .account::<opinions_market::state::PostAccount>(post_pda)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch post account: {}", e))?;
+
+        let mut ixs = Vec::new();
+        for level in 2..=opinions_market::constants::MAX_MOTHER_FEE_LEVELS as u8 {
+            let Some(ancestor_post_pda) = post_account.ancestor(level) else {
+                break;
+            };
+            let (ancestor_post_pot_token_account_pda, _) =
+                get_post_pot_token_account_pda(&program_id, &ancestor_post_pda, token_mint);
+            if self
+                .rpc
+                .get_account(&ancestor_post_pot_token_account_pda)
+                .await
+                .is_err()
+            {
+                continue;
+            }
+
+            let level_ixs = program
+                .request()
+                .accounts(opinions_market::accounts::DistributeAncestorPostShare {
+                    config: config_pda,
+                    payer: self.payer.pubkey(),
+                    post: post_pda,
+                    post_pot_token_account: post_pot_token_account_pda,
+                    post_pot_authority: post_pot_authority_pda,
+                    post_mint_payout: post_mint_payout_pda,
+                    ancestor_post: ancestor_post_pda,
+                    ancestor_post_pot_token_account: ancestor_post_pot_token_account_pda,
+                    token_mint: *token_mint,
+                    token_program: spl_token::ID,
+                })
+                .args(opinions_market::instruction::DistributeAncestorPostShare {
+                    _post_id_hash: post_id_hash,
+                    level,
+                })
+                .instructions()
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to build DistributeAncestorPostShare instruction: {}",
+                        e
+                    )
+                })?;
+            ixs.extend(level_ixs);
+        }
+
+        Ok(ixs)
+    }
+
     /// Get PostMintPayout account to check frozen status and fees
     pub async fn get_post_mint_payout(
         &self,
@@ -1784,26 +2184,21 @@ impl SolanaService {
             _ => return Ok(None), // Not settled or not frozen
         };
 
-        // Check if user has already claimed
-        let (user_post_mint_claim_pda, _) =
-            get_user_post_mint_claim_pda(&program_id, &post_pda, token_mint, user_wallet);
-        if let Ok(claim_account) = program
-            .account::<opinions_market::state::UserPostMintClaim>(user_post_mint_claim_pda)
-            .await
-        {
-            // Check if already claimed
-            if claim_account.claimed {
-                return Ok(None); // Already claimed
-            }
-        }
-        // If claim account doesn't exist, user hasn't claimed yet - continue
-
         // Get user's position
         let position = match self.get_user_position(user_wallet, post_id_hash).await? {
             Some(pos) => pos,
-            None => return Ok(None), // User hasn't voted
+            None => return Ok(None), // User hasn't voted, or the position was closed
         };
 
+        // Check if user has already claimed - the position keeps track, the claim record may be closed
+        let pot_mint_bit = match post_account.pot_mint_bit(token_mint) {
+            Ok(bit) => bit,
+            Err(_) => return Ok(None), // No pot in this mint
+        };
+        if position.claimed_pot_mints & pot_mint_bit != 0 {
+            return Ok(None); // Already claimed
+        }
+
         // Get winning side
         let winning_side = match post_account.winning_side {
             Some(side) => side,
@@ -1811,27 +2206,19 @@ impl SolanaService {
         };
 
         // Calculate user's winning votes
-        let user_votes = match winning_side {
-            opinions_market::state::Side::Pump => position.upvotes as u64,
-            opinions_market::state::Side::Smack => position.downvotes as u64,
-        };
+        let user_votes = position.votes_on(winning_side);
 
         if user_votes == 0 {
-            return Ok(None); // User didn't vote for winning side
+            return Ok(None); // User didn't vote for the winning side
         }
 
-        // Calculate claimable amount
-        use opinions_market::constants::PRECISION;
-        let scaled = user_votes
-            .checked_mul(payout.payout_per_winning_vote)
+        // Calculate claimable amount, per vote or by stake as frozen at settlement
+        let reward = payout
+            .reward_for(user_votes, position.paid_bling_on(winning_side))
             .ok_or(anyhow::anyhow!(
                 "Math overflow calculating claimable reward"
             ))?;
 
-        let reward = scaled
-            .checked_div(PRECISION)
-            .ok_or(anyhow::anyhow!("Math overflow dividing claimable reward"))?;
-
         if reward == 0 {
             return Ok(None); // Reward too small
         }
@@ -1859,6 +2246,7 @@ impl SolanaService {
         // Derive PDAs
         let (config_pda, _) = get_config_pda(&program_id);
         let (post_pda, _) = get_post_pda(&program_id, &post_id_hash);
+        let (user_account_pda, _) = get_user_account_pda(&program_id, user_wallet);
         let (position_pda, _) = get_position_pda(&program_id, &post_pda, user_wallet);
         let (user_post_mint_claim_pda, _) =
             get_user_post_mint_claim_pda(&program_id, &post_pda, token_mint, user_wallet);
@@ -1871,6 +2259,7 @@ impl SolanaService {
             get_user_vault_token_account_pda(&program_id, user_wallet, token_mint);
         let (session_authority_pda, _) =
             get_session_authority_pda(&program_id, user_wallet, &self.session_key.pubkey());
+        let (vault_authority_pda, _) = get_vault_authority_pda(&program_id);
 
         println!("  🔨 SolanaService::claim_post_reward: Building ClaimPostReward instruction...");
 
@@ -1883,12 +2272,16 @@ impl SolanaService {
                 session_key: self.session_key.pubkey(),
                 session_authority: session_authority_pda,
                 post: post_pda,
+                user_account: user_account_pda,
                 position: position_pda,
                 user_post_mint_claim: user_post_mint_claim_pda,
                 post_mint_payout: post_mint_payout_pda,
                 post_pot_token_account: post_pot_token_account_pda,
                 post_pot_authority: post_pot_authority_pda,
+                vault_authority: vault_authority_pda,
                 user_vault_token_account: user_vault_token_account_pda,
+                share_mint: None,
+                user_share_token_account: None,
                 token_mint: *token_mint,
                 token_program: spl_token::ID,
                 system_program: solana_sdk::system_program::ID,
@@ -1905,7 +2298,7 @@ impl SolanaService {
 
         println!("  ✅ SolanaService::claim_post_reward: Instruction built successfully");
 
-        let tx = self.build_partial_signed_tx(ixs).await.map_err(|e| {
+        let tx = self.build_session_signed_tx(ixs).await.map_err(|e| {
             eprintln!(
                 "  ❌ SolanaService::claim_post_reward: Failed to build transaction: {}",
                 e
@@ -1928,4 +2321,226 @@ impl SolanaService {
         );
         Ok(signature)
     }
+
+    /// Claim a user's rewards in one mint across many settled posts in a single transaction.
+    ///
+    /// claim_post_rewards is permissionless, so no session is involved: the backend payer
+    /// cranks it and rewards land in the user's vault.
+    pub async fn claim_post_rewards(
+        &self,
+        user_wallet: &Pubkey,
+        post_id_hashes: &[[u8; 32]],
+        token_mint: &Pubkey,
+    ) -> anyhow::Result<Signature> {
+        println!(
+            "  🔧 SolanaService::claim_post_rewards: Starting for user {}, {} posts, token_mint: {}",
+            user_wallet,
+            post_id_hashes.len(),
+            token_mint
+        );
+
+        let program = self.opinions_market_program();
+        let program_id = program.id();
+
+        let (user_account_pda, _) = get_user_account_pda(&program_id, user_wallet);
+        let (user_vault_token_account_pda, _) =
+            get_user_vault_token_account_pda(&program_id, user_wallet, token_mint);
+
+        // One (post, position, payout, pot, pot authority, claim) group per post
+        let mut remaining_accounts = Vec::with_capacity(post_id_hashes.len() * 6);
+        for post_id_hash in post_id_hashes {
+            let (post_pda, _) = get_post_pda(&program_id, post_id_hash);
+            let (position_pda, _) = get_position_pda(&program_id, &post_pda, user_wallet);
+            let (post_mint_payout_pda, _) =
+                get_post_mint_payout_pda(&program_id, &post_pda, token_mint);
+            let (post_pot_token_account_pda, _) =
+                get_post_pot_token_account_pda(&program_id, &post_pda, token_mint);
+            let (post_pot_authority_pda, _) = get_post_pot_authority_pda(&program_id, &post_pda);
+            let (user_post_mint_claim_pda, _) =
+                get_user_post_mint_claim_pda(&program_id, &post_pda, token_mint, user_wallet);
+
+            remaining_accounts.extend([
+                AccountMeta::new_readonly(post_pda, false),
+                AccountMeta::new(position_pda, false),
+                AccountMeta::new_readonly(post_mint_payout_pda, false),
+                AccountMeta::new(post_pot_token_account_pda, false),
+                AccountMeta::new_readonly(post_pot_authority_pda, false),
+                AccountMeta::new(user_post_mint_claim_pda, false),
+            ]);
+        }
+
+        println!(
+            "  🔨 SolanaService::claim_post_rewards: Building ClaimPostRewards instruction..."
+        );
+
+        let (config_pda, _) = get_config_pda(&program_id);
+
+        let ixs = program
+            .request()
+            .accounts(opinions_market::accounts::ClaimPostRewards {
+                config: config_pda,
+                payer: self.payer.pubkey(),
+                user: *user_wallet,
+                user_account: user_account_pda,
+                user_vault_token_account: user_vault_token_account_pda,
+                token_mint: *token_mint,
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            })
+            .accounts(remaining_accounts)
+            .args(opinions_market::instruction::ClaimPostRewards {})
+            .instructions()
+            .map_err(|e| {
+                eprintln!(
+                    "  ❌ SolanaService::claim_post_rewards: Failed to build instruction: {}",
+                    e
+                );
+                anyhow::anyhow!("Failed to build ClaimPostRewards instruction: {}", e)
+            })?;
+
+        let tx = self.build_partial_signed_tx(ixs).await.map_err(|e| {
+            eprintln!(
+                "  ❌ SolanaService::claim_post_rewards: Failed to build transaction: {}",
+                e
+            );
+            e
+        })?;
+
+        println!("  📡 SolanaService::claim_post_rewards: Sending transaction...");
+        let signature = self.send_signed_tx(&tx).await.map_err(|e| {
+            eprintln!(
+                "  ❌ SolanaService::claim_post_rewards: Failed to send transaction: {}",
+                e
+            );
+            e
+        })?;
+
+        println!(
+            "  ✅ SolanaService::claim_post_rewards: Transaction confirmed! Signature: {}",
+            signature
+        );
+        Ok(signature)
+    }
+
+    /// Close a user's claimed UserPostMintClaim records on a post, and the position itself once
+    /// every pot is claimed or swept. Rent goes back to whoever funded each account.
+    ///
+    /// Both instructions are permissionless, so the backend payer cranks them.
+    pub async fn close_user_post_accounts(
+        &self,
+        user_wallet: &Pubkey,
+        post_id_hash: [u8; 32],
+    ) -> anyhow::Result<Option<Signature>> {
+        println!(
+            "  🔧 SolanaService::close_user_post_accounts: Starting for user {}, post_id_hash: {}",
+            user_wallet,
+            hex::encode(post_id_hash)
+        );
+
+        let program = self.opinions_market_program();
+        let program_id = program.id();
+        let (post_pda, _) = get_post_pda(&program_id, &post_id_hash);
+        let (config_pda, _) = get_config_pda(&program_id);
+
+        let post_account = program
+            .account::<opinions_market::state::PostAccount>(post_pda)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch post account: {}", e))?;
+
+        let mut ixs = Vec::new();
+
+        for token_mint in post_account
+            .pot_mints
+            .iter()
+            .filter(|m| **m != Pubkey::default())
+        {
+            let (user_post_mint_claim_pda, _) =
+                get_user_post_mint_claim_pda(&program_id, &post_pda, token_mint, user_wallet);
+            let claim = match program
+                .account::<opinions_market::state::UserPostMintClaim>(user_post_mint_claim_pda)
+                .await
+            {
+                Ok(claim) if claim.claimed => claim,
+                _ => continue, // Not claimed yet, or already closed
+            };
+
+            ixs.extend(
+                program
+                    .request()
+                    .accounts(opinions_market::accounts::CloseUserPostMintClaim {
+                        config: config_pda,
+                        payer: self.payer.pubkey(),
+                        user: *user_wallet,
+                        post: post_pda,
+                        user_post_mint_claim: user_post_mint_claim_pda,
+                        rent_payer: claim.rent_payer,
+                        token_mint: *token_mint,
+                    })
+                    .args(opinions_market::instruction::CloseUserPostMintClaim {
+                        _post_id_hash: post_id_hash,
+                    })
+                    .instructions()
+                    .map_err(|e| {
+                        anyhow::anyhow!("Failed to build CloseUserPostMintClaim instruction: {}", e)
+                    })?,
+            );
+        }
+
+        let (position_pda, _) = get_position_pda(&program_id, &post_pda, user_wallet);
+        if let Some(position) = self.get_user_position(user_wallet, &post_id_hash).await? {
+            if position.fully_claimed(&post_account) {
+                ixs.extend(
+                    program
+                        .request()
+                        .accounts(opinions_market::accounts::CloseUserPostPosition {
+                            config: config_pda,
+                            payer: self.payer.pubkey(),
+                            user: *user_wallet,
+                            post: post_pda,
+                            user_account: get_user_account_pda(&program_id, user_wallet).0,
+                            position: position_pda,
+                            rent_payer: position.rent_payer,
+                        })
+                        .args(opinions_market::instruction::CloseUserPostPosition {
+                            _post_id_hash: post_id_hash,
+                        })
+                        .instructions()
+                        .map_err(|e| {
+                            anyhow::anyhow!(
+                                "Failed to build CloseUserPostPosition instruction: {}",
+                                e
+                            )
+                        })?,
+                );
+            }
+        }
+
+        if ixs.is_empty() {
+            println!("  ℹ️ SolanaService::close_user_post_accounts: Nothing to close");
+            return Ok(None);
+        }
+
+        let tx = self.build_partial_signed_tx(ixs).await.map_err(|e| {
+            eprintln!(
+                "  ❌ SolanaService::close_user_post_accounts: Failed to build transaction: {}",
+                e
+            );
+            e
+        })?;
+
+        println!("  📡 SolanaService::close_user_post_accounts: Sending transaction...");
+        let signature = self.send_signed_tx(&tx).await.map_err(|e| {
+            eprintln!(
+                "  ❌ SolanaService::close_user_post_accounts: Failed to send transaction: {}",
+                e
+            );
+            e
+        })?;
+
+        println!(
+            "  ✅ SolanaService::close_user_post_accounts: Transaction confirmed! Signature: {}",
+            signature
+        );
+        Ok(Some(signature))
+    }
 }
@@ -1,3 +1,4 @@
+use crate::state::CurveKind;
 use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
 
 // These will never be changed
@@ -12,15 +13,95 @@ pub const USDC_LAMPORTS_PER_USDC: u64 = 1_000_000;
 
 pub const SMACK_TO_PUMP_PRICE_RATIO: u64 = 10;
 
+/// 100% in basis points
+pub const MAX_BPS: u16 = 10_000;
+
+/// Upper bound for protocol_vote_fee_bps + creator_pump_fee_bps, so at least half of every vote reaches the pot
+pub const MAX_VOTE_FEE_BPS: u16 = 5_000;
+
+/// Withdraw penalty for a user with social score 0. Scales down linearly to 0 at score 10_000
+pub const WITHDRAW_PENALTY_AT_ZERO_SCORE_BPS: u16 = 2_000;
+
+/// Withdraw penalty ceiling, reached at score -100 and below
+pub const MAX_WITHDRAW_PENALTY_BPS: u16 = 5_000;
+
+/// Social score a voter gains per winning vote, applied once per post at the first claim,
+/// or when the position is closed if it never claimed
+pub const SOCIAL_SCORE_PER_WINNING_VOTE: i64 = 1;
+
+/// Social score a voter loses per losing Smack vote, applied like SOCIAL_SCORE_PER_WINNING_VOTE
+pub const SOCIAL_SCORE_PER_LOSING_SMACK_VOTE: i64 = 1;
+
+/// Bound on how far one post can move a voter's score in either direction, so it can't be farmed
+pub const MAX_SOCIAL_SCORE_DELTA_PER_POST: i64 = 100;
+
+/// Social score a creator gains when Pump wins their post, applied once per post at settlement
+pub const CREATOR_PUMP_WIN_SOCIAL_SCORE: i64 = 50;
+
+/// Bits of `SessionPrivileges.allowed_instructions`
+pub const SESSION_PRIVILEGE_CREATE_POST: u32 = 1 << 0;
+pub const SESSION_PRIVILEGE_VOTE_ON_POST: u32 = 1 << 1;
+pub const SESSION_PRIVILEGE_CLAIM_POST_REWARD: u32 = 1 << 2;
+
+/// Bits of `Config.paused`, each stopping one group of instructions
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+/// Kept apart from everything else so users can still get their funds out
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 1;
+/// vote_on_post, commit_vote and exit_position. reveal_vote stays open: its window
+/// doesn't move with a pause, and an unrevealed commitment loses its escrow.
+pub const PAUSE_VOTING: u8 = 1 << 2;
+pub const PAUSE_POST_CREATION: u8 = 1 << 3;
+/// Settlement, fee distribution, claims, sweeps, cancellation refunds, closing
+/// unrevealed commitments and closing claim records and positions
+pub const PAUSE_CLAIMS: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 =
+    PAUSE_DEPOSITS | PAUSE_WITHDRAWALS | PAUSE_VOTING | PAUSE_POST_CREATION | PAUSE_CLAIMS;
+
+/// Longest a session can be registered for
+pub const MAX_SESSION_DURATION_SECS: i64 = 60 * 60 * 24 * 30; // 30 days
+
+/// Number of mint slots in `SessionPrivileges.allowed_mints`
+pub const MAX_SESSION_ALLOWED_MINTS: usize = 4;
+
+/// Number of mints a single post can hold pots in (tracked as u8 bitmaps)
+pub const MAX_POST_POT_MINTS: usize = 4;
+
+/// Reply-tree levels a settling post can pay a mother fee to: parent, grandparent, ...
+pub const MAX_MOTHER_FEE_LEVELS: usize = 4;
+/// Cap on the parent's share of a child pot; the levels above only decay from it
+pub const MAX_MOTHER_FEE_BPS: u16 = 2_000;
+
+/// Post curve multipliers start at 1x (in BPS) and are capped at 100x
+pub const CURVE_BASE_MULT_BPS: u64 = 10_000;
+pub const MAX_POST_CURVE_MULT_BPS: u64 = 1_000_000;
+/// Cap on the user curve factor, the Linear curve's value at MAX_VOTE_COUNT_CAP previous votes
+pub const MAX_USER_CURVE_FACTOR: u64 = MAX_VOTE_COUNT_CAP + 1;
+/// Quadratic post curve: +1 BPS per QUADRATIC_CURVE_DIVISOR votes squared
+pub const QUADRATIC_CURVE_DIVISOR: u64 = 2_000;
+/// Exponential curves double every this many votes (post) / previous votes (user)
+pub const EXPONENTIAL_POST_CURVE_DOUBLING_VOTES: u64 = 100;
+pub const EXPONENTIAL_USER_CURVE_DOUBLING_VOTES: u64 = 10;
+
+/// Default fee schedule written into `Config` at `initialize`.
+/// The live values are on-chain and can be changed by the admin with `update_config`.
 pub struct PayoutParams {
     pub protocol_vote_fee_bps: u16,
     pub protocol_vote_settlement_fee_bps: u16,
     pub creator_pump_fee_bps: u16,
     pub creator_pump_win_settlement_fee_bps: u16,
+    pub exit_fee_bps: u16,
+    pub mother_fee_bps: u16,
+    pub mother_fee_decay_bps: u16,
+    pub mother_fee_levels: u8,
     // vote-tokenomics constants
     pub bling_per_vote_base_cost: u64,
     // user constants
     pub user_initial_social_score: i64,
+    // settlement constants
+    pub claim_window_secs: u32,
+    pub reveal_window_secs: u32,
+    // post constants
+    pub allowed_curves: u8,
 }
 
 pub const PARAMS: PayoutParams = PayoutParams {
@@ -28,6 +109,13 @@ pub const PARAMS: PayoutParams = PayoutParams {
     protocol_vote_settlement_fee_bps: 100, // 1% of every vote goes to the protocol
     creator_pump_fee_bps: 100,  // 1% of every pump vote goes to the creator
     creator_pump_win_settlement_fee_bps: 40, // 40% of the pot goes to the creator when the post is settled in favour of pump
+    exit_fee_bps: 500, // 5% of what an exiting position takes out of the pot stays in it
+    mother_fee_bps: 1_000, // 10% of a child post's pot goes to its parent
+    mother_fee_decay_bps: 5_000, // each level further up gets half of the level below: 10%, 5%, 2.5%
+    mother_fee_levels: 3,        // parent, grandparent and great-grandparent
     bling_per_vote_base_cost: 1 * LAMPORTS_PER_SOL, // 1 vote = 1 * LAMPORTS_PER_SOL by default
     user_initial_social_score: 10_000, // 10_000 by default - already have room to decrease and die of bankruptcy
+    claim_window_secs: 30 * 24 * 3600, // 30 days to claim after settlement, then leftovers can be swept
+    reveal_window_secs: 24 * 3600,     // commit–reveal posts: 1 day after end_time to reveal votes
+    allowed_curves: CurveKind::Linear.bit(), // only today's curve until the admin allows more
 };
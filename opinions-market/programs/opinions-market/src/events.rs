@@ -0,0 +1,253 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_MOTHER_FEE_LEVELS;
+use crate::state::{CurveKind, PayoutPolicy, PayoutWeighting, Side, VoteMode};
+
+#[event]
+pub struct Withdrawn {
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    /// Amount requested, before the penalty
+    pub amount: u64,
+    /// Amount sent to the user's wallet
+    pub effective_amount: u64,
+    /// Amount sent to the protocol treasury
+    pub penalty: u64,
+    pub penalty_bps: u16,
+    pub social_score: i64,
+}
+
+#[event]
+pub struct UserCreated {
+    pub user: Pubkey,
+    pub user_account: Pubkey,
+    pub social_score: i64,
+}
+
+#[event]
+pub struct SessionRegistered {
+    pub user: Pubkey,
+    pub session_key: Pubkey,
+    pub session_authority: Pubkey,
+    pub expires_at: i64,
+    pub privileges_hash: [u8; 32],
+    pub allowed_instructions: u32,
+    pub max_bling_spend: u64,
+}
+
+#[event]
+pub struct Deposited {
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PostCreated {
+    pub post: Pubkey,
+    pub creator: Pubkey,
+    pub post_id_hash: [u8; 32],
+    pub parent_post: Option<Pubkey>,
+    pub curve: CurveKind,
+    pub vote_mode: VoteMode,
+    pub tokenized: bool,
+    pub payout_weighting: PayoutWeighting,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct Voted {
+    pub post: Pubkey,
+    pub voter: Pubkey,
+    pub side: Side,
+    /// Votes applied, after the overflow cap
+    pub votes: u64,
+    pub token_mint: Pubkey,
+    /// Total cost in BLING, before conversion to `token_mint`
+    pub cost_bling: u64,
+    /// Amounts actually transferred, in `token_mint`
+    pub protocol_fee: u64,
+    pub creator_pump_fee: u64,
+    pub pot_increment: u64,
+    pub post_upvotes: u64,
+    pub post_downvotes: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct PositionExited {
+    pub post: Pubkey,
+    pub voter: Pubkey,
+    pub side: Side,
+    pub votes: u64,
+    pub token_mint: Pubkey,
+    /// Refund the curve quoted in BLING, before conversion to `token_mint` and the cap at
+    /// the position's pot contribution
+    pub quoted_refund_bling: u64,
+    /// Amounts in `token_mint`, capped at the position's pot contribution: paid out of the
+    /// pot, and kept in it
+    pub refund: u64,
+    pub exit_fee: u64,
+    pub post_upvotes: u64,
+    pub post_downvotes: u64,
+}
+
+#[event]
+pub struct VoteCommitted {
+    pub post: Pubkey,
+    pub voter: Pubkey,
+    pub token_mint: Pubkey,
+    /// Moved into the post pot, in `token_mint`
+    pub escrow: u64,
+    pub reveal_end_time: i64,
+}
+
+#[event]
+pub struct VoteRevealed {
+    pub post: Pubkey,
+    pub voter: Pubkey,
+    pub token_mint: Pubkey,
+    /// Escrow left over after paying for the vote, returned to the voter's vault
+    pub refund: u64,
+}
+
+#[event]
+pub struct VoteCommitmentForfeited {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    /// Escrow kept by the post pot
+    pub escrow: u64,
+    pub rent_payer: Pubkey,
+}
+
+#[event]
+pub struct PostSettled {
+    pub post: Pubkey,
+    pub token_mint: Pubkey,
+    pub winning_side: Side,
+    pub total_winning_votes: u64,
+    pub initial_pot: u64,
+    /// Sum of `mother_fees`
+    pub mother_fee: u64,
+    /// Share of each ancestor, parent first
+    pub mother_fees: [u64; MAX_MOTHER_FEE_LEVELS],
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub total_payout: u64,
+    /// Scaled by PRECISION
+    pub payout_per_winning_vote: u64,
+    pub policy: PayoutPolicy,
+    pub weighting: PayoutWeighting,
+}
+
+#[event]
+pub struct CreatorRewardDistributed {
+    pub post: Pubkey,
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProtocolFeeDistributed {
+    pub post: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ParentShareDistributed {
+    pub post: Pubkey,
+    /// The ancestor `level` steps up the reply tree that received the share
+    pub parent_post: Pubkey,
+    /// 1 for the parent
+    pub level: u8,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardClaimed {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub winning_votes: u64,
+    /// Zero when an earlier claim on this post already moved the score
+    pub social_score_delta: i64,
+}
+
+#[event]
+pub struct PostPotSwept {
+    pub post: Pubkey,
+    pub token_mint: Pubkey,
+    /// Dust and unclaimed rewards sent to the protocol treasury
+    pub amount: u64,
+    pub claims_closed: u32,
+}
+
+#[event]
+pub struct UserPostAccountClosed {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    /// Set for a UserPostMintClaim, None for a UserPostPosition
+    pub token_mint: Option<Pubkey>,
+    pub rent_payer: Pubkey,
+}
+
+#[event]
+pub struct PostCancelled {
+    pub post: Pubkey,
+    pub admin: Pubkey,
+    pub upvotes: u64,
+    pub downvotes: u64,
+    /// Refunds are open until then, the pots can be swept after
+    pub refund_deadline: i64,
+}
+
+#[event]
+pub struct PositionRefunded {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    /// The position's pot contribution in `token_mint`, fees excluded
+    pub amount: u64,
+}
+
+#[event]
+pub struct VoteCommitmentRefunded {
+    pub post: Pubkey,
+    pub user: Pubkey,
+    pub token_mint: Pubkey,
+    pub escrow: u64,
+    pub rent_payer: Pubkey,
+}
+
+#[event]
+pub struct PauseSet {
+    pub admin: Pubkey,
+    /// The new PAUSE_* bitmap
+    pub paused: u8,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    /// `Pubkey::default()` when a proposal is withdrawn
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct PayerAuthoritySet {
+    pub admin: Pubkey,
+    pub previous_payer_authority: Pubkey,
+    pub payer_authority: Pubkey,
+}
@@ -55,15 +55,17 @@ pub struct Initialize<'info> {
 #[derive(Accounts)]
 pub struct RegisterValidPayment<'info> {
     #[account(mut,
-    constraint = config.admin == admin.key())]
+    constraint = config.admin == admin.key() @ ErrorCode::Unauthorized)]
     pub config: Account<'info, Config>,
     // we need to require this to be the admin of the config account
-    #[account(mut)]
     pub admin: Signer<'info>,
+    /// Funds the new accounts, so a multisig admin never has to hold lamports
+    #[account(mut)]
+    pub payer: Signer<'info>,
     pub token_mint: Account<'info, Mint>,
     #[account(
         init,
-        payer = admin,
+        payer = payer,
         seeds = [VALID_PAYMENT_SEED, token_mint.key().as_ref()],
         bump,
         space = 8 + ValidPayment::INIT_SPACE,
@@ -74,7 +76,7 @@ pub struct RegisterValidPayment<'info> {
     /// NEW treasury token account for this mint, canonical PDA.
     #[account(
         init,
-        payer = admin,
+        payer = payer,
         seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
         bump,
         token::mint = token_mint,
@@ -87,17 +89,84 @@ pub struct RegisterValidPayment<'info> {
 }
 
 
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.pending_admin == new_admin.key() @ ErrorCode::NotPendingAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayerAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+
 #[derive(Accounts)]
 pub struct ModifyAcceptedMint<'info> {
     #[account(
         mut,
         seeds = [CONFIG_SEED],
         bump,
-        constraint = config.admin == admin.key(),
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
     )]
     pub config: Account<'info, Config>,
 
-    #[account(mut)]
     pub admin: Signer<'info>,
 
     pub mint: Account<'info, Mint>,
@@ -106,12 +175,87 @@ pub struct ModifyAcceptedMint<'info> {
         mut,
         seeds = [VALID_PAYMENT_SEED, mint.key().as_ref()],
         bump = accepted_mint.bump,
+        constraint = accepted_mint.token_mint == mint.key(),
     )]
     pub accepted_mint: Account<'info, ValidPayment>,
 }
 
 
 
+#[derive(Accounts)]
+pub struct SetPriceFeed<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = token_mint.key() != config.bling_mint @ ErrorCode::BlingCannotBeAlternativePayment,
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [VALID_PAYMENT_SEED, token_mint.key().as_ref()],
+        bump = valid_payment.bump,
+    )]
+    pub valid_payment: Account<'info, ValidPayment>,
+
+    /// CHECK: Pyth price update account, owner and layout checked in the handler. None detaches.
+    pub price_feed: Option<UncheckedAccount<'info>>,
+}
+
+#[cfg(feature = "pushed-price-feed")]
+#[derive(Accounts)]
+pub struct SetPushedPriceFeed<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    /// Funds the price feed account, so a multisig admin never has to hold lamports
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = token_mint.key() != config.bling_mint @ ErrorCode::BlingCannotBeAlternativePayment,
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [PUSHED_PRICE_FEED_SEED, token_mint.key().as_ref()],
+        bump,
+        space = 8 + PushedPriceFeed::INIT_SPACE,
+    )]
+    pub pushed_price_feed: Account<'info, PushedPriceFeed>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(feature = "pushed-price-feed")]
+#[derive(Accounts)]
+pub struct UpdatePushedPriceFeed<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PUSHED_PRICE_FEED_SEED, pushed_price_feed.token_mint.as_ref()],
+        bump = pushed_price_feed.bump,
+        constraint = pushed_price_feed.authority == authority.key() @ ErrorCode::Unauthorized,
+    )]
+    pub pushed_price_feed: Account<'info, PushedPriceFeed>,
+}
+
 // This initializes the UserAccount PDA only
 #[derive(Accounts)]
 pub struct CreateUser<'info> {
@@ -160,6 +304,13 @@ pub struct RegisterSession<'info> {
     /// CHECK: ephemeral delegated session key
     pub session_key: UncheckedAccount<'info>,
 
+    #[account(
+        mut,
+        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
     #[account(
         init_if_needed,
         payer = payer,
@@ -176,12 +327,110 @@ pub struct RegisterSession<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    /// CHECK: Payer for transaction fees
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: the user wallet; signs the tx or the Ed25519 message
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: session key being revoked
+    pub session_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [SESSION_AUTHORITY_SEED, user.key().as_ref(), session_key.key().as_ref()],
+        bump = session_authority.bump,
+        close = rent_payer,
+    )]
+    pub session_authority: Account<'info, SessionAuthority>,
+
+    /// CHECK: whoever funded the session account, receives the rent
+    #[account(
+        mut,
+        address = session_authority.rent_payer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: sysvar required to load instructions in the tx
+    #[account(address = instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RotateSession<'info> {
+    /// CHECK: Payer for transaction fees and the new session authority account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: the user wallet; signs the tx or the Ed25519 message
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: session key being replaced
+    pub old_session_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [SESSION_AUTHORITY_SEED, user.key().as_ref(), old_session_key.key().as_ref()],
+        bump = old_session_authority.bump,
+        close = rent_payer,
+    )]
+    pub old_session_authority: Account<'info, SessionAuthority>,
+
+    /// CHECK: whoever funded the old session account, receives the rent
+    #[account(
+        mut,
+        address = old_session_authority.rent_payer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// CHECK: new ephemeral delegated session key
+    pub new_session_key: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [SESSION_AUTHORITY_SEED, user.key().as_ref(), new_session_key.key().as_ref()],
+        bump,
+        space = 8 + SessionAuthority::INIT_SPACE,
+    )]
+    pub new_session_authority: Account<'info, SessionAuthority>,
+
+    /// CHECK: sysvar required to load instructions in the tx
+    #[account(address = instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 
 
 /// User deposits from their wallet into the program-controlled vault.
 /// Also initializes the program-controlled vault if it doesn't exist.
 #[derive(Accounts)]
 pub struct Deposit<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
     // Here the user must be a signer. If we want to use someone else to pay other than our centralized payer, just pass user into payer.
     #[account(mut)]
     pub user: Signer<'info>,
@@ -233,6 +482,12 @@ pub struct Deposit<'info> {
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+
     // Here the user must be a signer. If we want to use someone else to pay other than our centralized payer, just pass user into payer.
     #[account(mut)]
     pub user: Signer<'info>,    
@@ -262,6 +517,16 @@ pub struct Withdraw<'info> {
     )]
     pub user_vault_token_account: Account<'info, TokenAccount>,
 
+    // protocol treasury for this mint, receives the withdraw penalty
+    #[account(
+        mut,
+        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = config,
+    )]
+    pub protocol_token_treasury_token_account: Account<'info, TokenAccount>,
+
     /// CHECK: Global vault authority PDA derived from seeds
     #[account(
         seeds = [VAULT_AUTHORITY_SEED],
@@ -313,6 +578,43 @@ pub struct CreatePost<'info> {
         space = 8 + PostAccount::INIT_SPACE,
     )]
     pub post: Account<'info, PostAccount>,
+
+    // Replies only: must still be open, gains a child
+    #[account(
+        mut,
+        constraint = parent_post.state == PostState::Open @ ErrorCode::PostNotOpen,
+    )]
+    pub parent_post: Option<Box<Account<'info, PostAccount>>>,
+
+    /// CHECK: Post pot authority PDA derived from seeds; mint authority of the share mints
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    // Tokenized posts only
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POST_SHARE_MINT_SEED, post.key().as_ref(), &[Side::Pump as u8]],
+        bump,
+        mint::decimals = 0,
+        mint::authority = post_pot_authority,
+    )]
+    pub pump_share_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POST_SHARE_MINT_SEED, post.key().as_ref(), &[Side::Smack as u8]],
+        bump,
+        mint::decimals = 0,
+        mint::authority = post_pot_authority,
+    )]
+    pub smack_share_mint: Option<Box<Account<'info, Mint>>>,
+
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -434,6 +736,19 @@ pub struct VoteOnPost<'info> {
     )]
     pub valid_payment: Box<Account<'info, ValidPayment>>,
 
+    /// CHECK: Required when valid_payment.price_feed is set, owner and layout checked when read
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    // Tokenized posts only: the share mint of the voted side and the voter's token account for it
+    #[account(mut)]
+    pub share_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(
+        mut,
+        constraint = voter_share_token_account.owner == voter.key() @ ErrorCode::InvalidShareAccounts,
+    )]
+    pub voter_share_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
     pub token_mint: Account<'info, Mint>,
     
     pub token_program: Program<'info, Token>,
@@ -441,89 +756,940 @@ pub struct VoteOnPost<'info> {
 }
 
 
+// Read-only: nothing is written and nobody needs to sign but the fee payer of the simulation
 #[derive(Accounts)]
-#[instruction(post_id_hash: [u8; 32])]
-pub struct SettlePost<'info> {
-    /// CHECK: Payer for transaction fees
-    #[account(mut)]
-    pub payer: Signer<'info>,
+#[instruction(side: Side, votes: u64, post_id_hash: [u8; 32])]
+pub struct QuoteVote<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: the user wallet the quote is for
+    pub voter: UncheckedAccount<'info>,
 
     #[account(
-        mut,
         seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
         bump,
+        constraint = post.state == PostState::Open @ ErrorCode::PostNotOpen,
     )]
-    pub post: Account<'info, PostAccount>,
+    pub post: Box<Account<'info, PostAccount>>,
 
     #[account(
-        mut,
-        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        seeds = [USER_ACCOUNT_SEED, voter.key().as_ref()],
+        bump,
+    )]
+    pub voter_user_account: Box<Account<'info, UserAccount>>,
+
+    // None until the voter's first vote on this post
+    #[account(
+        seeds = [POSITION_SEED, post.key().as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub position: Option<Box<Account<'info, UserPostPosition>>>,
+
+    #[account(
+        seeds = [VALID_PAYMENT_SEED, token_mint.key().as_ref()],
+        bump = valid_payment.bump,
+        constraint = valid_payment.enabled @ ErrorCode::MintNotEnabled,
+    )]
+    pub valid_payment: Box<Account<'info, ValidPayment>>,
+
+    /// CHECK: Required when valid_payment.price_feed is set, owner and layout checked when read
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    pub token_mint: Account<'info, Mint>,
+}
+
+// Same pattern as VoteOnPost: the voter may sign directly or through a session
+#[derive(Accounts)]
+#[instruction(side: Side, votes: u64, post_id_hash: [u8; 32])]
+pub struct ExitPosition<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: real user identity (owner of UserAccount and vaults)
+    #[account(mut)]
+    pub voter: UncheckedAccount<'info>,
+
+    /// CHECK: Signer paying the TX fee (user or backend)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: ephemeral delegated session key
+    #[account(mut)]
+    pub session_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SESSION_AUTHORITY_SEED, voter.key().as_ref(), session_key.key().as_ref()],
+        bump,
+    )]
+    pub session_authority: Account<'info, SessionAuthority>,
+
+    #[account(
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+        constraint = post.state == PostState::Open @ ErrorCode::PostNotOpen,
+    )]
+    pub post: Box<Account<'info, PostAccount>>,
+
+    #[account(
+        seeds = [USER_ACCOUNT_SEED, voter.key().as_ref()],
+        bump,
+    )]
+    pub voter_user_account: Box<Account<'info, UserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, post.key().as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, UserPostPosition>>,
+
+    #[account(
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, voter.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+    )]
+    pub voter_user_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA derived from seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = post_pot_authority,
+    )]
+    pub post_pot_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Post pot authority PDA derived from seeds
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump = post.pot_authority_bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [VALID_PAYMENT_SEED, token_mint.key().as_ref()],
+        bump = valid_payment.bump,
+        constraint = valid_payment.enabled @ ErrorCode::MintNotEnabled,
+    )]
+    pub valid_payment: Box<Account<'info, ValidPayment>>,
+
+    /// CHECK: Required when valid_payment.price_feed is set, owner and layout checked when read
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Same pattern as VoteOnPost: the voter may sign directly or through a session
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct CommitVote<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: real user identity (owner of UserAccount and vaults)
+    #[account(mut)]
+    pub voter: UncheckedAccount<'info>,
+
+    /// CHECK: Signer paying the TX fee (user or backend)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: ephemeral delegated session key
+    #[account(mut)]
+    pub session_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SESSION_AUTHORITY_SEED, voter.key().as_ref(), session_key.key().as_ref()],
+        bump,
+    )]
+    pub session_authority: Account<'info, SessionAuthority>,
+
+    #[account(
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+        constraint = post.state == PostState::Open @ ErrorCode::PostNotOpen,
+    )]
+    pub post: Box<Account<'info, PostAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [VOTE_COMMITMENT_SEED, post.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + VoteCommitment::INIT_SPACE,
+    )]
+    pub vote_commitment: Box<Account<'info, VoteCommitment>>,
+
+    #[account(
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, voter.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+    )]
+    pub voter_user_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA derived from seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = post_pot_authority,
+    )]
+    pub post_pot_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Post pot authority PDA derived from seeds
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump = post.pot_authority_bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [VALID_PAYMENT_SEED, token_mint.key().as_ref()],
+        bump = valid_payment.bump,
+        constraint = valid_payment.enabled @ ErrorCode::MintNotEnabled,
+    )]
+    pub valid_payment: Box<Account<'info, ValidPayment>>,
+
+    /// CHECK: Required when valid_payment.price_feed is set, owner and layout checked when read
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// Permissionless: the salt in the args is what authorizes the reveal
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct RevealVote<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: the user wallet the commitment belongs to
+    pub voter: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+        constraint = post.state == PostState::Open @ ErrorCode::PostNotOpen,
+    )]
+    pub post: Box<Account<'info, PostAccount>>,
+
+    #[account(
+        mut,
+        seeds = [VOTE_COMMITMENT_SEED, post.key().as_ref(), voter.key().as_ref()],
+        bump = vote_commitment.bump,
+        constraint = vote_commitment.token_mint == token_mint.key() @ ErrorCode::InvalidReveal,
+        close = rent_payer,
+    )]
+    pub vote_commitment: Box<Account<'info, VoteCommitment>>,
+
+    /// CHECK: whoever funded the commitment account, receives the rent
+    #[account(
+        mut,
+        address = vote_commitment.rent_payer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [USER_ACCOUNT_SEED, voter.key().as_ref()],
+        bump,
+    )]
+    pub voter_user_account: Box<Account<'info, UserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [POSITION_SEED, post.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = 8 + UserPostPosition::INIT_SPACE,
+    )]
+    pub position: Box<Account<'info, UserPostPosition>>,
+
+    #[account(
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, voter.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+    )]
+    pub voter_user_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA derived from seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = post_pot_authority,
+    )]
+    pub post_pot_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Post pot authority PDA derived from seeds
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump = post.pot_authority_bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = config,
+    )]
+    pub protocol_token_treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, post.creator_user.as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+    )]
+    pub creator_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    // Not required to be enabled: the escrow is already in, it must still be revealable
+    #[account(
+        seeds = [VALID_PAYMENT_SEED, token_mint.key().as_ref()],
+        bump = valid_payment.bump,
+    )]
+    pub valid_payment: Box<Account<'info, ValidPayment>>,
+
+    /// CHECK: Required when valid_payment.price_feed is set, owner and layout checked when read
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct CloseVoteCommitment<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Anyone can close a commitment left unrevealed
+    pub payer: Signer<'info>,
+
+    /// CHECK: the user wallet the commitment belongs to
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+    )]
+    pub post: Account<'info, PostAccount>,
+
+    #[account(
+        mut,
+        seeds = [VOTE_COMMITMENT_SEED, post.key().as_ref(), user.key().as_ref()],
+        bump = vote_commitment.bump,
+        close = rent_payer,
+    )]
+    pub vote_commitment: Account<'info, VoteCommitment>,
+
+    /// CHECK: whoever funded the commitment account, receives the rent
+    #[account(
+        mut,
+        address = vote_commitment.rent_payer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct SettlePost<'info> {
+    /// CHECK: Payer for transaction fees
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+    )]
+    pub post: Account<'info, PostAccount>,
+
+    // creator's UserAccount, for the social score update
+    #[account(
+        mut,
+        seeds = [USER_ACCOUNT_SEED, post.creator_user.as_ref()],
+        bump,
+    )]
+    pub creator_user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = post_pot_token_account.mint == token_mint.key(),
+        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+    )]
+    pub post_pot_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Post pot authority PDA derived from seeds
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    // per-post per-mint “snapshot” payout
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        space = 8 + PostMintPayout::INIT_SPACE
+    )]
+    pub post_mint_payout: Account<'info, PostMintPayout>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = config,
+    )]
+    pub protocol_token_treasury_token_account: Account<'info, TokenAccount>,
+
+    // Parent post, required for child posts. Only its state is read here,
+    // the mother fee moves in distribute_parent_post_share
+    #[account(
+        constraint = post.parent() == Some(parent_post.key()) @ ErrorCode::InvalidParentPost,
+    )]
+    pub parent_post: Option<Account<'info, PostAccount>>,
+
+    pub config: Account<'info, Config>,
+    pub token_mint: Account<'info, Mint>,
+
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct DistributeCreatorReward<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Payer for transaction fees
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+    )]
+    pub post: Account<'info, PostAccount>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = post_pot_token_account.mint == token_mint.key(),
+        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+    )]
+    pub post_pot_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Post pot authority PDA
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump = post_mint_payout.bump,
+    )]
+    pub post_mint_payout: Account<'info, PostMintPayout>,
+
+    #[account(
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, post.creator_user.as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+    )]
+    pub creator_vault_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct DistributeProtocolFee<'info> {
+    /// CHECK: Payer for transaction fees
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+    )]
+    pub post: Account<'info, PostAccount>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = post_pot_token_account.mint == token_mint.key(),
+        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+    )]
+    pub post_pot_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Post pot authority PDA
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump = post_mint_payout.bump,
+    )]
+    pub post_mint_payout: Account<'info, PostMintPayout>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = config,
+    )]
+    pub protocol_token_treasury_token_account: Account<'info, TokenAccount>,
+
+    pub config: Account<'info, Config>,
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct DistributeParentPostShare<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Payer for transaction fees
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+    )]
+    pub post: Account<'info, PostAccount>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = post_pot_token_account.mint == token_mint.key(),
+        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+    )]
+    pub post_pot_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Post pot authority PDA
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump = post_mint_payout.bump,
+    )]
+    pub post_mint_payout: Account<'info, PostMintPayout>,
+
+    #[account(
+        constraint = post.parent() == Some(parent_post.key()) @ ErrorCode::InvalidParentPost,
+    )]
+    pub parent_post: Account<'info, PostAccount>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, parent_post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+    )]
+    pub parent_post_pot_token_account: Account<'info, TokenAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32], level: u8)]
+pub struct DistributeAncestorPostShare<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+    )]
+    pub post: Account<'info, PostAccount>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+    )]
+    pub post_pot_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Post pot authority PDA
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump = post.pot_authority_bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump = post_mint_payout.bump,
+    )]
+    pub post_mint_payout: Account<'info, PostMintPayout>,
+
+    #[account(
+        constraint = post.ancestor(level) == Some(ancestor_post.key()) @ ErrorCode::InvalidAncestorPost,
+    )]
+    pub ancestor_post: Account<'info, PostAccount>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, ancestor_post.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+    )]
+    pub ancestor_post_pot_token_account: Account<'info, TokenAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
+
+
+// The User-uncheckedAccount and payer-Signer pattern is used to allow for dual signing - so the user doesn't need to see a signature prompt pop-up
+
+#[derive(Accounts)]
+#[instruction( post_id_hash: [u8; 32])]
+pub struct ClaimPostReward<'info> {
+    #[account(mut,
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: User is marked as an UncheckedAccount so that it could be just a pubkey or a signer - allowing for dual signing - in the case where the user wants to directly interact with the program and not use our centralized payer, just pass its own keypair to payer and user as the same keypair.
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+     
+   /// CHECK: Signer paying the TX fee (user or backend)
+   #[account(
+    mut,
+    
+    )]
+    pub payer: Signer<'info>,
+
+
+    /// CHECK: ephemeral delegated session key
+    #[account(mut)]
+    pub session_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SESSION_AUTHORITY_SEED, user.key().as_ref(), session_key.key().as_ref()],
+        bump,
+    )]
+    pub session_authority: Account<'info, SessionAuthority>,
+
+
+    #[account(
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+    )]
+    pub post: Account<'info, PostAccount>,
+    #[account(
+        mut,
+        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    // The user's own position, since its votes move this user's social score.
+    // Created on claim for holders of shares of a tokenized post who never voted
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [POSITION_SEED, post.key().as_ref(), user.key().as_ref()],
+        bump,
+        space = 8 + UserPostPosition::INIT_SPACE,
+    )]
+    pub position: Account<'info, UserPostPosition>,
+    // One claim record per user: keyed by post and mint alone, the first claimant's record
+    // would mark the pot claimed for every other voter
+    #[account(
+        init_if_needed, 
+        payer = payer, 
+        seeds = [USER_POST_MINT_CLAIM_SEED, post.key().as_ref(), token_mint.key().as_ref(), user.key().as_ref()], bump, space = 8 + UserPostMintClaim::INIT_SPACE)]
+    pub user_post_mint_claim: Account<'info, UserPostMintClaim>,
+
+    #[account(
+        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        bump = post_mint_payout.bump,
+    )]
+    pub post_mint_payout: Account<'info, PostMintPayout>,
+
+    #[account(
+        mut,
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
         bump,
-        constraint = post_pot_token_account.mint == token_mint.key(),
         constraint = post_pot_token_account.owner == post_pot_authority.key(),
+        constraint = post_pot_token_account.mint == token_mint.key(),
+    )]
+    pub post_pot_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Post pot authority PDA derived from seeds
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Vault authority PDA derived from seeds
+    #[account(
+        seeds = [VAULT_AUTHORITY_SEED],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    // Always the user's own vault, a session key can't send the reward elsewhere
+    #[account(
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, user.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+    )]
+    pub user_vault_token_account: Account<'info, TokenAccount>,
+
+    // Tokenized posts only: the winning side's share mint and the user's shares, burnt on claim
+    #[account(mut)]
+    pub share_mint: Option<Box<Account<'info, Mint>>>,
+
+    #[account(
+        mut,
+        constraint = user_share_token_account.owner == user.key() @ ErrorCode::InvalidShareAccounts,
+    )]
+    pub user_share_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPostRewards<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Anyone can crank claims for a user; pays for claim accounts that don't exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Wallet whose rewards are claimed. Does not need to sign: rewards only move into its own vault.
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, user.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+    )]
+    pub user_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct SweepPostPot<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
     )]
-    pub post_pot_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+
+    /// Anyone can crank the sweep once the claim window has passed
+    pub payer: Signer<'info>,
 
-    /// CHECK: Post pot authority PDA derived from seeds
     #[account(
-        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
         bump,
     )]
-    pub post_pot_authority: UncheckedAccount<'info>,
+    pub post: Account<'info, PostAccount>,
 
-    // per-post per-mint “snapshot” payout
     #[account(
-        init_if_needed,
-        payer = payer,
+        mut,
         seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
-        bump,
-        space = 8 + PostMintPayout::INIT_SPACE
+        bump = post_mint_payout.bump,
+        close = payout_rent_payer,
     )]
     pub post_mint_payout: Account<'info, PostMintPayout>,
 
     #[account(
         mut,
-        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
+        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
         bump,
         token::mint = token_mint,
-        token::authority = config,
+        token::authority = post_pot_authority,
     )]
-    pub protocol_token_treasury_token_account: Account<'info, TokenAccount>,
+    pub post_pot_token_account: Account<'info, TokenAccount>,
 
-    // Optional parent post (if child)
-    pub parent_post: Option<Account<'info, PostAccount>>,
+    /// CHECK: Post pot authority PDA derived from seeds
+    #[account(
+        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        bump = post.pot_authority_bump,
+    )]
+    pub post_pot_authority: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
+        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
         bump,
-        constraint = post_pot_token_account.mint == token_mint.key(),
-        constraint = post_pot_token_account.owner == parent_post_pot_authority.key(),
+        token::mint = token_mint,
+        token::authority = config,
     )]
-    pub parent_post_pot_token_account: Option<Account<'info, TokenAccount>>,
+    pub protocol_token_treasury_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Post pot authority PDA derived from seeds
+    /// CHECK: Funded the pot token account, receives its rent
     #[account(
-            seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
-            bump,
-        )]
-    pub parent_post_pot_authority: UncheckedAccount<'info>,
+        mut,
+        constraint = post.pot_rent_payer(&token_mint.key()) == Some(pot_rent_payer.key()) @ ErrorCode::InvalidRentPayer,
+    )]
+    pub pot_rent_payer: UncheckedAccount<'info>,
 
-    pub config: Account<'info, Config>,
-    pub token_mint: Account<'info, Mint>,
+    /// CHECK: Funded the payout at settle_post, receives its rent
+    #[account(mut, address = post_mint_payout.rent_payer @ ErrorCode::InvalidRentPayer)]
+    pub payout_rent_payer: UncheckedAccount<'info>,
 
-    
+    pub token_mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(post_id_hash: [u8; 32])]
-pub struct DistributeCreatorReward<'info> {
-    /// CHECK: Payer for transaction fees
-    #[account(mut)]
+pub struct CloseUserPostMintClaim<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Anyone can close a claim that has been made
     pub payer: Signer<'info>,
 
+    /// CHECK: the user wallet the claim belongs to
+    pub user: UncheckedAccount<'info>,
+
     #[account(
         seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
         bump,
@@ -532,69 +1698,130 @@ pub struct DistributeCreatorReward<'info> {
 
     #[account(
         mut,
-        seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
-        bump,
-        constraint = post_pot_token_account.mint == token_mint.key(),
-        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+        seeds = [USER_POST_MINT_CLAIM_SEED, post.key().as_ref(), token_mint.key().as_ref(), user.key().as_ref()],
+        bump = user_post_mint_claim.bump,
+        close = rent_payer,
     )]
-    pub post_pot_token_account: Account<'info, TokenAccount>,
+    pub user_post_mint_claim: Account<'info, UserPostMintClaim>,
 
-    /// CHECK: Post pot authority PDA
+    /// CHECK: whoever funded the claim account, receives the rent
     #[account(
-        seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
+        mut,
+        address = user_post_mint_claim.rent_payer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    pub token_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct CloseUserPostPosition<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Anyone can close a position with nothing left to claim
+    pub payer: Signer<'info>,
+
+    /// CHECK: the user wallet the position belongs to
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
         bump,
     )]
-    pub post_pot_authority: UncheckedAccount<'info>,
+    pub post: Account<'info, PostAccount>,
 
+    // Takes the social score of a position that never claimed, so losing smackers can't
+    // dodge their penalty by not claiming
     #[account(
-        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
-        bump = post_mint_payout.bump,
+        mut,
+        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        bump = user_account.bump,
     )]
-    pub post_mint_payout: Account<'info, PostMintPayout>,
+    pub user_account: Account<'info, UserAccount>,
 
     #[account(
         mut,
-        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, post.creator_user.as_ref(), token_mint.key().as_ref()],
+        seeds = [POSITION_SEED, post.key().as_ref(), user.key().as_ref()],
         bump,
-        token::mint = token_mint,
-        token::authority = vault_authority,
+        close = rent_payer,
     )]
-    pub creator_vault_token_account: Account<'info, TokenAccount>,
+    pub position: Account<'info, UserPostPosition>,
 
-    /// CHECK: Vault authority PDA
+    /// CHECK: whoever funded the position account, receives the rent
     #[account(
-        seeds = [VAULT_AUTHORITY_SEED],
-        bump,
+        mut,
+        address = position.rent_payer,
     )]
-    pub vault_authority: UncheckedAccount<'info>,
+    pub rent_payer: UncheckedAccount<'info>,
+}
 
-    pub token_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
+#[derive(Accounts)]
+#[instruction(post_id_hash: [u8; 32])]
+pub struct CancelPost<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
+        bump,
+        constraint = post.state == PostState::Open @ ErrorCode::PostNotOpen,
+    )]
+    pub post: Account<'info, PostAccount>,
 }
 
 #[derive(Accounts)]
 #[instruction(post_id_hash: [u8; 32])]
-pub struct DistributeProtocolFee<'info> {
-    /// CHECK: Payer for transaction fees
-    #[account(mut)]
+pub struct RefundPosition<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Anyone can crank a refund, it only ever lands in the user's vault
     pub payer: Signer<'info>,
 
+    /// CHECK: the user wallet the position belongs to; signs to burn shares of a tokenized post
+    pub user: UncheckedAccount<'info>,
+
     #[account(
+        mut,
         seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
         bump,
+        constraint = post.state == PostState::Cancelled @ ErrorCode::PostNotCancelled,
     )]
     pub post: Account<'info, PostAccount>,
 
+    // Untokenized posts only: the position whose contribution is refunded
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, post.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub position: Option<Box<Account<'info, UserPostPosition>>>,
+
     #[account(
         mut,
         seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
         bump,
-        constraint = post_pot_token_account.mint == token_mint.key(),
-        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+        token::mint = token_mint,
+        token::authority = post_pot_authority,
     )]
     pub post_pot_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Post pot authority PDA
+    /// CHECK: Post pot authority PDA derived from seeds
     #[account(
         seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
         bump,
@@ -602,48 +1829,76 @@ pub struct DistributeProtocolFee<'info> {
     pub post_pot_authority: UncheckedAccount<'info>,
 
     #[account(
-        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
-        bump = post_mint_payout.bump,
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, user.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
     )]
-    pub post_mint_payout: Account<'info, PostMintPayout>,
+    pub user_vault_token_account: Account<'info, TokenAccount>,
+
+    // Tokenized posts only: a share mint of the post and the user's shares, burnt on refund
+    #[account(mut)]
+    pub share_mint: Option<Box<Account<'info, Mint>>>,
 
     #[account(
         mut,
-        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
-        bump,
-        token::mint = token_mint,
-        token::authority = config,
+        constraint = user_share_token_account.owner == user.key() @ ErrorCode::InvalidShareAccounts,
     )]
-    pub protocol_token_treasury_token_account: Account<'info, TokenAccount>,
+    pub user_share_token_account: Option<Box<Account<'info, TokenAccount>>>,
 
-    pub config: Account<'info, Config>,
     pub token_mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
 #[instruction(post_id_hash: [u8; 32])]
-pub struct DistributeParentPostShare<'info> {
-    /// CHECK: Payer for transaction fees
-    #[account(mut)]
+pub struct RefundVoteCommitment<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Anyone can crank a refund, it only ever lands in the user's vault
     pub payer: Signer<'info>,
 
+    /// CHECK: the user wallet the commitment belongs to
+    pub user: UncheckedAccount<'info>,
+
     #[account(
+        mut,
         seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
         bump,
+        constraint = post.state == PostState::Cancelled @ ErrorCode::PostNotCancelled,
     )]
     pub post: Account<'info, PostAccount>,
 
+    #[account(
+        mut,
+        seeds = [VOTE_COMMITMENT_SEED, post.key().as_ref(), user.key().as_ref()],
+        bump = vote_commitment.bump,
+        constraint = vote_commitment.token_mint == token_mint.key() @ ErrorCode::InvalidReveal,
+        close = rent_payer,
+    )]
+    pub vote_commitment: Account<'info, VoteCommitment>,
+
+    /// CHECK: whoever funded the commitment account, receives the rent
+    #[account(
+        mut,
+        address = vote_commitment.rent_payer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
     #[account(
         mut,
         seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
         bump,
-        constraint = post_pot_token_account.mint == token_mint.key(),
-        constraint = post_pot_token_account.owner == post_pot_authority.key(),
+        token::mint = token_mint,
+        token::authority = post_pot_authority,
     )]
     pub post_pot_token_account: Account<'info, TokenAccount>,
 
-    /// CHECK: Post pot authority PDA
+    /// CHECK: Post pot authority PDA derived from seeds
     #[account(
         seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
         bump,
@@ -651,101 +1906,71 @@ pub struct DistributeParentPostShare<'info> {
     pub post_pot_authority: UncheckedAccount<'info>,
 
     #[account(
-        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
-        bump = post_mint_payout.bump,
+        mut,
+        seeds = [USER_VAULT_TOKEN_ACCOUNT_SEED, user.key().as_ref(), token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
     )]
-    pub post_mint_payout: Account<'info, PostMintPayout>,
-
-    // Optional parent post (must be provided if this is a child post)
-    pub parent_post: Option<Account<'info, PostAccount>>,
-
-    /// CHECK: Parent post pot token account (optional, only needed if parent_post is Some)
-    pub parent_post_pot_token_account: Option<Account<'info, TokenAccount>>,
-
-    /// CHECK: Parent post pot authority PDA (optional, only needed if parent_post is Some)
-    pub parent_post_pot_authority: Option<UncheckedAccount<'info>>,
+    pub user_vault_token_account: Account<'info, TokenAccount>,
 
     pub token_mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
 }
 
-
-// The User-uncheckedAccount and payer-Signer pattern is used to allow for dual signing - so the user doesn't need to see a signature prompt pop-up
-
 #[derive(Accounts)]
-#[instruction( post_id_hash: [u8; 32])]
-pub struct ClaimPostReward<'info> {
-    #[account(mut,
+#[instruction(post_id_hash: [u8; 32])]
+pub struct SweepCancelledPostPot<'info> {
+    #[account(
         seeds = [CONFIG_SEED],
-        bump,
+        bump = config.bump,
     )]
     pub config: Account<'info, Config>,
-    /// CHECK: User is marked as an UncheckedAccount so that it could be just a pubkey or a signer - allowing for dual signing - in the case where the user wants to directly interact with the program and not use our centralized payer, just pass its own keypair to payer and user as the same keypair.
-    #[account(mut)]
-    pub user: UncheckedAccount<'info>,
-     
-   /// CHECK: Signer paying the TX fee (user or backend)
-   #[account(
-    mut,
-    
-    )]
-    pub payer: Signer<'info>,
-
-
-    /// CHECK: ephemeral delegated session key
-    #[account(mut)]
-    pub session_key: UncheckedAccount<'info>,
-
-    #[account(
-        mut,
-        seeds = [SESSION_AUTHORITY_SEED, user.key().as_ref(), session_key.key().as_ref()],
-        bump,
-    )]
-    pub session_authority: Account<'info, SessionAuthority>,
 
+    /// Anyone can crank the sweep once the refund deadline has passed
+    pub payer: Signer<'info>,
 
     #[account(
         mut,
         seeds = [POST_ACCOUNT_SEED, post_id_hash.as_ref()],
         bump,
+        constraint = post.state == PostState::Cancelled @ ErrorCode::PostNotCancelled,
     )]
     pub post: Account<'info, PostAccount>,
-    #[account(mut)]
-    pub position: Account<'info, UserPostPosition>,
-    #[account(
-        init_if_needed, 
-        payer = payer, 
-        seeds = [USER_POST_MINT_CLAIM_SEED, post.key().as_ref(), token_mint.key().as_ref()], bump, space = 8 + UserPostMintClaim::INIT_SPACE)]
-    pub user_post_mint_claim: Account<'info, UserPostMintClaim>,
-
-    #[account(
-        seeds = [POST_MINT_PAYOUT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
-        bump = post_mint_payout.bump,
-    )]
-    pub post_mint_payout: Account<'info, PostMintPayout>,
 
     #[account(
         mut,
         seeds = [POST_POT_TOKEN_ACCOUNT_SEED, post.key().as_ref(), token_mint.key().as_ref()],
         bump,
-        constraint = post_pot_token_account.owner == post_pot_authority.key(),
-        constraint = post_pot_token_account.mint == token_mint.key(),
+        token::mint = token_mint,
+        token::authority = post_pot_authority,
     )]
     pub post_pot_token_account: Account<'info, TokenAccount>,
 
     /// CHECK: Post pot authority PDA derived from seeds
     #[account(
         seeds = [POST_POT_AUTHORITY_SEED, post.key().as_ref()],
-        bump,
+        bump = post.pot_authority_bump,
     )]
     pub post_pot_authority: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub user_vault_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = config,
+    )]
+    pub protocol_token_treasury_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Funded the pot token account, receives its rent
+    #[account(
+        mut,
+        constraint = post.pot_rent_payer(&token_mint.key()) == Some(pot_rent_payer.key()) @ ErrorCode::InvalidRentPayer,
+    )]
+    pub pot_rent_payer: UncheckedAccount<'info>,
 
     pub token_mint: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
 }
 
 // -----------------------------------------------------------------------------
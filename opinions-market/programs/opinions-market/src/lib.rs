@@ -2,12 +2,15 @@ use crate::pda_seeds::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::pubkey::Pubkey;
 pub mod constants;
+pub mod events;
 pub mod instructions;
 pub mod math;
 pub mod middleware;
+pub mod oracle;
 pub mod pda_seeds;
 pub mod state;
 use constants::*;
+use events::*;
 use instructions::*;
 use state::*;
 
@@ -49,6 +52,88 @@ pub enum ErrorCode {
     SessionExpired,
     #[msg("Unauthorized signer")]
     UnauthorizedSigner,
+    #[msg("Invalid duration configuration")]
+    InvalidDuration,
+    #[msg("Fee configuration out of bounds")]
+    InvalidFeeBps,
+    #[msg("Invalid price: must be non-zero, and BLING is always priced at 1")]
+    InvalidPrice,
+    #[msg("Price feed account missing or not the one set for the payment mint")]
+    MissingPriceFeed,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Price feed confidence interval too wide")]
+    PriceConfidenceTooWide,
+    #[msg(
+        "Claim accounts must come in (post, position, payout, pot, pot authority, claim) groups"
+    )]
+    InvalidClaimAccounts,
+    #[msg("Claim window is still open")]
+    ClaimWindowOpen,
+    #[msg("Claim window has closed")]
+    ClaimWindowClosed,
+    #[msg("Post already holds pots in the maximum number of mints")]
+    TooManyPotMints,
+    #[msg("Post has no pot in this mint")]
+    UnknownPotMint,
+    #[msg("Position still has rewards to claim or the post is not fully settled")]
+    PositionNotFullyClaimed,
+    #[msg("Claim has not been made yet")]
+    ClaimNotMade,
+    #[msg("Vote cost exceeds max_cost_in_token")]
+    SlippageExceeded,
+    #[msg("Allowed curves must be a non-empty set of known curves")]
+    InvalidAllowedCurves,
+    #[msg("Curve is not allowed by the config")]
+    CurveNotAllowed,
+    #[msg("Instruction does not match the post's vote mode")]
+    WrongVoteMode,
+    #[msg("Reveal window is not open")]
+    RevealWindowNotOpen,
+    #[msg("Revealed vote does not match the commitment")]
+    InvalidReveal,
+    #[msg("Escrow does not cover the cost of the revealed vote")]
+    EscrowTooLow,
+    #[msg("Commitment can still be revealed")]
+    RevealWindowOpen,
+    #[msg("Position does not hold that many votes on this side")]
+    InsufficientPositionVotes,
+    #[msg("Exit refund is below min_refund_in_token")]
+    RefundBelowMinimum,
+    #[msg("Post pot in this mint cannot cover the refund")]
+    InsufficientPot,
+    #[msg("Tokenized posts hold their pot in a single mint")]
+    TokenizedPostSingleMint,
+    #[msg("Share mint or share token account missing or does not match the post")]
+    InvalidShareAccounts,
+    #[msg("Shares can only be burnt with the holder's signature")]
+    ShareHolderMustSign,
+    #[msg("Positions on tokenized posts are held as shares")]
+    PositionTokenized,
+    #[msg("Shares of a tokenized post are paid per vote, not by stake")]
+    TokenizedPostStakeWeighted,
+    #[msg("Post has been cancelled")]
+    PostCancelled,
+    #[msg("Post is not cancelled")]
+    PostNotCancelled,
+    #[msg("Nothing to refund in this mint")]
+    NothingToRefund,
+    #[msg("This instruction is paused by the admin")]
+    Paused,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("Invalid payer authority")]
+    InvalidPayerAuthority,
+    #[msg("Ancestor post missing or not at this level of the reply tree")]
+    InvalidAncestorPost,
+    #[msg("Creator, protocol and mother fees must be distributed before the pot is swept")]
+    FeesNotDistributed,
+    #[msg("Price feed is not a fully verified Pyth price update")]
+    InvalidPriceFeed,
+    #[msg("Rent must go back to the account that funded it")]
+    InvalidRentPayer,
 }
 #[derive(Accounts)]
 pub struct Ping {}
@@ -58,9 +143,15 @@ pub mod opinions_market {
 
     use anchor_lang::solana_program::{ed25519_program, program::invoke};
 
-    use crate::middleware::session::{assert_session_or_wallet, validate_session_signature};
+    use crate::middleware::session::{
+        assert_session_or_wallet, assert_session_privileges, assert_wallet_authorized,
+        rotate_session_message, session_message, validate_session_signature, SessionError,
+        SESSION_ACTION_REGISTER, SESSION_ACTION_REVOKE,
+    };
     use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
 
+    use crate::math::token_conversion::convert_token_to_bling_lamports;
+
     use super::*;
     // Don't import from instructions module - use re-exports from crate root
     pub fn ping(ctx: Context<Ping>) -> Result<()> {
@@ -89,6 +180,7 @@ pub mod opinions_market {
         );
 
         cfg.admin = new_cfg.admin;
+        cfg.pending_admin = new_cfg.pending_admin;
         cfg.payer_authroity = new_cfg.payer_authroity;
         cfg.bling_mint = new_cfg.bling_mint;
 
@@ -96,9 +188,26 @@ pub mod opinions_market {
         cfg.max_duration_secs = new_cfg.max_duration_secs;
         cfg.extension_per_vote_secs = new_cfg.extension_per_vote_secs;
 
+        cfg.protocol_vote_fee_bps = new_cfg.protocol_vote_fee_bps;
+        cfg.protocol_vote_settlement_fee_bps = new_cfg.protocol_vote_settlement_fee_bps;
+        cfg.creator_pump_fee_bps = new_cfg.creator_pump_fee_bps;
+        cfg.creator_pump_win_settlement_fee_bps = new_cfg.creator_pump_win_settlement_fee_bps;
+        cfg.exit_fee_bps = new_cfg.exit_fee_bps;
+        cfg.mother_fee_bps = new_cfg.mother_fee_bps;
+        cfg.mother_fee_decay_bps = new_cfg.mother_fee_decay_bps;
+        cfg.mother_fee_levels = new_cfg.mother_fee_levels;
+        cfg.bling_per_vote_base_cost = new_cfg.bling_per_vote_base_cost;
+        cfg.user_initial_social_score = new_cfg.user_initial_social_score;
+        cfg.claim_window_secs = new_cfg.claim_window_secs;
+        cfg.reveal_window_secs = new_cfg.reveal_window_secs;
+        cfg.allowed_curves = new_cfg.allowed_curves;
+        cfg.paused = new_cfg.paused;
+
         cfg.bump = new_cfg.bump;
         cfg.padding = new_cfg.padding;
 
+        cfg.validate()?;
+
         let valid_payment = &mut ctx.accounts.valid_payment;
 
         let new_valid_payment = ValidPayment::new(ctx.accounts.bling_mint.key(), 1, true);
@@ -111,6 +220,97 @@ pub mod opinions_market {
         Ok(())
     }
 
+    /// Admin-only. Changes durations and the fee schedule without a redeploy.
+    /// Only the fields set in `params` are touched; the result must pass `Config::validate`.
+    pub fn update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+        let cfg = &mut ctx.accounts.config;
+
+        let mut new_cfg = **cfg;
+        new_cfg.apply_update(&params);
+        new_cfg.validate()?;
+
+        cfg.set_inner(new_cfg);
+
+        Ok(())
+    }
+
+    /// Admin-only emergency stop. `paused` replaces the whole PAUSE_* bitmap; 0 resumes
+    /// everything. Withdrawals have their own bit, so users can exit while the rest is stopped.
+    pub fn set_pause(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+        require!(paused & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+
+        ctx.accounts.config.paused = paused;
+        msg!("⏸ Pause flags set to {:#07b}", paused);
+
+        emit!(PauseSet {
+            admin: ctx.accounts.admin.key(),
+            paused,
+        });
+
+        Ok(())
+    }
+
+    /// Admin-only, first half of an admin transfer. The new admin takes over once it signs
+    /// accept_admin; proposing `Pubkey::default()` withdraws a pending proposal.
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let cfg = &mut ctx.accounts.config;
+        cfg.pending_admin = new_admin;
+        msg!("👑 Admin transfer proposed: {} -> {}", cfg.admin, new_admin);
+
+        emit!(AdminProposed {
+            admin: cfg.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+
+    /// Signed by the pending admin, so a transfer can never land on a key nobody controls.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let cfg = &mut ctx.accounts.config;
+        let previous_admin = cfg.admin;
+
+        cfg.admin = ctx.accounts.new_admin.key();
+        cfg.pending_admin = Pubkey::default();
+        msg!("👑 Admin transferred: {} -> {}", previous_admin, cfg.admin);
+
+        emit!(AdminTransferred {
+            previous_admin,
+            admin: cfg.admin,
+        });
+
+        Ok(())
+    }
+
+    /// Admin-only. Rotates the backend payer; rent of accounts the old payer funded still goes
+    /// back to it, since every account (and every pot, on its post) records who funded it.
+    pub fn set_payer_authority(
+        ctx: Context<SetPayerAuthority>,
+        payer_authority: Pubkey,
+    ) -> Result<()> {
+        require!(
+            payer_authority != Pubkey::default(),
+            ErrorCode::InvalidPayerAuthority
+        );
+
+        let cfg = &mut ctx.accounts.config;
+        let previous_payer_authority = cfg.payer_authroity;
+        cfg.payer_authroity = payer_authority;
+        msg!(
+            "💳 Payer authority rotated: {} -> {}",
+            previous_payer_authority,
+            payer_authority
+        );
+
+        emit!(PayerAuthoritySet {
+            admin: ctx.accounts.admin.key(),
+            previous_payer_authority,
+            payer_authority,
+        });
+
+        Ok(())
+    }
+
     pub fn register_valid_payment(
         ctx: Context<RegisterValidPayment>,
         price_in_bling: u64, // How much is 1 token in BLING -
@@ -132,6 +332,114 @@ pub mod opinions_market {
         Ok(())
     }
 
+    /// Admin-only. Enables/disables a payment mint and optionally reprices it.
+    /// Disabled mints are rejected by `deposit` and `vote_on_post`; withdrawals and claims still work.
+    pub fn modify_accepted_mint(
+        ctx: Context<ModifyAcceptedMint>,
+        enabled: bool,
+        price_in_bling: Option<u64>,
+    ) -> Result<()> {
+        let cfg = &ctx.accounts.config;
+        let accepted_mint = &mut ctx.accounts.accepted_mint;
+
+        if let Some(price_in_bling) = price_in_bling {
+            require!(price_in_bling > 0, ErrorCode::InvalidPrice);
+            // BLING is the unit of account, its price is fixed at 1
+            require!(
+                accepted_mint.token_mint != cfg.bling_mint || price_in_bling == 1,
+                ErrorCode::InvalidPrice
+            );
+            accepted_mint.price_in_bling = price_in_bling;
+        }
+
+        accepted_mint.enabled = enabled;
+
+        msg!(
+            "ValidPayment {} updated: enabled={}, price_in_bling={}",
+            accepted_mint.token_mint,
+            accepted_mint.enabled,
+            accepted_mint.price_in_bling
+        );
+
+        Ok(())
+    }
+
+    /// Admin-only. Attaches a Pyth price feed account to a payment mint, or detaches it (`None`)
+    /// so conversions fall back to the static `price_in_bling`. Votes then read the feed,
+    /// refusing a price older than `max_staleness_secs` or with a confidence interval wider
+    /// than `max_confidence_bps`, and convert it at `bling_per_feed_unit` BLING per USD.
+    pub fn set_price_feed(
+        ctx: Context<SetPriceFeed>,
+        max_staleness_secs: u32,
+        max_confidence_bps: u16,
+        bling_per_feed_unit: u64,
+    ) -> Result<()> {
+        let valid_payment = &mut ctx.accounts.valid_payment;
+
+        let Some(feed) = ctx.accounts.price_feed.as_ref() else {
+            valid_payment.price_feed = None;
+            msg!("Price feed detached from {}", valid_payment.token_mint);
+            return Ok(());
+        };
+
+        require!(max_staleness_secs > 0, ErrorCode::StalePrice);
+        require!(
+            max_confidence_bps <= MAX_BPS,
+            ErrorCode::PriceConfidenceTooWide
+        );
+        require!(bling_per_feed_unit > 0, ErrorCode::InvalidPrice);
+        // Only the owner and layout here, the price itself is checked when a vote reads it
+        oracle::FeedPrice::read(feed.owner, &feed.try_borrow_data()?)?;
+
+        valid_payment.price_feed = Some(feed.key());
+        valid_payment.max_staleness_secs = max_staleness_secs;
+        valid_payment.max_confidence_bps = max_confidence_bps;
+        valid_payment.bling_per_feed_unit = bling_per_feed_unit;
+
+        msg!(
+            "Price feed {} attached to {}",
+            feed.key(),
+            valid_payment.token_mint
+        );
+
+        Ok(())
+    }
+
+    /// Admin-only, `pushed-price-feed` builds. Creates the PushedPriceFeed of a payment mint
+    /// or hands it to a new authority. Attach it with set_price_feed like a Pyth account.
+    #[cfg(feature = "pushed-price-feed")]
+    pub fn set_pushed_price_feed(
+        ctx: Context<SetPushedPriceFeed>,
+        feed_authority: Pubkey,
+    ) -> Result<()> {
+        let feed = &mut ctx.accounts.pushed_price_feed;
+        feed.token_mint = ctx.accounts.token_mint.key();
+        feed.authority = feed_authority;
+        feed.bump = ctx.bumps.pushed_price_feed;
+
+        Ok(())
+    }
+
+    /// Signed by the feed authority, `pushed-price-feed` builds. Publishes `price * 10^exponent`
+    /// USD per token with the current clock as publish time.
+    #[cfg(feature = "pushed-price-feed")]
+    pub fn update_pushed_price_feed(
+        ctx: Context<UpdatePushedPriceFeed>,
+        price: i64,
+        conf: u64,
+        exponent: i32,
+    ) -> Result<()> {
+        require!(price > 0, ErrorCode::InvalidPrice);
+
+        let feed = &mut ctx.accounts.pushed_price_feed;
+        feed.price = price;
+        feed.conf = conf;
+        feed.exponent = exponent;
+        feed.publish_time = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
     // -------------------------------------------------------------------------
     // USER + VAULTS
     // -------------------------------------------------------------------------
@@ -140,17 +448,34 @@ pub mod opinions_market {
     pub fn create_user(ctx: Context<CreateUser>) -> Result<()> {
         let config = &mut ctx.accounts.config;
         let user_account = &mut ctx.accounts.user_account;
-        let new_user_account = UserAccount::new(ctx.accounts.user.key(), ctx.bumps.user_account);
+        let new_user_account = UserAccount::new(
+            ctx.accounts.user.key(),
+            config.user_initial_social_score,
+            ctx.bumps.user_account,
+        );
 
         user_account.user = new_user_account.user;
         user_account.social_score = new_user_account.social_score;
         user_account.bump = new_user_account.bump;
+        user_account.session_nonce = new_user_account.session_nonce;
+
+        emit!(UserCreated {
+            user: user_account.user,
+            user_account: user_account.key(),
+            social_score: user_account.social_score,
+        });
 
         Ok(())
     }
 
     // to be called only after create_user
-    pub fn register_session(ctx: Context<RegisterSession>, expected_index: u8) -> Result<()> {
+    /// Registers (or re-registers) a session key with the given privileges.
+    /// Re-registering replaces the privileges; the spend counter resets only with a new expiry.
+    pub fn register_session(
+        ctx: Context<RegisterSession>,
+        expected_index: u8,
+        privileges: SessionPrivileges,
+    ) -> Result<()> {
         // // ---- Load Ed25519 verify instruction from tx instruction list ----
         // let ix = load_instruction_at_checked(
         //     expected_index as usize,
@@ -184,33 +509,140 @@ pub mod opinions_market {
         //     ErrorCode::UnauthorizedSigner
         // );
 
-        let now = Clock::get()?.unix_timestamp;
-        let expires_at = now + 60 * 60 * 24 * 30; // 30 days
-
-        validate_session_signature(
+        // The user signs the session key, expiry and privileges, so none of them can be
+        // swapped by whoever relays the transaction. The nonce makes the signature single-use.
+        let expires_at = validate_session_signature(
             &ctx.accounts.user.key(),
             expected_index,
             &ctx.accounts.instructions_sysvar,
-            expires_at,
+            |expires_at| {
+                session_message(
+                    SESSION_ACTION_REGISTER,
+                    &crate::ID,
+                    &ctx.accounts.session_key.key(),
+                    expires_at,
+                    &privileges.hash(),
+                    ctx.accounts.user_account.session_nonce,
+                )
+            },
         )?;
+        ctx.accounts.user_account.bump_session_nonce()?;
 
         // ---- Check expiry ----
-        // let now = Clock::get()?.unix_timestamp;
-        // require!(expires_at > now, ErrorCode::SessionExpired);
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            expires_at > now && expires_at <= now + MAX_SESSION_DURATION_SECS,
+            SessionError::InvalidSessionExpiry
+        );
 
         // ---- Initialize SessionAuthority PDA ----
         let session = &mut ctx.accounts.session_authority;
+        // Re-registering the same key and expiry keeps the spend so far, so replaying the
+        // REGISTER message cannot reset the spend cap
+        if session.expires_at != expires_at {
+            session.bling_spent = 0;
+        }
         session.user = ctx.accounts.user.key();
         session.session_key = ctx.accounts.session_key.key();
         session.expires_at = expires_at;
-        session.privileges_hash = [0u8; 32];
+        session.privileges_hash = privileges.hash();
         session.bump = ctx.bumps.session_authority;
+        session.privileges = privileges;
+        if session.rent_payer == Pubkey::default() {
+            session.rent_payer = ctx.accounts.payer.key();
+        }
+
+        emit!(SessionRegistered {
+            user: session.user,
+            session_key: session.session_key,
+            session_authority: session.key(),
+            expires_at: session.expires_at,
+            privileges_hash: session.privileges_hash,
+            allowed_instructions: session.privileges.allowed_instructions,
+            max_bling_spend: session.privileges.max_bling_spend,
+        });
+
+        Ok(())
+    }
+
+    /// Revokes a session before it expires. Closes the SessionAuthority and returns its rent.
+    /// Signed by the user's wallet, either on the transaction or through an Ed25519 verify
+    /// instruction at `expected_index` (for relayed calls).
+    pub fn revoke_session(ctx: Context<RevokeSession>, expected_index: Option<u8>) -> Result<()> {
+        let session = &ctx.accounts.session_authority;
+
+        assert_wallet_authorized(
+            &ctx.accounts.user,
+            expected_index,
+            &ctx.accounts.instructions_sysvar,
+            session_message(
+                SESSION_ACTION_REVOKE,
+                &crate::ID,
+                &session.session_key,
+                session.expires_at,
+                &session.privileges_hash,
+                ctx.accounts.user_account.session_nonce,
+            ),
+        )?;
+        // Invalidates every message signed before the revoke, REGISTER included
+        ctx.accounts.user_account.bump_session_nonce()?;
+
+        msg!(
+            "Session {} revoked for {}",
+            session.session_key,
+            session.user
+        );
+
+        Ok(())
+    }
+
+    /// Swaps the session key in one go: closes the old SessionAuthority and opens one for
+    /// `new_session_key` with the same privileges, expiry and spend so far.
+    pub fn rotate_session(ctx: Context<RotateSession>, expected_index: Option<u8>) -> Result<()> {
+        let old_session = &ctx.accounts.old_session_authority;
+
+        // Names the old key too, so the signature only rotates this session
+        assert_wallet_authorized(
+            &ctx.accounts.user,
+            expected_index,
+            &ctx.accounts.instructions_sysvar,
+            rotate_session_message(
+                &crate::ID,
+                &old_session.session_key,
+                &ctx.accounts.new_session_key.key(),
+                old_session.expires_at,
+                &old_session.privileges_hash,
+                ctx.accounts.user_account.session_nonce,
+            ),
+        )?;
+        ctx.accounts.user_account.bump_session_nonce()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < old_session.expires_at, SessionError::SessionExpired);
+
+        let new_session = &mut ctx.accounts.new_session_authority;
+        new_session.user = old_session.user;
+        new_session.session_key = ctx.accounts.new_session_key.key();
+        new_session.expires_at = old_session.expires_at;
+        new_session.privileges_hash = old_session.privileges_hash;
+        new_session.bump = ctx.bumps.new_session_authority;
+        new_session.privileges = old_session.privileges;
+        new_session.bling_spent = old_session.bling_spent;
+        new_session.rent_payer = ctx.accounts.payer.key();
+
+        msg!(
+            "Session rotated for {}: {} -> {}",
+            new_session.user,
+            old_session.session_key,
+            new_session.session_key
+        );
 
         Ok(())
     }
 
     /// User deposits from their wallet into the program-controlled vault.
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_DEPOSITS)?;
         // No logic needed—Anchor already checked mint is allowed.
         let cpi_accounts = anchor_spl::token::Transfer {
             from: ctx.accounts.user_token_ata.to_account_info(),
@@ -219,15 +651,23 @@ pub mod opinions_market {
         };
         let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
         anchor_spl::token::transfer(cpi_ctx, amount)?;
+
+        emit!(Deposited {
+            user: ctx.accounts.user.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            amount,
+        });
         Ok(())
     }
 
-    /// Withdraw with possible penalty based on social interactions.
-    /// You can later implement:
+    /// Withdraw with a penalty based on social score:
     ///   effective_amount = amount * (10000 - user.withdraw_penalty_bps()) / 10000
+    /// The penalty goes to the protocol treasury for this mint.
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        // later you’ll put the social penalty logic here
-        let effective_amount = amount;
+        ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+        let user_account = &ctx.accounts.user_account;
+        let penalty_bps = user_account.withdraw_penalty_bps();
+        let (effective_amount, penalty) = user_account.split_withdrawal(amount)?;
 
         let vault_bump = ctx.bumps.vault_authority;
         let seeds: &[&[&[u8]]] = &[&[VAULT_AUTHORITY_SEED, &[vault_bump]]];
@@ -243,6 +683,42 @@ pub mod opinions_market {
             seeds,
         );
         anchor_spl::token::transfer(cpi_ctx, effective_amount)?;
+
+        if penalty > 0 {
+            let cpi_accounts = anchor_spl::token::Transfer {
+                from: ctx.accounts.user_vault_token_account.to_account_info(),
+                to: ctx
+                    .accounts
+                    .protocol_token_treasury_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                seeds,
+            );
+            anchor_spl::token::transfer(cpi_ctx, penalty)?;
+        }
+
+        msg!(
+            "💸 Withdrew {} ({} penalty at {} bps, social score {})",
+            effective_amount,
+            penalty,
+            penalty_bps,
+            user_account.social_score
+        );
+
+        emit!(Withdrawn {
+            user: ctx.accounts.user.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            amount,
+            effective_amount,
+            penalty,
+            penalty_bps,
+            social_score: user_account.social_score,
+        });
+
         Ok(())
     }
 
@@ -253,19 +729,61 @@ pub mod opinions_market {
     pub fn create_post(
         ctx: Context<CreatePost>,
         post_id_hash: [u8; 32],
-        parent_post_pda: Option<Pubkey>,
+        curve: CurveKind,
+        vote_mode: VoteMode,
+        tokenized: bool, // positions held as Pump/Smack share tokens instead of in UserPostPosition
+        payout_weighting: PayoutWeighting,
     ) -> Result<()> {
+        ctx.accounts
+            .config
+            .require_not_paused(PAUSE_POST_CREATION)?;
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
 
         assert_session_or_wallet(
-            &ctx.accounts.user.key(),
-            &ctx.accounts.session_authority.user,
+            &ctx.accounts.user,
+            &ctx.accounts.session_key,
             Some(&ctx.accounts.session_authority),
             now,
         )?;
+        assert_session_privileges(
+            ctx.accounts.user.is_signer,
+            &mut ctx.accounts.session_authority,
+            SESSION_PRIVILEGE_CREATE_POST,
+            None,
+            0,
+        )?;
 
         let config = &ctx.accounts.config;
+        require!(config.allows_curve(curve), ErrorCode::CurveNotAllowed);
+        // Hidden votes can't be minted as shares until they are revealed
+        require!(
+            !tokenized || vote_mode == VoteMode::Open,
+            ErrorCode::WrongVoteMode
+        );
+        // Shares change hands, what their first holder paid doesn't follow them
+        require!(
+            !tokenized || payout_weighting == PayoutWeighting::PerVote,
+            ErrorCode::TokenizedPostStakeWeighted
+        );
+
+        // The parent is a live PostAccount of this program, so settle_post can always load it
+        let lineage = match ctx.accounts.parent_post.as_mut() {
+            Some(parent) => {
+                parent.child_count = parent
+                    .child_count
+                    .checked_add(1)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                Some((
+                    parent.key(),
+                    parent.depth.saturating_add(1),
+                    parent.child_ancestors(parent.key()),
+                ))
+            }
+            None => None,
+        };
+        let parent_post_pda = lineage.map(|(parent, _, _)| parent);
+
         let post = &mut ctx.accounts.post;
         let new_post = PostAccount::new(
             ctx.accounts.user.key(),
@@ -274,6 +792,8 @@ pub mod opinions_market {
                 Some(parent_pda) => PostType::Child { parent: parent_pda },
                 None => PostType::Original,
             },
+            curve,
+            vote_mode,
             now,
             config,
         );
@@ -281,12 +801,49 @@ pub mod opinions_market {
         post.creator_user = new_post.creator_user;
         post.post_id_hash = new_post.post_id_hash;
         post.post_type = new_post.post_type;
+        post.curve = new_post.curve;
+        post.vote_mode = new_post.vote_mode;
         post.start_time = new_post.start_time;
         post.end_time = new_post.end_time;
+        post.reveal_end_time = new_post.reveal_end_time;
         post.state = new_post.state;
         post.upvotes = new_post.upvotes;
         post.downvotes = new_post.downvotes;
+        post.creator_upvotes = new_post.creator_upvotes;
         post.winning_side = new_post.winning_side;
+        post.creator_score_applied = new_post.creator_score_applied;
+        post.child_count = new_post.child_count;
+        (post.depth, post.ancestors) = match lineage {
+            Some((_, depth, ancestors)) => (depth, ancestors),
+            None => (new_post.depth, new_post.ancestors),
+        };
+        post.payout_weighting = payout_weighting;
+        // Derived once here so claims can sign for the pot with create_program_address
+        post.pot_authority_bump = ctx.bumps.post_pot_authority;
+
+        // Share mints are created with the post, minted and burnt by the pot authority
+        post.share_mints = match (
+            tokenized,
+            &ctx.accounts.pump_share_mint,
+            &ctx.accounts.smack_share_mint,
+        ) {
+            (true, Some(pump), Some(smack)) => [pump.key(), smack.key()],
+            (false, None, None) => [Pubkey::default(); 2],
+            _ => return err!(ErrorCode::InvalidShareAccounts),
+        };
+
+        emit!(PostCreated {
+            post: post.key(),
+            creator: post.creator_user,
+            post_id_hash,
+            parent_post: parent_post_pda,
+            curve: post.curve,
+            vote_mode: post.vote_mode,
+            tokenized,
+            payout_weighting,
+            start_time: post.start_time,
+            end_time: post.end_time,
+        });
 
         Ok(())
     }
@@ -299,30 +856,27 @@ pub mod opinions_market {
         side: Side,
         votes: u64,
         post_id_hash: [u8; 32], // do not remove this - this is used to derive the post pda!
+        max_cost_in_token: u64, // slippage guard on the total charged in token_mint
     ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_VOTING)?;
         require!(votes > 0, ErrorCode::ZeroVotes);
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
 
         assert_session_or_wallet(
-            &ctx.accounts.voter.key(),
-            &ctx.accounts.session_authority.user,
+            &ctx.accounts.voter,
+            &ctx.accounts.session_key,
             Some(&ctx.accounts.session_authority),
             now,
         )?;
 
         let cfg = &ctx.accounts.config;
         let post = &mut ctx.accounts.post;
-        let current = match side {
-            Side::Pump => post.upvotes,
-            Side::Smack => post.downvotes,
-        };
 
         let requested = votes as u64;
-        let remaining_capacity = u64::MAX - current;
 
         // Cap valid votes at safe capacity
-        let valid_votes = requested.min(remaining_capacity);
+        let valid_votes = post.capped_votes(side, requested);
 
         if valid_votes < requested {
             msg!("⚠ Overflow prevented: {} votes requested, only {} applied. Full cost still charged.", requested, valid_votes);
@@ -333,6 +887,11 @@ pub mod opinions_market {
 
         require!(post.state == PostState::Open, ErrorCode::PostNotOpen);
         require!(post.within_time_limit(now), ErrorCode::PostExpired);
+        // Hidden votes go through commit_vote / reveal_vote
+        require!(post.vote_mode == VoteMode::Open, ErrorCode::WrongVoteMode);
+
+        // Track the pot's mint so settlement and claims can tell when the post is done
+        post.register_pot_mint(ctx.accounts.token_mint.key(), ctx.accounts.payer.key())?;
 
         // Handle position
         let pos = &mut ctx.accounts.position;
@@ -342,58 +901,67 @@ pub mod opinions_market {
             pos.post = new_pos.post;
             pos.upvotes = new_pos.upvotes;
             pos.downvotes = new_pos.downvotes;
+            pos.score_applied = new_pos.score_applied;
+            pos.claimed_pot_mints = new_pos.claimed_pot_mints;
+            pos.rent_payer = ctx.accounts.payer.key();
         }
 
         //
-        // ---- 1. Compute BLING cost ----
+        // ---- 1. Compute BLING cost and fees, converted to token_mint (if not BLING) ----
         //
 
+        // Convert from BLING to selected token using the ValidPayment price (or its feed)
+        let price_in_bling = if ctx.accounts.token_mint.key() == cfg.bling_mint {
+            None
+        } else {
+            Some(
+                ctx.accounts
+                    .valid_payment
+                    .resolve_price_in_bling(ctx.accounts.price_feed.as_deref(), now)?,
+            )
+        };
+
         let vote = Vote::new(side, valid_votes, ctx.accounts.voter.key(), post.key());
-        let cost_bling = vote.compute_cost_in_bling(post, pos, &ctx.accounts.voter_user_account)?;
+        let VoteQuote {
+            cost_bling,
+            protocol_fee_token,
+            creator_pump_fee_token,
+            pot_increment_token,
+            cost_in_token,
+            ..
+        } = vote.quote(
+            post,
+            pos,
+            &ctx.accounts.voter_user_account,
+            cfg,
+            price_in_bling,
+            ctx.accounts.token_mint.decimals,
+        )?;
 
         msg!("cost_bling: {}", cost_bling);
         msg!("post.upvotes BEFORE: {}", post.upvotes);
-
-        let protocol_fee = cost_bling * (PARAMS.protocol_vote_fee_bps as u64) / 10_000;
-        let creator_pump_fee = match side {
-            Side::Pump => cost_bling * (PARAMS.creator_pump_fee_bps as u64) / 10_000,
-            Side::Smack => 0,
-        };
-
-        let pot_increment = cost_bling
-            .checked_sub(protocol_fee + creator_pump_fee)
-            .ok_or(ErrorCode::MathOverflow)?;
+        msg!("protocol_fee_token: {}", protocol_fee_token);
+        msg!("creator_pump_fee_token: {}", creator_pump_fee_token);
+        msg!("pot_increment_token: {}", pot_increment_token);
 
         //
-        // ---- 2. CONVERT COSTS TO token_mint (if not BLING) ----
+        // ---- 2. Guards on what is charged ----
         //
 
-        use crate::math::token_conversion::convert_bling_fees_to_token;
-
-        // Get token decimals
-        let token_decimals = ctx.accounts.token_mint.decimals;
-
-        // Convert costs from BLING to selected token if needed
-        let (protocol_fee_token, creator_pump_fee_token, pot_increment_token) =
-            if ctx.accounts.token_mint.key() == ctx.accounts.config.bling_mint {
-                // Already in BLING, no conversion needed
-                (protocol_fee, creator_pump_fee, pot_increment)
-            } else {
-                // Convert from BLING to selected token using ValidPayment price
-                let price_in_bling = ctx.accounts.valid_payment.price_in_bling;
-
-                convert_bling_fees_to_token(
-                    protocol_fee,
-                    creator_pump_fee,
-                    pot_increment,
-                    price_in_bling,
-                    token_decimals,
-                )?
-            };
+        // Session spend is counted in BLING whatever the payment mint
+        assert_session_privileges(
+            ctx.accounts.voter.is_signer,
+            &mut ctx.accounts.session_authority,
+            SESSION_PRIVILEGE_VOTE_ON_POST,
+            Some(&ctx.accounts.token_mint.key()),
+            cost_bling,
+        )?;
 
-        msg!("protocol_fee_token: {}", protocol_fee_token);
-        msg!("creator_pump_fee_token: {}", creator_pump_fee_token);
-        msg!("pot_increment_token: {}", pot_increment_token);
+        // The curve may have moved since the quote, e.g. while the backend batched the votes
+        require!(
+            cost_in_token <= max_cost_in_token,
+            ErrorCode::SlippageExceeded
+        );
 
         //
         // ---- 3. TRANSFERS (IN token_mint) ----
@@ -460,6 +1028,42 @@ pub mod opinions_market {
             pot_increment_token,
         )?;
 
+        // Tokenized posts hand the votes out as shares of the side's mint
+        if post.tokenized() {
+            let (Some(share_mint), Some(voter_share_token_account)) = (
+                &ctx.accounts.share_mint,
+                &ctx.accounts.voter_share_token_account,
+            ) else {
+                return err!(ErrorCode::InvalidShareAccounts);
+            };
+            require!(
+                share_mint.key() == post.share_mint(side)
+                    && voter_share_token_account.mint == share_mint.key(),
+                ErrorCode::InvalidShareAccounts
+            );
+
+            if valid_votes > 0 {
+                let post_key = post.key();
+                let pot_signer_seeds: &[&[&[u8]]] = &[&[
+                    POST_POT_AUTHORITY_SEED,
+                    post_key.as_ref(),
+                    &[ctx.bumps.post_pot_authority],
+                ]];
+                anchor_spl::token::mint_to(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        anchor_spl::token::MintTo {
+                            mint: share_mint.to_account_info(),
+                            to: voter_share_token_account.to_account_info(),
+                            authority: ctx.accounts.post_pot_authority.to_account_info(),
+                        },
+                        pot_signer_seeds,
+                    ),
+                    valid_votes,
+                )?;
+            }
+        }
+
         //
         // ---- 4. UPDATE COUNTERS ----
         //
@@ -467,6 +1071,7 @@ pub mod opinions_market {
         match side {
             Side::Pump => {
                 post.upvotes += valid_votes;
+                post.track_creator_upvotes(&pos.user, valid_votes, 0);
                 pos.upvotes = pos.upvotes.saturating_add(valid_votes);
             }
             Side::Smack => {
@@ -474,142 +1079,743 @@ pub mod opinions_market {
                 pos.downvotes = pos.downvotes.saturating_add(valid_votes);
             }
         }
+        // What the votes cost, for stake-weighted payouts
+        post.paid_bling[side as usize] = post.paid_bling_on(side).saturating_add(cost_bling);
+        pos.paid_bling[side as usize] = pos.paid_bling_on(side).saturating_add(cost_bling);
+        // What stays in the pot, refunded if the post is cancelled
+        pos.add_pot_contribution(post, &ctx.accounts.token_mint.key(), pot_increment_token)?;
 
         // Extend post duration
-        post.extend_time_limit(clock.unix_timestamp, valid_votes as u32, cfg)?;
+        let end_time = post.extend_time_limit(clock.unix_timestamp, valid_votes as u32, cfg)?;
+
+        emit!(Voted {
+            post: post.key(),
+            voter: ctx.accounts.voter.key(),
+            side,
+            votes: valid_votes,
+            token_mint: ctx.accounts.token_mint.key(),
+            cost_bling,
+            protocol_fee: protocol_fee_token,
+            creator_pump_fee: creator_pump_fee_token,
+            pot_increment: pot_increment_token,
+            post_upvotes: post.upvotes,
+            post_downvotes: post.downvotes,
+            end_time,
+        });
 
         Ok(())
     }
 
-    // This is token mint specific - to settle the pots for all tokens, chain all the
-    //instructions together, each parametrized by individual token mints, and send it
-    // off in one transaction.
-    // Naturally this means we cannot require the PostState to be open, we only require it to be past the settlement time.
-    // This instruction does ALL the math and freezes it. NO token transfers happen here.
-    pub fn settle_post(ctx: Context<SettlePost>, post_id_hash: [u8; 32]) -> Result<()> {
-        msg!("\n🌟🌟🌟🌟🌟 Settling post 🌟🌟🌟🌟🌟");
-        let post = &mut ctx.accounts.post;
-        let clock = Clock::get()?;
-        let now = clock.unix_timestamp;
-
-        msg!("clock.unix_timestamp: {}", now);
-        msg!("post.end_time: {}", post.end_time);
-
-        // If still within time limit, exit early.
-        if post.within_time_limit(now) {
-            msg!("Post is still within time limit, not doing anything and exiting early!");
-            return Ok(());
-        }
+    /// Read-only quote of what `vote_on_post` would charge right now, from the same math.
+    /// The VoteQuote is returned through return data (set_return_data), so clients can read
+    /// it by simulating the transaction instead of re-implementing the curve.
+    pub fn quote_vote(
+        ctx: Context<QuoteVote>,
+        side: Side,
+        votes: u64,
+        _post_id_hash: [u8; 32],
+    ) -> Result<VoteQuote> {
+        require!(votes > 0, ErrorCode::ZeroVotes);
+        let now = Clock::get()?.unix_timestamp;
+        let cfg = &ctx.accounts.config;
+        let post = &ctx.accounts.post;
+        require!(post.within_time_limit(now), ErrorCode::PostExpired);
+        // Hidden votes go through commit_vote / reveal_vote
+        require!(post.vote_mode == VoteMode::Open, ErrorCode::WrongVoteMode);
 
-        // Check if already frozen (prevent re-settlement)
-        let payout = &mut ctx.accounts.post_mint_payout;
-        if payout.frozen {
-            msg!("Post already settled and frozen, skipping");
-            return Ok(());
-        }
+        let price_in_bling = if ctx.accounts.token_mint.key() == cfg.bling_mint {
+            None
+        } else {
+            Some(
+                ctx.accounts
+                    .valid_payment
+                    .resolve_price_in_bling(ctx.accounts.price_feed.as_deref(), now)?,
+            )
+        };
 
-        // Determine winner — ties and zero votes = Pump side wins
-        let (winner, total_winning_votes) = match post.upvotes.cmp(&post.downvotes) {
-            std::cmp::Ordering::Greater => (Side::Pump, post.upvotes),
-            std::cmp::Ordering::Less => (Side::Smack, post.downvotes),
-            std::cmp::Ordering::Equal => (Side::Pump, post.upvotes), // tie → Pump wins
+        // A first vote has no position yet: quote it from an empty one, like vote_on_post
+        let pos = match &ctx.accounts.position {
+            Some(pos) => ***pos,
+            None => UserPostPosition::new(ctx.accounts.voter.key(), post.key()),
         };
-        let initial_pot = ctx.accounts.post_pot_token_account.amount;
 
-        msg!("Initial pot amount: {}", initial_pot);
-        msg!(
-            "Winner: {:?}, Winning votes: {}",
-            winner,
-            total_winning_votes
+        let vote = Vote::new(
+            side,
+            post.capped_votes(side, votes),
+            ctx.accounts.voter.key(),
+            post.key(),
         );
+        let quote = vote.quote(
+            post,
+            &pos,
+            &ctx.accounts.voter_user_account,
+            cfg,
+            price_in_bling,
+            ctx.accounts.token_mint.decimals,
+        )?;
 
-        // Calculate mother post fee (10% for child posts, only if parent is still open)
-        let mother_fee = match post.post_type {
-            PostType::Child { .. } => {
-                let parent = ctx
-                    .accounts
-                    .parent_post
-                    .as_ref()
-                    .ok_or(ErrorCode::InvalidParentPost)?;
-                if parent.state == PostState::Open {
-                    initial_pot / 10
-                } else {
-                    0
-                }
-            }
-            _ => 0,
-        };
+        msg!("📝 Vote quote: {:?}", quote);
 
-        let pot_after_mother = initial_pot
-            .checked_sub(mother_fee)
-            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(quote)
+    }
 
-        // Calculate protocol fee (1% of remaining pot after mother fee)
-        let protocol_fee = pot_after_mother
-            .checked_mul(PARAMS.protocol_vote_settlement_fee_bps as u64)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(10_000)
-            .ok_or(ErrorCode::MathOverflow)?;
+    /// Sells `votes` of the position on `side` back to the post before end_time.
+    /// The refund is the inverse of the vote cost (see Vote::exit_quote), minus the exit fee,
+    /// paid out of the pot in `token_mint` to the user's vault, up to what the position put
+    /// in that pot. Post and position counters go down; the end time does not move back.
+    pub fn exit_position(
+        ctx: Context<ExitPosition>,
+        side: Side,
+        votes: u64,
+        _post_id_hash: [u8; 32],
+        min_refund_in_token: u64, // slippage guard on the refund paid in token_mint
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_VOTING)?;
+        require!(votes > 0, ErrorCode::ZeroVotes);
+        let now = Clock::get()?.unix_timestamp;
 
-        let pot_after_protocol = pot_after_mother
-            .checked_sub(protocol_fee)
-            .ok_or(ErrorCode::MathOverflow)?;
+        assert_session_or_wallet(
+            &ctx.accounts.voter,
+            &ctx.accounts.session_key,
+            Some(&ctx.accounts.session_authority),
+            now,
+        )?;
+        // Selling is trading like voting, but spends nothing
+        assert_session_privileges(
+            ctx.accounts.voter.is_signer,
+            &mut ctx.accounts.session_authority,
+            SESSION_PRIVILEGE_VOTE_ON_POST,
+            Some(&ctx.accounts.token_mint.key()),
+            0,
+        )?;
 
-        // Calculate creator fee (40% of remaining pot if Pump wins, 0 if Smack wins)
-        let creator_fee = match winner {
-            Side::Pump => pot_after_protocol
-                .checked_mul(PARAMS.creator_pump_win_settlement_fee_bps as u64)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_div(10_000)
-                .ok_or(ErrorCode::MathOverflow)?,
-            Side::Smack => 0,
-        };
+        let cfg = &ctx.accounts.config;
+        let post = &mut ctx.accounts.post;
+        let pos = &mut ctx.accounts.position;
 
-        let total_payout = pot_after_protocol
-            .checked_sub(creator_fee)
+        require!(post.within_time_limit(now), ErrorCode::PostExpired);
+        // Commit–reveal counts are hidden until after end_time, there is no curve to sell on
+        require!(post.vote_mode == VoteMode::Open, ErrorCode::WrongVoteMode);
+        // The votes of a tokenized post live in its shares, which may have changed hands
+        require!(!post.tokenized(), ErrorCode::PositionTokenized);
+
+        let price_in_bling = if ctx.accounts.token_mint.key() == cfg.bling_mint {
+            None
+        } else {
+            Some(
+                ctx.accounts
+                    .valid_payment
+                    .resolve_price_in_bling(ctx.accounts.price_feed.as_deref(), now)?,
+            )
+        };
+
+        let vote = Vote::new(side, votes, ctx.accounts.voter.key(), post.key());
+        let ExitQuote {
+            refund,
+            exit_fee_token,
+            refund_token,
+            ..
+        } = vote
+            .exit_quote(post, pos, &ctx.accounts.voter_user_account, cfg)?
+            .in_token(
+                pos.pot_contribution(post, &ctx.accounts.token_mint.key())?,
+                price_in_bling,
+                ctx.accounts.token_mint.decimals,
+                cfg.exit_fee_bps,
+            )?;
+
+        msg!(
+            "💸 Exiting {:?} x{}: refund {} (fee {})",
+            side,
+            votes,
+            refund_token,
+            exit_fee_token
+        );
+
+        require!(
+            refund_token >= min_refund_in_token,
+            ErrorCode::RefundBelowMinimum
+        );
+        require!(
+            refund_token <= ctx.accounts.post_pot_token_account.amount,
+            ErrorCode::InsufficientPot
+        );
+
+        if refund_token > 0 {
+            let post_key = post.key();
+            let bump_array = [post.pot_authority_bump];
+            let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+            let seeds: &[&[&[u8]]] = &[&seeds_array];
+
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: ctx.accounts.post_pot_token_account.to_account_info(),
+                        to: ctx
+                            .accounts
+                            .voter_user_vault_token_account
+                            .to_account_info(),
+                        authority: ctx.accounts.post_pot_authority.to_account_info(),
+                    },
+                    seeds,
+                ),
+                refund_token,
+            )?;
+        }
+
+        // Both the refund and the exit fee come out of what the position can get back on a cancel
+        pos.reduce_pot_contribution(
+            post,
+            &ctx.accounts.token_mint.key(),
+            refund_token.saturating_add(exit_fee_token),
+        )?;
+
+        // The sold votes take their share of what the position paid with them
+        let paid_bling_sold = pos.paid_bling_for(side, votes);
+        post.paid_bling[side as usize] = post.paid_bling_on(side).saturating_sub(paid_bling_sold);
+        pos.paid_bling[side as usize] = pos.paid_bling_on(side).saturating_sub(paid_bling_sold);
+
+        match side {
+            Side::Pump => {
+                post.upvotes = post.upvotes.saturating_sub(votes);
+                post.track_creator_upvotes(&pos.user, 0, votes);
+                pos.upvotes -= votes;
+            }
+            Side::Smack => {
+                post.downvotes = post.downvotes.saturating_sub(votes);
+                pos.downvotes -= votes;
+            }
+        }
+
+        emit!(PositionExited {
+            post: post.key(),
+            voter: ctx.accounts.voter.key(),
+            side,
+            votes,
+            token_mint: ctx.accounts.token_mint.key(),
+            quoted_refund_bling: refund,
+            refund: refund_token,
+            exit_fee: exit_fee_token,
+            post_upvotes: post.upvotes,
+            post_downvotes: post.downvotes,
+        });
+
+        Ok(())
+    }
+
+    /// Commit–reveal counterpart of vote_on_post: the side and votes stay hidden behind
+    /// `commitment` (see VoteCommitment::hash) until reveal_vote, so late voters can't
+    /// see which side is winning. `escrow` moves into the post pot now; the reveal pays
+    /// for the vote out of it and refunds the rest. One commitment per user and post.
+    pub fn commit_vote(
+        ctx: Context<CommitVote>,
+        _post_id_hash: [u8; 32],
+        commitment: [u8; 32],
+        escrow: u64, // in token_mint; must cover the vote's cost at reveal time
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_VOTING)?;
+        require!(escrow > 0, ErrorCode::EscrowTooLow);
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+
+        assert_session_or_wallet(
+            &ctx.accounts.voter,
+            &ctx.accounts.session_key,
+            Some(&ctx.accounts.session_authority),
+            now,
+        )?;
+
+        let cfg = &ctx.accounts.config;
+        let post = &mut ctx.accounts.post;
+
+        require!(post.within_time_limit(now), ErrorCode::PostExpired);
+        require!(
+            post.vote_mode == VoteMode::CommitReveal,
+            ErrorCode::WrongVoteMode
+        );
+
+        // The escrow sits in the pot from now on, forfeited or not
+        post.register_pot_mint(ctx.accounts.token_mint.key(), ctx.accounts.payer.key())?;
+
+        // Session spend is counted in BLING whatever the payment mint
+        let escrow_bling = if ctx.accounts.token_mint.key() == cfg.bling_mint {
+            escrow
+        } else {
+            let price_in_bling = ctx
+                .accounts
+                .valid_payment
+                .resolve_price_in_bling(ctx.accounts.price_feed.as_deref(), now)?;
+            convert_token_to_bling_lamports(
+                escrow,
+                price_in_bling,
+                ctx.accounts.token_mint.decimals,
+            )?
+        };
+        assert_session_privileges(
+            ctx.accounts.voter.is_signer,
+            &mut ctx.accounts.session_authority,
+            SESSION_PRIVILEGE_VOTE_ON_POST,
+            Some(&ctx.accounts.token_mint.key()),
+            escrow_bling,
+        )?;
+
+        let vault_bump = ctx.bumps.vault_authority;
+        let user_authority_seeds: &[&[&[u8]]] = &[&[VAULT_AUTHORITY_SEED, &[vault_bump]]];
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx
+                        .accounts
+                        .voter_user_vault_token_account
+                        .to_account_info(),
+                    to: ctx.accounts.post_pot_token_account.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                user_authority_seeds,
+            ),
+            escrow,
+        )?;
+
+        let new_commitment = VoteCommitment::new(
+            ctx.accounts.voter.key(),
+            post.key(),
+            ctx.accounts.token_mint.key(),
+            commitment,
+            escrow,
+            ctx.bumps.vote_commitment,
+        );
+        let vote_commitment = &mut ctx.accounts.vote_commitment;
+        vote_commitment.user = new_commitment.user;
+        vote_commitment.post = new_commitment.post;
+        vote_commitment.token_mint = new_commitment.token_mint;
+        vote_commitment.commitment = new_commitment.commitment;
+        vote_commitment.escrow = new_commitment.escrow;
+        vote_commitment.bump = new_commitment.bump;
+        vote_commitment.rent_payer = ctx.accounts.payer.key();
+        post.add_pot_escrow(&ctx.accounts.token_mint.key(), escrow)?;
+
+        msg!("🙈 Vote committed with {} escrowed", escrow);
+
+        emit!(VoteCommitted {
+            post: post.key(),
+            voter: ctx.accounts.voter.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            escrow,
+            reveal_end_time: post.reveal_end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Opens a commitment during the reveal window and counts its votes at the current
+    /// curve price, paid from the escrow already in the pot. The leftover escrow goes back
+    /// to the voter's vault and the commitment is closed.
+    /// Permissionless: knowing the salt is the authorization, funds only move to the voter.
+    /// Not stopped by PAUSE_VOTING: the reveal window doesn't move, so a pause inside it
+    /// would forfeit the escrows of everyone who couldn't reveal.
+    pub fn reveal_vote(
+        ctx: Context<RevealVote>,
+        _post_id_hash: [u8; 32],
+        side: Side,
+        votes: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        require!(votes > 0, ErrorCode::ZeroVotes);
+        let now = Clock::get()?.unix_timestamp;
+
+        let cfg = &ctx.accounts.config;
+        let post = &mut ctx.accounts.post;
+        let vote_commitment = &ctx.accounts.vote_commitment;
+
+        require!(
+            post.within_reveal_window(now),
+            ErrorCode::RevealWindowNotOpen
+        );
+        require!(
+            vote_commitment.matches(side, votes, &salt),
+            ErrorCode::InvalidReveal
+        );
+
+        // Handle position
+        let pos = &mut ctx.accounts.position;
+        if pos.user == Pubkey::default() {
+            let new_pos = UserPostPosition::new(ctx.accounts.voter.key(), post.key());
+            pos.user = new_pos.user;
+            pos.post = new_pos.post;
+            pos.upvotes = new_pos.upvotes;
+            pos.downvotes = new_pos.downvotes;
+            pos.score_applied = new_pos.score_applied;
+            pos.claimed_pot_mints = new_pos.claimed_pot_mints;
+            pos.rent_payer = ctx.accounts.payer.key();
+        }
+
+        // Priced on the curve as revealed so far, in the escrowed mint
+        let price_in_bling = if ctx.accounts.token_mint.key() == cfg.bling_mint {
+            None
+        } else {
+            Some(
+                ctx.accounts
+                    .valid_payment
+                    .resolve_price_in_bling(ctx.accounts.price_feed.as_deref(), now)?,
+            )
+        };
+
+        let valid_votes = post.capped_votes(side, votes);
+        let vote = Vote::new(side, valid_votes, ctx.accounts.voter.key(), post.key());
+        let VoteQuote {
+            cost_bling,
+            protocol_fee_token,
+            creator_pump_fee_token,
+            pot_increment_token,
+            cost_in_token,
+            ..
+        } = vote.quote(
+            post,
+            pos,
+            &ctx.accounts.voter_user_account,
+            cfg,
+            price_in_bling,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        // The escrow is the voter's slippage bound
+        require!(
+            cost_in_token <= vote_commitment.escrow,
+            ErrorCode::EscrowTooLow
+        );
+        let refund = vote_commitment.escrow - cost_in_token;
+
+        msg!(
+            "🙉 Revealed {:?} x{} for {} of {} escrowed",
+            side,
+            valid_votes,
+            cost_in_token,
+            vote_commitment.escrow
+        );
+
+        // The pot increment stays where it is; fees and the refund leave the pot
+        let post_key = post.key();
+        let bump_array = [post.pot_authority_bump];
+        let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+        let seeds: &[&[&[u8]]] = &[&seeds_array];
+
+        for (to, amount) in [
+            (
+                ctx.accounts
+                    .protocol_token_treasury_token_account
+                    .to_account_info(),
+                protocol_fee_token,
+            ),
+            (
+                ctx.accounts.creator_vault_token_account.to_account_info(),
+                creator_pump_fee_token,
+            ),
+            (
+                ctx.accounts
+                    .voter_user_vault_token_account
+                    .to_account_info(),
+                refund,
+            ),
+        ] {
+            if amount > 0 {
+                anchor_spl::token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        anchor_spl::token::Transfer {
+                            from: ctx.accounts.post_pot_token_account.to_account_info(),
+                            to,
+                            authority: ctx.accounts.post_pot_authority.to_account_info(),
+                        },
+                        seeds,
+                    ),
+                    amount,
+                )?;
+            }
+        }
+
+        // Only revealed votes are counted; the end time no longer moves
+        match side {
+            Side::Pump => {
+                post.upvotes += valid_votes;
+                post.track_creator_upvotes(&pos.user, valid_votes, 0);
+                pos.upvotes = pos.upvotes.saturating_add(valid_votes);
+            }
+            Side::Smack => {
+                post.downvotes += valid_votes;
+                pos.downvotes = pos.downvotes.saturating_add(valid_votes);
+            }
+        }
+        // What the votes cost, for stake-weighted payouts
+        post.paid_bling[side as usize] = post.paid_bling_on(side).saturating_add(cost_bling);
+        pos.paid_bling[side as usize] = pos.paid_bling_on(side).saturating_add(cost_bling);
+        // What stays in the pot, refunded if the post is cancelled
+        pos.add_pot_contribution(post, &ctx.accounts.token_mint.key(), pot_increment_token)?;
+        post.remove_pot_escrow(&ctx.accounts.token_mint.key(), vote_commitment.escrow)?;
+
+        emit!(Voted {
+            post: post.key(),
+            voter: ctx.accounts.voter.key(),
+            side,
+            votes: valid_votes,
+            token_mint: ctx.accounts.token_mint.key(),
+            cost_bling,
+            protocol_fee: protocol_fee_token,
+            creator_pump_fee: creator_pump_fee_token,
+            pot_increment: pot_increment_token,
+            post_upvotes: post.upvotes,
+            post_downvotes: post.downvotes,
+            end_time: post.end_time,
+        });
+        emit!(VoteRevealed {
+            post: post.key(),
+            voter: ctx.accounts.voter.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            refund,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a commitment that was never revealed once the reveal window is over.
+    /// Its escrow stays in the post pot; only the rent goes back to whoever funded it.
+    /// On a cancelled post, only once its pot was swept without the escrow being refunded.
+    pub fn close_vote_commitment(
+        ctx: Context<CloseVoteCommitment>,
+        _post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let now = Clock::get()?.unix_timestamp;
+        let post = &mut ctx.accounts.post;
+        let vote_commitment = &ctx.accounts.vote_commitment;
+        if post.state == PostState::Cancelled {
+            // Escrows on a cancelled post go back through refund_vote_commitment
+            require!(
+                post.pot_swept(&vote_commitment.token_mint),
+                ErrorCode::PostCancelled
+            );
+        } else {
+            require!(post.votes_final(now), ErrorCode::RevealWindowOpen);
+        }
+        post.remove_pot_escrow(&vote_commitment.token_mint, vote_commitment.escrow)?;
+
+        msg!(
+            "🙊 Unrevealed commitment forfeits {} to the pot",
+            vote_commitment.escrow
+        );
+
+        emit!(VoteCommitmentForfeited {
+            post: post.key(),
+            user: vote_commitment.user,
+            token_mint: vote_commitment.token_mint,
+            escrow: vote_commitment.escrow,
+            rent_payer: vote_commitment.rent_payer,
+        });
+
+        Ok(())
+    }
+
+    // This is token mint specific - to settle the pots for all tokens, chain all the
+    //instructions together, each parametrized by individual token mints, and send it
+    // off in one transaction.
+    // Naturally this means we cannot require the PostState to be open, we only require it to be past the settlement time.
+    // This instruction does ALL the math and freezes it. NO token transfers happen here.
+    /// Child posts pass their parent as `parent_post` and the ancestors further up, grandparent
+    /// first, as remaining accounts, as many as `Config::mother_fee_schedule` has levels.
+    pub fn settle_post<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettlePost<'info>>,
+        post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        msg!("\n🌟🌟🌟🌟🌟 Settling post 🌟🌟🌟🌟🌟");
+        let post = &mut ctx.accounts.post;
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+
+        require!(post.state != PostState::Cancelled, ErrorCode::PostCancelled);
+
+        msg!("clock.unix_timestamp: {}", now);
+        msg!("post.end_time: {}", post.end_time);
+
+        // If votes can still be cast (or revealed), exit early.
+        if !post.votes_final(now) {
+            msg!("Post is still within time limit, not doing anything and exiting early!");
+            return Ok(());
+        }
+
+        // Check if already frozen (prevent re-settlement)
+        let payout = &mut ctx.accounts.post_mint_payout;
+        if payout.frozen {
+            msg!("Post already settled and frozen, skipping");
+            return Ok(());
+        }
+
+        // Determine winner — ties and zero votes = Pump side wins
+        let (winner, total_winning_votes) = match post.upvotes.cmp(&post.downvotes) {
+            std::cmp::Ordering::Greater => (Side::Pump, post.upvotes),
+            std::cmp::Ordering::Less => (Side::Smack, post.downvotes),
+            std::cmp::Ordering::Equal => (Side::Pump, post.upvotes), // tie → Pump wins
+        };
+        let initial_pot = ctx.accounts.post_pot_token_account.amount;
+
+        msg!("Initial pot amount: {}", initial_pot);
+        msg!(
+            "Winner: {:?}, Winning votes: {}",
+            winner,
+            total_winning_votes
+        );
+
+        // Mother fees decay up the reply tree, and only go to ancestors that are still open
+        let parent_open = match post.post_type {
+            PostType::Child { .. } => {
+                let parent = ctx
+                    .accounts
+                    .parent_post
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidParentPost)?;
+                parent.state == PostState::Open
+            }
+            _ => false,
+        };
+        let schedule = ctx.accounts.config.mother_fee_schedule(post.depth);
+        let mut mother_fees = [0u64; MAX_MOTHER_FEE_LEVELS];
+        for (i, bps) in schedule.iter().enumerate() {
+            if *bps == 0 {
+                break;
+            }
+            let ancestor_open = if i == 0 {
+                parent_open
+            } else {
+                let ancestor_info = ctx
+                    .remaining_accounts
+                    .get(i - 1)
+                    .ok_or(ErrorCode::InvalidAncestorPost)?;
+                require!(
+                    post.ancestors[i] == ancestor_info.key(),
+                    ErrorCode::InvalidAncestorPost
+                );
+                let ancestor: Account<PostAccount> = Account::try_from(ancestor_info)?;
+                ancestor.state == PostState::Open
+            };
+            if ancestor_open {
+                mother_fees[i] = initial_pot
+                    .checked_mul(*bps as u64)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_div(MAX_BPS as u64)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+        let mother_fee: u64 = mother_fees.iter().sum();
+
+        let pot_after_mother = initial_pot
+            .checked_sub(mother_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Calculate protocol fee (1% of remaining pot after mother fee)
+        let protocol_fee = pot_after_mother
+            .checked_mul(ctx.accounts.config.protocol_vote_settlement_fee_bps as u64)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(10_000)
             .ok_or(ErrorCode::MathOverflow)?;
 
+        let pot_after_protocol = pot_after_mother
+            .checked_sub(protocol_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Calculate creator fee (40% of remaining pot if Pump wins, 0 if Smack wins)
+        let creator_fee = match winner {
+            Side::Pump => pot_after_protocol
+                .checked_mul(ctx.accounts.config.creator_pump_win_settlement_fee_bps as u64)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(10_000)
+                .ok_or(ErrorCode::MathOverflow)?,
+            Side::Smack => 0,
+        };
+
+        let total_payout = pot_after_protocol
+            .checked_sub(creator_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // With no winning votes (no votes at all, as the winner has the most) the payout
+        // would be locked in the pot forever: roll it into the parent pot or the treasury
+        let policy = PayoutPolicy::for_settlement(total_winning_votes, parent_open);
+        let (protocol_fee, total_payout, paid_votes) = match policy {
+            PayoutPolicy::Winners => (protocol_fee, total_payout, total_winning_votes),
+            PayoutPolicy::ParentRollover => {
+                // The parent takes the whole payout on top of its own share
+                mother_fees[0] = mother_fees[0]
+                    .checked_add(total_payout)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                (protocol_fee, 0, 0)
+            }
+            PayoutPolicy::TreasurySweep => (
+                protocol_fee
+                    .checked_add(total_payout)
+                    .ok_or(ErrorCode::MathOverflow)?,
+                0,
+                0,
+            ),
+        };
+        let mother_fee: u64 = mother_fees.iter().sum();
+
         // Calculate payout_per_winning_vote (scaled by PRECISION)
-        let payout_per_winning_vote = if total_winning_votes == 0 {
+        let payout_per_winning_vote = if paid_votes == 0 {
             0
         } else {
             total_payout
                 .checked_mul(PRECISION)
                 .ok_or(ErrorCode::MathOverflow)?
-                .checked_div(total_winning_votes)
+                .checked_div(paid_votes)
                 .ok_or(ErrorCode::MathOverflow)?
         };
 
+        // Stake-weighted posts split by what the winning side paid. Per vote when nothing was
+        // recorded, which leaves the payout claimable instead of locked
+        let paid_stake = match policy {
+            PayoutPolicy::Winners => post.paid_bling_on(winner),
+            PayoutPolicy::ParentRollover | PayoutPolicy::TreasurySweep => 0,
+        };
+        let (weighting, paid_stake) = match post.payout_weighting {
+            PayoutWeighting::Stake if paid_stake > 0 => (PayoutWeighting::Stake, paid_stake),
+            _ => (PayoutWeighting::PerVote, 0),
+        };
+
         msg!("Fees calculated:");
-        msg!("  Mother fee: {}", mother_fee);
+        msg!("  Mother fee: {} ({:?} by level)", mother_fee, mother_fees);
         msg!("  Protocol fee: {}", protocol_fee);
         msg!("  Creator fee: {}", creator_fee);
         msg!("  Total payout for voters: {}", total_payout);
         msg!("  Payout per winning vote: {}", payout_per_winning_vote);
+        msg!("  Payout policy: {:?}", policy);
+        msg!(
+            "  Payout weighting: {:?}, paid stake: {}",
+            weighting,
+            paid_stake
+        );
 
         // Freeze all calculations in PostMintPayout
-        let new_payout = PostMintPayout::new(
-            post.key(),
-            ctx.accounts.token_mint.key(),
+        payout.set_inner(PostMintPayout {
+            post: post.key(),
+            token_mint: ctx.accounts.token_mint.key(),
             initial_pot,
             total_payout,
             payout_per_winning_vote,
             creator_fee,
             protocol_fee,
             mother_fee,
-            ctx.bumps.post_mint_payout,
-        );
-
-        payout.post = new_payout.post;
-        payout.token_mint = new_payout.token_mint;
-        payout.initial_pot = new_payout.initial_pot;
-        payout.total_payout = new_payout.total_payout;
-        payout.payout_per_winning_vote = new_payout.payout_per_winning_vote;
-        payout.creator_fee = new_payout.creator_fee;
-        payout.protocol_fee = new_payout.protocol_fee;
-        payout.mother_fee = new_payout.mother_fee;
-        payout.frozen = new_payout.frozen;
-        payout.bump = new_payout.bump;
+            frozen: true, // Always frozen when created
+            bump: ctx.bumps.post_mint_payout,
+            policy,
+            claim_deadline: now + ctx.accounts.config.claim_window_secs as i64,
+            weighting,
+            paid_stake,
+            mother_fees,
+            distributed_mother_fees: 0,
+            creator_fee_distributed: false,
+            protocol_fee_distributed: false,
+            rent_payer: ctx.accounts.payer.key(),
+        });
 
         // Update post state
         if post.winning_side.is_none() {
@@ -622,21 +1828,50 @@ pub mod opinions_market {
 
         require!(post.state == PostState::Settled, ErrorCode::PostNotSettled);
 
+        post.settled_pot_mints |= post.pot_mint_bit(&payout.token_mint)?;
+
+        // Creator score moves once per post, whichever mint settles first
+        if !post.creator_score_applied {
+            let delta = post.creator_social_score_delta();
+            ctx.accounts
+                .creator_user_account
+                .apply_social_score_delta(delta);
+            post.creator_score_applied = true;
+            msg!("Creator social score delta: {}", delta);
+        }
+
+        emit!(PostSettled {
+            post: post.key(),
+            token_mint: payout.token_mint,
+            winning_side: winner,
+            total_winning_votes,
+            initial_pot,
+            mother_fee,
+            mother_fees,
+            protocol_fee,
+            creator_fee,
+            total_payout,
+            payout_per_winning_vote,
+            policy,
+            weighting,
+        });
+
         msg!("✅ Settlement frozen successfully. Distribution instructions can now be called.");
 
         Ok(())
     }
 
     /// Distribute creator reward from frozen settlement.
-    /// Reads creator_fee from PostMintPayout and transfers it to creator's vault.
+    /// Reads creator_fee from PostMintPayout and transfers it to creator's vault, once.
     pub fn distribute_creator_reward(
         ctx: Context<DistributeCreatorReward>,
         post_id_hash: [u8; 32],
     ) -> Result<()> {
-        let payout = &ctx.accounts.post_mint_payout;
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let payout = &mut ctx.accounts.post_mint_payout;
         require!(payout.frozen, ErrorCode::PostNotSettled);
 
-        let creator_fee = payout.creator_fee;
+        let creator_fee = payout.take_creator_fee();
         if creator_fee == 0 {
             msg!("No creator fee to distribute");
             return Ok(());
@@ -666,21 +1901,29 @@ pub mod opinions_market {
 
         anchor_spl::token::transfer(cpi, creator_fee)?;
 
+        emit!(CreatorRewardDistributed {
+            post: post_key,
+            creator: ctx.accounts.post.creator_user,
+            token_mint: payout.token_mint,
+            amount: creator_fee,
+        });
+
         msg!("✅ Creator reward distributed successfully");
 
         Ok(())
     }
 
     /// Distribute protocol fee from frozen settlement.
-    /// Reads protocol_fee from PostMintPayout and transfers it to protocol treasury.
+    /// Reads protocol_fee from PostMintPayout and transfers it to protocol treasury, once.
     pub fn distribute_protocol_fee(
         ctx: Context<DistributeProtocolFee>,
         post_id_hash: [u8; 32],
     ) -> Result<()> {
-        let payout = &ctx.accounts.post_mint_payout;
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let payout = &mut ctx.accounts.post_mint_payout;
         require!(payout.frozen, ErrorCode::PostNotSettled);
 
-        let protocol_fee = payout.protocol_fee;
+        let protocol_fee = payout.take_protocol_fee();
         if protocol_fee == 0 {
             msg!("No protocol fee to distribute");
             return Ok(());
@@ -702,61 +1945,111 @@ pub mod opinions_market {
             ctx.accounts.token_program.to_account_info(),
             anchor_spl::token::Transfer {
                 from: ctx.accounts.post_pot_token_account.to_account_info(),
-                to: ctx
-                    .accounts
-                    .protocol_token_treasury_token_account
-                    .to_account_info(),
+                to: ctx
+                    .accounts
+                    .protocol_token_treasury_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.post_pot_authority.to_account_info(),
+            },
+            seeds,
+        );
+
+        anchor_spl::token::transfer(cpi, protocol_fee)?;
+
+        emit!(ProtocolFeeDistributed {
+            post: post_key,
+            token_mint: payout.token_mint,
+            amount: protocol_fee,
+        });
+
+        msg!("✅ Protocol fee distributed successfully");
+
+        Ok(())
+    }
+
+    /// Distribute parent post share from frozen settlement.
+    /// Reads the parent's mother fee from PostMintPayout and transfers it to the parent post's pot.
+    pub fn distribute_parent_post_share(
+        ctx: Context<DistributeParentPostShare>,
+        post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let payout = &mut ctx.accounts.post_mint_payout;
+        require!(payout.frozen, ErrorCode::PostNotSettled);
+
+        let mother_fee = payout.take_mother_fee(1)?;
+        if mother_fee == 0 {
+            msg!("No mother post fee to distribute");
+            return Ok(());
+        }
+
+        let parent_post_key = ctx.accounts.parent_post.key();
+        msg!(
+            "Distributing mother post fee: {} to parent post: {}",
+            mother_fee,
+            parent_post_key
+        );
+
+        // Transfer from child post pot to parent post pot
+        let post = &ctx.accounts.post;
+        let post_key = post.key();
+        let bump_array = [post.pot_authority_bump];
+        let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+        let seeds: &[&[&[u8]]] = &[&seeds_array];
+
+        let cpi = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.post_pot_token_account.to_account_info(),
+                to: ctx.accounts.parent_post_pot_token_account.to_account_info(),
                 authority: ctx.accounts.post_pot_authority.to_account_info(),
             },
             seeds,
         );
 
-        anchor_spl::token::transfer(cpi, protocol_fee)?;
+        anchor_spl::token::transfer(cpi, mother_fee)?;
 
-        msg!("✅ Protocol fee distributed successfully");
+        emit!(ParentShareDistributed {
+            post: post_key,
+            parent_post: parent_post_key,
+            level: 1,
+            token_mint: payout.token_mint,
+            amount: mother_fee,
+        });
+
+        msg!("✅ Parent post share distributed successfully");
 
         Ok(())
     }
 
-    /// Distribute parent post share from frozen settlement.
-    /// Reads mother_fee from PostMintPayout and transfers it to parent post's pot.
-    pub fn distribute_parent_post_share(
-        ctx: Context<DistributeParentPostShare>,
-        post_id_hash: [u8; 32],
+    /// Same as distribute_parent_post_share for the ancestor `level` steps up the reply tree
+    /// (2 = grandparent). The ancestor's pot must already exist in this mint.
+    pub fn distribute_ancestor_post_share(
+        ctx: Context<DistributeAncestorPostShare>,
+        _post_id_hash: [u8; 32],
+        level: u8,
     ) -> Result<()> {
-        let payout = &ctx.accounts.post_mint_payout;
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let payout = &mut ctx.accounts.post_mint_payout;
         require!(payout.frozen, ErrorCode::PostNotSettled);
 
-        let mother_fee = payout.mother_fee;
+        let mother_fee = payout.take_mother_fee(level)?;
         if mother_fee == 0 {
-            msg!("No mother post fee to distribute");
+            msg!("No mother post fee to distribute at level {}", level);
             return Ok(());
         }
 
-        let parent_post = ctx
-            .accounts
-            .parent_post
-            .as_ref()
-            .ok_or(ErrorCode::InvalidParentPost)?;
-        let parent_post_pot = ctx
-            .accounts
-            .parent_post_pot_token_account
-            .as_ref()
-            .ok_or(ErrorCode::InvalidParentPost)?;
+        let ancestor_post_key = ctx.accounts.ancestor_post.key();
         msg!(
-            "Distributing mother post fee: {} to parent post: {}",
+            "Distributing mother post fee: {} to level {} ancestor post: {}",
             mother_fee,
-            parent_post.key()
-        );
-
-        // Transfer from child post pot to parent post pot
-        let post_key = ctx.accounts.post.key();
-        let (_, bump) = Pubkey::find_program_address(
-            &[POST_POT_AUTHORITY_SEED, post_key.as_ref()],
-            ctx.program_id,
+            level,
+            ancestor_post_key
         );
 
-        let bump_array = [bump];
+        let post = &ctx.accounts.post;
+        let post_key = post.key();
+        let bump_array = [post.pot_authority_bump];
         let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
         let seeds: &[&[&[u8]]] = &[&seeds_array];
 
@@ -764,7 +2057,10 @@ pub mod opinions_market {
             ctx.accounts.token_program.to_account_info(),
             anchor_spl::token::Transfer {
                 from: ctx.accounts.post_pot_token_account.to_account_info(),
-                to: parent_post_pot.to_account_info(),
+                to: ctx
+                    .accounts
+                    .ancestor_post_pot_token_account
+                    .to_account_info(),
                 authority: ctx.accounts.post_pot_authority.to_account_info(),
             },
             seeds,
@@ -772,21 +2068,37 @@ pub mod opinions_market {
 
         anchor_spl::token::transfer(cpi, mother_fee)?;
 
-        msg!("✅ Parent post share distributed successfully");
+        emit!(ParentShareDistributed {
+            post: post_key,
+            parent_post: ancestor_post_key,
+            level,
+            token_mint: payout.token_mint,
+            amount: mother_fee,
+        });
+
+        msg!("✅ Ancestor post share distributed successfully");
 
         Ok(())
     }
 
     pub fn claim_post_reward(ctx: Context<ClaimPostReward>, post_id_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
         let clock = Clock::get()?;
         let now = clock.unix_timestamp;
 
         assert_session_or_wallet(
-            &ctx.accounts.user.key(),
-            &ctx.accounts.session_authority.user,
+            &ctx.accounts.user,
+            &ctx.accounts.session_key,
             Some(&ctx.accounts.session_authority),
             now,
         )?;
+        assert_session_privileges(
+            ctx.accounts.user.is_signer,
+            &mut ctx.accounts.session_authority,
+            SESSION_PRIVILEGE_CLAIM_POST_REWARD,
+            Some(&ctx.accounts.token_mint.key()),
+            0,
+        )?;
         let post = &ctx.accounts.post;
         let pos = &mut ctx.accounts.position;
         let claim = &mut ctx.accounts.user_post_mint_claim;
@@ -794,58 +2106,774 @@ pub mod opinions_market {
 
         require!(post.state == PostState::Settled, ErrorCode::PostNotSettled);
         require!(payout.frozen, ErrorCode::PostNotSettled); // Must be frozen (settled)
-        require!(!claim.claimed, ErrorCode::AlreadyClaimed);
+        require!(now < payout.claim_deadline, ErrorCode::ClaimWindowClosed);
+
+        // Shares of a tokenized post are burnt when claimed, so they can't be claimed twice;
+        // whoever holds them can claim, more than once if they bought more
+        let pot_mint_bit = post.pot_mint_bit(&ctx.accounts.token_mint.key())?;
+        if !post.tokenized() {
+            require!(!claim.claimed, ErrorCode::AlreadyClaimed);
+            // The position remembers claims too, so the claim record can be closed early
+            require!(
+                pos.claimed_pot_mints & pot_mint_bit == 0,
+                ErrorCode::AlreadyClaimed
+            );
+        }
 
-        let winning_side = post.winning_side.ok_or(ErrorCode::NoWinner)?;
+        // Share holders of a tokenized post may claim without ever having voted
+        if pos.user == Pubkey::default() {
+            let new_pos = UserPostPosition::new(ctx.accounts.user.key(), post.key());
+            pos.user = new_pos.user;
+            pos.post = new_pos.post;
+            pos.upvotes = new_pos.upvotes;
+            pos.downvotes = new_pos.downvotes;
+            pos.score_applied = new_pos.score_applied;
+            pos.claimed_pot_mints = new_pos.claimed_pot_mints;
+            pos.rent_payer = ctx.accounts.payer.key();
+        }
+
+        // First claim for this post and mint
+        if claim.user == Pubkey::default() {
+            let new_claim = UserPostMintClaim::new(
+                ctx.accounts.user.key(),
+                post.key(),
+                ctx.accounts.token_mint.key(),
+                ctx.bumps.user_post_mint_claim,
+            );
+            claim.user = new_claim.user;
+            claim.post = new_claim.post;
+            claim.mint = new_claim.mint;
+            claim.bump = new_claim.bump;
+            claim.rent_payer = ctx.accounts.payer.key();
+        }
 
-        let user_votes = match winning_side {
-            Side::Pump => pos.upvotes as u64,
-            Side::Smack => pos.downvotes as u64,
+        let winning_side = post.winning_side.ok_or(ErrorCode::NoWinner)?;
+        let shares = if post.tokenized() {
+            let (Some(share_mint), Some(user_share_token_account)) = (
+                &ctx.accounts.share_mint,
+                &ctx.accounts.user_share_token_account,
+            ) else {
+                return err!(ErrorCode::InvalidShareAccounts);
+            };
+            require!(
+                share_mint.key() == post.share_mint(winning_side)
+                    && user_share_token_account.mint == share_mint.key(),
+                ErrorCode::InvalidShareAccounts
+            );
+            // Burning needs the holder's own signature, a session key can't do it
+            require!(ctx.accounts.user.is_signer, ErrorCode::ShareHolderMustSign);
+
+            let shares = user_share_token_account.amount;
+            if shares > 0 {
+                anchor_spl::token::burn(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        anchor_spl::token::Burn {
+                            mint: share_mint.to_account_info(),
+                            from: user_share_token_account.to_account_info(),
+                            authority: ctx.accounts.user.to_account_info(),
+                        },
+                    ),
+                    shares,
+                )?;
+            }
+            Some(shares)
+        } else {
+            None
         };
 
-        if user_votes == 0 {
-            claim.claimed = true;
-            return Ok(()); // non-winner → no payout
+        let RewardClaim {
+            reward,
+            winning_votes,
+            social_score_delta,
+        } = pos.claim_reward(post, payout, &mut ctx.accounts.user_account, shares)?;
+        msg!("Social score delta: {}", social_score_delta);
+
+        // Non-winners and rewards too small to matter are claimed without a transfer
+        if reward > 0 {
+            let post_key = post.key();
+            let bump_array = [ctx.bumps.post_pot_authority];
+            let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+            let seeds: &[&[&[u8]]] = &[&seeds_array];
+
+            let cpi = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.post_pot_token_account.to_account_info(),
+                    to: ctx.accounts.user_vault_token_account.to_account_info(),
+                    authority: ctx.accounts.post_pot_authority.to_account_info(),
+                },
+                seeds,
+            );
+
+            anchor_spl::token::transfer(cpi, reward)?;
         }
 
-        // SCALE → unscale before transfer
-        let scaled = user_votes
-            .checked_mul(payout.payout_per_winning_vote)
-            .ok_or(ErrorCode::MathOverflow)?;
+        claim.claimed = true;
 
-        let reward = scaled
-            .checked_div(PRECISION)
-            .ok_or(ErrorCode::MathOverflow)?;
+        emit!(RewardClaimed {
+            post: post.key(),
+            user: ctx.accounts.user.key(),
+            token_mint: ctx.accounts.token_mint.key(),
+            amount: reward,
+            winning_votes,
+            social_score_delta,
+        });
+
+        Ok(())
+    }
+
+    /// Claims a user's rewards in one mint across many settled posts.
+    ///
+    /// Permissionless: anyone can pay to crank it, rewards only ever land in the user's vault.
+    /// `remaining_accounts` holds one group per post:
+    /// (post, position, post_mint_payout, post_pot_token_account, post_pot_authority,
+    /// user_post_mint_claim). Position and claim must be writable; a missing claim account
+    /// is created, funded by the payer. Already claimed posts are skipped.
+    pub fn claim_post_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimPostRewards<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        const GROUP_LEN: usize = 6;
+
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.len().is_multiple_of(GROUP_LEN),
+            ErrorCode::InvalidClaimAccounts
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let user_key = ctx.accounts.user.key();
+        let mint_key = ctx.accounts.token_mint.key();
+        let mut total_reward: u64 = 0;
+
+        for group in ctx.remaining_accounts.chunks_exact(GROUP_LEN) {
+            let [post_info, position_info, payout_info, pot_info, pot_authority_info, claim_info] =
+                group
+            else {
+                unreachable!()
+            };
+
+            let post: Account<PostAccount> = Account::try_from(post_info)?;
+            let post_key = post.key();
+            require!(post.state == PostState::Settled, ErrorCode::PostNotSettled);
+            require!(post.winning_side.is_some(), ErrorCode::NoWinner);
+
+            // Shares are burnt by their holder through claim_post_reward
+            if post.tokenized() {
+                msg!("Post {} is tokenized, skipping", post_key);
+                continue;
+            }
+
+            let mut pos: Account<UserPostPosition> = Account::try_from(position_info)?;
+            require!(
+                pos.post == post_key && pos.user == user_key,
+                ErrorCode::InvalidClaimAccounts
+            );
+
+            let payout: Account<PostMintPayout> = Account::try_from(payout_info)?;
+            require!(
+                payout.post == post_key && payout.token_mint == mint_key,
+                ErrorCode::InvalidClaimAccounts
+            );
+            require!(payout.frozen, ErrorCode::PostNotSettled);
+            require!(now < payout.claim_deadline, ErrorCode::ClaimWindowClosed);
+
+            // Checked before the claim record, which may already have been closed
+            let pot_mint_bit = post.pot_mint_bit(&mint_key)?;
+            if pos.claimed_pot_mints & pot_mint_bit != 0 {
+                msg!("Post {} already claimed, skipping", post_key);
+                continue;
+            }
+
+            let bump_array = [post.pot_authority_bump];
+            let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+            let pot_authority = Pubkey::create_program_address(&seeds_array, ctx.program_id)
+                .map_err(|_| ErrorCode::InvalidClaimAccounts)?;
+            let pot: Account<anchor_spl::token::TokenAccount> = Account::try_from(pot_info)?;
+            require!(
+                pot_authority_info.key() == pot_authority
+                    && pot.owner == pot_authority
+                    && pot.mint == mint_key,
+                ErrorCode::InvalidClaimAccounts
+            );
+
+            let mut claim = if claim_info.owner == ctx.program_id {
+                let claim: Account<UserPostMintClaim> = Account::try_from(claim_info)?;
+                require!(
+                    claim.user == user_key && claim.post == post_key && claim.mint == mint_key,
+                    ErrorCode::InvalidClaimAccounts
+                );
+                claim
+            } else {
+                // First claim for this post and mint: create the PDA like init_if_needed would
+                let (claim_pda, claim_bump) = Pubkey::find_program_address(
+                    &[
+                        USER_POST_MINT_CLAIM_SEED,
+                        post_key.as_ref(),
+                        mint_key.as_ref(),
+                        user_key.as_ref(),
+                    ],
+                    ctx.program_id,
+                );
+                require!(
+                    claim_info.key() == claim_pda,
+                    ErrorCode::InvalidClaimAccounts
+                );
+
+                let claim_bump_array = [claim_bump];
+                let claim_seeds_array = [
+                    USER_POST_MINT_CLAIM_SEED,
+                    post_key.as_ref(),
+                    mint_key.as_ref(),
+                    user_key.as_ref(),
+                    &claim_bump_array,
+                ];
+                init_pda_account(
+                    &ctx.accounts.payer.to_account_info(),
+                    claim_info,
+                    &ctx.accounts.system_program.to_account_info(),
+                    &claim_seeds_array,
+                    8 + UserPostMintClaim::INIT_SPACE,
+                    ctx.program_id,
+                )?;
+
+                let mut claim: Account<UserPostMintClaim> =
+                    Account::try_from_unchecked(claim_info)?;
+                claim.set_inner(UserPostMintClaim {
+                    rent_payer: ctx.accounts.payer.key(),
+                    ..UserPostMintClaim::new(user_key, post_key, mint_key, claim_bump)
+                });
+                claim
+            };
+
+            if claim.claimed {
+                msg!("Post {} already claimed, skipping", post_key);
+                continue;
+            }
+
+            let RewardClaim {
+                reward,
+                winning_votes,
+                social_score_delta,
+            } = pos.claim_reward(&post, &payout, &mut ctx.accounts.user_account, None)?;
+
+            if reward > 0 {
+                let seeds: &[&[&[u8]]] = &[&seeds_array];
+                let cpi = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: pot_info.clone(),
+                        to: ctx.accounts.user_vault_token_account.to_account_info(),
+                        authority: pot_authority_info.clone(),
+                    },
+                    seeds,
+                );
+                anchor_spl::token::transfer(cpi, reward)?;
+
+                total_reward = total_reward
+                    .checked_add(reward)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
 
-        if reward == 0 {
             claim.claimed = true;
-            return Ok(()); // too small to matter, claim and exit
+
+            emit!(RewardClaimed {
+                post: post_key,
+                user: user_key,
+                token_mint: mint_key,
+                amount: reward,
+                winning_votes,
+                social_score_delta,
+            });
+
+            // Accounts outside the Accounts struct are not written back automatically
+            claim.exit(ctx.program_id)?;
+            pos.exit(ctx.program_id)?;
         }
 
-        // Transfer reward
-        let post_key = post.key();
-        let (_, bump) = Pubkey::find_program_address(
-            &[POST_POT_AUTHORITY_SEED, post_key.as_ref()],
-            ctx.program_id,
+        msg!(
+            "💰 Claimed {} across {} posts for {}",
+            total_reward,
+            ctx.remaining_accounts.len() / GROUP_LEN,
+            user_key
         );
 
-        let bump_array = [bump];
+        Ok(())
+    }
+
+    /// Once the claim window has passed, sends whatever is left in a post's pot for one mint
+    /// (rounding dust and unclaimed rewards) to the protocol treasury, then closes the pot,
+    /// the PostMintPayout and the UserPostMintClaim accounts passed in `remaining_accounts`.
+    /// Pot and payout rent goes back to whoever funded them. Claims are passed as
+    /// (user_post_mint_claim, rent_payer) pairs and each refunds whoever paid for it.
+    ///
+    /// Permissionless, so the admin or any crank can call it. The creator, protocol and mother
+    /// fees must have been distributed first, so the sweep only takes what nobody is owed.
+    pub fn sweep_post_pot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepPostPot<'info>>,
+        _post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let now = Clock::get()?.unix_timestamp;
+        let payout = &ctx.accounts.post_mint_payout;
+        require!(payout.frozen, ErrorCode::PostNotSettled);
+        require!(now >= payout.claim_deadline, ErrorCode::ClaimWindowOpen);
+        require!(payout.fees_distributed(), ErrorCode::FeesNotDistributed);
+
+        let post_key = ctx.accounts.post.key();
+        let token_mint = ctx.accounts.token_mint.key();
+        let bump_array = [ctx.accounts.post.pot_authority_bump];
         let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
         let seeds: &[&[&[u8]]] = &[&seeds_array];
 
-        let cpi = CpiContext::new_with_signer(
+        let swept = ctx.accounts.post_pot_token_account.amount;
+        if swept > 0 {
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: ctx.accounts.post_pot_token_account.to_account_info(),
+                        to: ctx
+                            .accounts
+                            .protocol_token_treasury_token_account
+                            .to_account_info(),
+                        authority: ctx.accounts.post_pot_authority.to_account_info(),
+                    },
+                    seeds,
+                ),
+                swept,
+            )?;
+        }
+
+        anchor_spl::token::close_account(CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token::Transfer {
-                from: ctx.accounts.post_pot_token_account.to_account_info(),
-                to: ctx.accounts.user_vault_token_account.to_account_info(),
+            anchor_spl::token::CloseAccount {
+                account: ctx.accounts.post_pot_token_account.to_account_info(),
+                destination: ctx.accounts.pot_rent_payer.to_account_info(),
                 authority: ctx.accounts.post_pot_authority.to_account_info(),
             },
             seeds,
+        ))?;
+
+        let post = &mut ctx.accounts.post;
+        post.swept_pot_mints |= post.pot_mint_bit(&token_mint)?;
+
+        require!(
+            ctx.remaining_accounts.len().is_multiple_of(2),
+            ErrorCode::InvalidClaimAccounts
+        );
+        let claims_closed = ctx.remaining_accounts.len() / 2;
+        for pair in ctx.remaining_accounts.chunks_exact(2) {
+            let [claim_info, rent_payer_info] = pair else {
+                unreachable!()
+            };
+            let claim: Account<UserPostMintClaim> = Account::try_from(claim_info)?;
+            require!(
+                claim.post == post_key
+                    && claim.mint == token_mint
+                    && claim.rent_payer == rent_payer_info.key(),
+                ErrorCode::InvalidClaimAccounts
+            );
+            claim.close(rent_payer_info.clone())?;
+        }
+
+        emit!(PostPotSwept {
+            post: post_key,
+            token_mint,
+            amount: swept,
+            claims_closed: claims_closed as u32,
+        });
+
+        msg!(
+            "🧹 Swept {} from post {} to the treasury, closed {} claims",
+            swept,
+            post_key,
+            claims_closed
         );
 
-        anchor_spl::token::transfer(cpi, reward)?;
+        Ok(())
+    }
+
+    /// Closes a claim record once it has been claimed, refunding its rent to whoever paid for it.
+    /// The position keeps track of the claim, so it can't be made twice.
+    ///
+    /// Permissionless, so the backend or any crank can tidy up.
+    pub fn close_user_post_mint_claim(
+        ctx: Context<CloseUserPostMintClaim>,
+        _post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let claim = &ctx.accounts.user_post_mint_claim;
+        require!(claim.claimed, ErrorCode::ClaimNotMade);
+
+        emit!(UserPostAccountClosed {
+            post: claim.post,
+            user: claim.user,
+            token_mint: Some(claim.mint),
+            rent_payer: claim.rent_payer,
+        });
+
+        msg!(
+            "🧹 Closed claim of {} on post {} for mint {}",
+            claim.user,
+            claim.post,
+            claim.mint
+        );
+
+        Ok(())
+    }
+
+    /// Closes a position once its post is fully settled and every pot has been claimed by the
+    /// position or swept, refunding its rent to whoever paid for it. A position that never
+    /// claimed moves the user's social score here instead.
+    ///
+    /// Permissionless, so the backend or any crank can tidy up.
+    pub fn close_user_post_position(
+        ctx: Context<CloseUserPostPosition>,
+        _post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let pos = &ctx.accounts.position;
+        let post = &ctx.accounts.post;
+        require!(pos.fully_claimed(post), ErrorCode::PositionNotFullyClaimed);
+
+        // Cancelled posts have no winner and move no score
+        if let (PostState::Settled, Some(winning_side), false) =
+            (post.state, post.winning_side, pos.score_applied)
+        {
+            let social_score_delta = pos.social_score_delta(winning_side);
+            ctx.accounts
+                .user_account
+                .apply_social_score_delta(social_score_delta);
+            msg!("Social score delta: {}", social_score_delta);
+        }
+
+        emit!(UserPostAccountClosed {
+            post: pos.post,
+            user: pos.user,
+            token_mint: None,
+            rent_payer: pos.rent_payer,
+        });
+
+        msg!("🧹 Closed position of {} on post {}", pos.user, pos.post);
+
+        Ok(())
+    }
+
+    /// Admin takedown of an open post, e.g. for illegal or abusive content.
+    /// Voting and settlement stop. Voters (share holders on tokenized posts) get their pot
+    /// contributions back through refund_position and refund_vote_commitment; fees already
+    /// paid are not returned. Refunds stay open for the claim window, then
+    /// sweep_cancelled_post_pot sends what is left to the treasury.
+    pub fn cancel_post(ctx: Context<CancelPost>, _post_id_hash: [u8; 32]) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let post = &mut ctx.accounts.post;
+        post.state = PostState::Cancelled;
+        post.refund_deadline = now + ctx.accounts.config.claim_window_secs as i64;
+
+        msg!("⛔ Post {} cancelled", post.key());
+
+        emit!(PostCancelled {
+            post: post.key(),
+            admin: ctx.accounts.admin.key(),
+            upvotes: post.upvotes,
+            downvotes: post.downvotes,
+            refund_deadline: post.refund_deadline,
+        });
+
+        Ok(())
+    }
+
+    /// Refunds what a position put in a cancelled post's pot in one mint.
+    /// Call once per mint the user voted with. Permissionless: the refund only ever
+    /// lands in the user's vault. If the pot is short, every position takes the same cut.
+    ///
+    /// On tokenized posts the shares carry the votes, so the holder is refunded instead, by
+    /// burning the shares of one side (call once per side). Burning needs their signature.
+    pub fn refund_position(ctx: Context<RefundPosition>, _post_id_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let now = Clock::get()?.unix_timestamp;
+        let post = &mut ctx.accounts.post;
+        require!(now < post.refund_deadline, ErrorCode::ClaimWindowClosed);
+        let token_mint = ctx.accounts.token_mint.key();
+        let pot_amount = ctx.accounts.post_pot_token_account.amount;
+
+        let refund = if post.tokenized() {
+            let (Some(share_mint), Some(user_share_token_account)) = (
+                &ctx.accounts.share_mint,
+                &ctx.accounts.user_share_token_account,
+            ) else {
+                return err!(ErrorCode::InvalidShareAccounts);
+            };
+            let side = if share_mint.key() == post.share_mint(Side::Pump) {
+                Side::Pump
+            } else if share_mint.key() == post.share_mint(Side::Smack) {
+                Side::Smack
+            } else {
+                return err!(ErrorCode::InvalidShareAccounts);
+            };
+            require!(
+                user_share_token_account.mint == share_mint.key(),
+                ErrorCode::InvalidShareAccounts
+            );
+            require!(ctx.accounts.user.is_signer, ErrorCode::ShareHolderMustSign);
+
+            let shares = user_share_token_account.amount;
+            require!(shares > 0, ErrorCode::NothingToRefund);
+            let (refund, paid_bling) =
+                post.refund_for_shares(side, shares, share_mint.supply, pot_amount)?;
+
+            anchor_spl::token::burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Burn {
+                        mint: share_mint.to_account_info(),
+                        from: user_share_token_account.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                shares,
+            )?;
+            // The burnt shares take what was paid for them out of the side's total,
+            // so later holders keep the same refund per BLING paid
+            post.paid_bling[side as usize] = post.paid_bling_on(side).saturating_sub(paid_bling);
+            refund
+        } else {
+            let Some(position) = ctx.accounts.position.as_mut() else {
+                return err!(ErrorCode::NothingToRefund);
+            };
+            let contribution = position.pot_contribution(post, &token_mint)?;
+            require!(contribution > 0, ErrorCode::NothingToRefund);
+
+            let refund = post.refund_for_contribution(&token_mint, contribution, pot_amount)?;
+            position.take_pot_contribution(post, &token_mint)?;
+            refund
+        };
+
+        let post_key = post.key();
+        let bump_array = [post.pot_authority_bump];
+        let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+        let seeds: &[&[&[u8]]] = &[&seeds_array];
+
+        if refund > 0 {
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: ctx.accounts.post_pot_token_account.to_account_info(),
+                        to: ctx.accounts.user_vault_token_account.to_account_info(),
+                        authority: ctx.accounts.post_pot_authority.to_account_info(),
+                    },
+                    seeds,
+                ),
+                refund,
+            )?;
+        }
+
+        msg!(
+            "↩️ Refunded {} of {} to {}",
+            refund,
+            token_mint,
+            ctx.accounts.user.key()
+        );
+
+        emit!(PositionRefunded {
+            post: post_key,
+            user: ctx.accounts.user.key(),
+            token_mint,
+            amount: refund,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the escrow of a commitment on a cancelled post and closes it. Revealed
+    /// commitments are already closed, their votes are refunded through refund_position.
+    /// Escrows and positions share a short pot pro rata, like refund_position.
+    pub fn refund_vote_commitment(
+        ctx: Context<RefundVoteCommitment>,
+        _post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let now = Clock::get()?.unix_timestamp;
+        let post = &mut ctx.accounts.post;
+        require!(now < post.refund_deadline, ErrorCode::ClaimWindowClosed);
+        let vote_commitment = &ctx.accounts.vote_commitment;
+        let refund = post.refund_for_contribution(
+            &vote_commitment.token_mint,
+            vote_commitment.escrow,
+            ctx.accounts.post_pot_token_account.amount,
+        )?;
+        post.remove_pot_escrow(&vote_commitment.token_mint, vote_commitment.escrow)?;
+
+        let post_key = post.key();
+        let bump_array = [post.pot_authority_bump];
+        let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+        let seeds: &[&[&[u8]]] = &[&seeds_array];
+
+        if refund > 0 {
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: ctx.accounts.post_pot_token_account.to_account_info(),
+                        to: ctx.accounts.user_vault_token_account.to_account_info(),
+                        authority: ctx.accounts.post_pot_authority.to_account_info(),
+                    },
+                    seeds,
+                ),
+                refund,
+            )?;
+        }
+
+        emit!(VoteCommitmentRefunded {
+            post: post_key,
+            user: vote_commitment.user,
+            token_mint: vote_commitment.token_mint,
+            escrow: refund,
+            rent_payer: vote_commitment.rent_payer,
+        });
+
+        Ok(())
+    }
+
+    /// Once the refund deadline of a cancelled post has passed, sends whatever is left in its
+    /// pot for one mint (rounding dust and refunds nobody cranked) to the protocol treasury and
+    /// closes the pot, refunding its rent to whoever funded it. Commitments left unrevealed can
+    /// then be closed through close_vote_commitment.
+    ///
+    /// Permissionless, so the admin or any crank can call it.
+    pub fn sweep_cancelled_post_pot(
+        ctx: Context<SweepCancelledPostPot>,
+        _post_id_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.require_not_paused(PAUSE_CLAIMS)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= ctx.accounts.post.refund_deadline,
+            ErrorCode::ClaimWindowOpen
+        );
+
+        let post_key = ctx.accounts.post.key();
+        let token_mint = ctx.accounts.token_mint.key();
+        let bump_array = [ctx.accounts.post.pot_authority_bump];
+        let seeds_array = [POST_POT_AUTHORITY_SEED, post_key.as_ref(), &bump_array];
+        let seeds: &[&[&[u8]]] = &[&seeds_array];
+
+        let swept = ctx.accounts.post_pot_token_account.amount;
+        if swept > 0 {
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: ctx.accounts.post_pot_token_account.to_account_info(),
+                        to: ctx
+                            .accounts
+                            .protocol_token_treasury_token_account
+                            .to_account_info(),
+                        authority: ctx.accounts.post_pot_authority.to_account_info(),
+                    },
+                    seeds,
+                ),
+                swept,
+            )?;
+        }
+
+        anchor_spl::token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::CloseAccount {
+                account: ctx.accounts.post_pot_token_account.to_account_info(),
+                destination: ctx.accounts.pot_rent_payer.to_account_info(),
+                authority: ctx.accounts.post_pot_authority.to_account_info(),
+            },
+            seeds,
+        ))?;
+
+        let post = &mut ctx.accounts.post;
+        post.swept_pot_mints |= post.pot_mint_bit(&token_mint)?;
+
+        emit!(PostPotSwept {
+            post: post_key,
+            token_mint,
+            amount: swept,
+            claims_closed: 0,
+        });
+
+        msg!(
+            "🧹 Swept {} left from cancelled post {} to the treasury",
+            swept,
+            post_key
+        );
 
-        claim.claimed = true;
         Ok(())
     }
 }
+
+/// Creates a program-owned PDA the way Anchor's `init` does: a plain create_account when the
+/// address holds no lamports, otherwise a rent top-up, allocate and assign, so lamports sent
+/// to the address ahead of time can't block the creation.
+fn init_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    space: usize,
+    owner: &Pubkey,
+) -> Result<()> {
+    use anchor_lang::system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    };
+
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            space as u64,
+            owner,
+        );
+    }
+
+    let top_up = rent.saturating_sub(lamports);
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        owner,
+    )
+}
@@ -0,0 +1,139 @@
+use crate::constants::{
+    CURVE_BASE_MULT_BPS, EXPONENTIAL_POST_CURVE_DOUBLING_VOTES,
+    EXPONENTIAL_USER_CURVE_DOUBLING_VOTES, MAX_POST_CURVE_MULT_BPS, MAX_USER_CURVE_FACTOR,
+    MAX_VOTE_COUNT_CAP, QUADRATIC_CURVE_DIVISOR,
+};
+use crate::state::CurveKind;
+use crate::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// Per-user price factor: how much the user's previous votes on a side raise their next vote
+///
+/// - Linear: prev + 1
+/// - Quadratic: (prev + 1)^2
+/// - ExponentialCapped: 2^(prev / EXPONENTIAL_USER_CURVE_DOUBLING_VOTES)
+/// - Flat: 1
+///
+/// Result is in [1, MAX_USER_CURVE_FACTOR]
+pub fn user_curve_factor(curve: CurveKind, prev: u64) -> Result<u64> {
+    let prev = prev.min(MAX_VOTE_COUNT_CAP);
+
+    let factor = match curve {
+        CurveKind::Linear => prev.checked_add(1).ok_or(ErrorCode::MathOverflow)?,
+        CurveKind::Quadratic => {
+            // (1e6 + 1)^2 ≈ 1e12 → safe in u64, capped below
+            let n = prev.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+            n.checked_mul(n).ok_or(ErrorCode::MathOverflow)?
+        }
+        CurveKind::ExponentialCapped => pow2_saturating(
+            prev / EXPONENTIAL_USER_CURVE_DOUBLING_VOTES,
+            MAX_USER_CURVE_FACTOR,
+        ),
+        CurveKind::Flat => 1,
+    };
+
+    Ok(factor.clamp(1, MAX_USER_CURVE_FACTOR))
+}
+
+/// Post bonding curve multiplier in BPS, from the number of votes already on the side
+///
+/// - Linear: 10_000 + votes * 5
+/// - Quadratic: 10_000 + votes^2 / QUADRATIC_CURVE_DIVISOR
+/// - ExponentialCapped: 10_000 * 2^(votes / EXPONENTIAL_POST_CURVE_DOUBLING_VOTES)
+/// - Flat: 10_000
+///
+/// Result is in [CURVE_BASE_MULT_BPS, MAX_POST_CURVE_MULT_BPS]
+pub fn post_curve_mult_bps(curve: CurveKind, post_votes: u64) -> Result<u64> {
+    let post_votes = post_votes.min(MAX_VOTE_COUNT_CAP);
+
+    let growth = match curve {
+        CurveKind::Linear => post_votes.checked_mul(5).ok_or(ErrorCode::MathOverflow)?,
+        CurveKind::Quadratic => {
+            // 1e6^2 = 1e12 → safe in u64
+            post_votes
+                .checked_mul(post_votes)
+                .ok_or(ErrorCode::MathOverflow)?
+                / QUADRATIC_CURVE_DIVISOR
+        }
+        CurveKind::ExponentialCapped => {
+            let mult = CURVE_BASE_MULT_BPS.saturating_mul(pow2_saturating(
+                post_votes / EXPONENTIAL_POST_CURVE_DOUBLING_VOTES,
+                MAX_POST_CURVE_MULT_BPS,
+            ));
+            mult - CURVE_BASE_MULT_BPS
+        }
+        CurveKind::Flat => 0,
+    };
+
+    Ok(CURVE_BASE_MULT_BPS
+        .saturating_add(growth)
+        .clamp(CURVE_BASE_MULT_BPS, MAX_POST_CURVE_MULT_BPS))
+}
+
+/// 2^exp, saturating at `cap` instead of overflowing
+fn pow2_saturating(exp: u64, cap: u64) -> u64 {
+    if exp >= 63 {
+        return cap;
+    }
+    (1u64 << exp).min(cap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [CurveKind; 4] = [
+        CurveKind::Linear,
+        CurveKind::Quadratic,
+        CurveKind::ExponentialCapped,
+        CurveKind::Flat,
+    ];
+
+    #[test]
+    fn test_curves_bounded_and_monotonic() {
+        for curve in ALL {
+            assert_eq!(user_curve_factor(curve, 0).unwrap(), 1);
+            assert_eq!(post_curve_mult_bps(curve, 0).unwrap(), CURVE_BASE_MULT_BPS);
+
+            let mut last_user = 0;
+            let mut last_post = 0;
+            for n in [
+                0,
+                1,
+                9,
+                10,
+                99,
+                100,
+                1_000,
+                50_000,
+                MAX_VOTE_COUNT_CAP,
+                u64::MAX,
+            ] {
+                let user = user_curve_factor(curve, n).unwrap();
+                let post = post_curve_mult_bps(curve, n).unwrap();
+                assert!(user >= last_user && user <= MAX_USER_CURVE_FACTOR);
+                assert!(post >= last_post && post <= MAX_POST_CURVE_MULT_BPS);
+                last_user = user;
+                last_post = post;
+            }
+        }
+
+        // Linear keeps the original curve
+        assert_eq!(user_curve_factor(CurveKind::Linear, 41).unwrap(), 42);
+        assert_eq!(post_curve_mult_bps(CurveKind::Linear, 100).unwrap(), 10_500);
+        // Quadratic and exponential grow faster, flat never moves
+        assert_eq!(user_curve_factor(CurveKind::Quadratic, 9).unwrap(), 100);
+        assert_eq!(
+            user_curve_factor(CurveKind::ExponentialCapped, 30).unwrap(),
+            8
+        );
+        assert_eq!(
+            post_curve_mult_bps(CurveKind::ExponentialCapped, 200).unwrap(),
+            40_000
+        );
+        assert_eq!(
+            post_curve_mult_bps(CurveKind::Flat, MAX_VOTE_COUNT_CAP).unwrap(),
+            CURVE_BASE_MULT_BPS
+        );
+    }
+}
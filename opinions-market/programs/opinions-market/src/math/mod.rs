@@ -1,2 +1,3 @@
+pub mod curves;
 pub mod token_conversion;
 pub mod vote_cost;
@@ -103,6 +103,30 @@ pub fn convert_bling_fees_to_token(
     ))
 }
 
+/// Convert token lamports back to BLING lamports, the inverse of `convert_bling_to_token_lamports`
+///
+/// # Formula
+/// bling_lamports = (token_lamports * price_in_bling * 10^bling_decimals) / 10^token_decimals
+pub fn convert_token_to_bling_lamports(
+    token_lamports: u64,
+    price_in_bling: u64,
+    token_decimals: u8,
+) -> Result<u64> {
+    // If converting from BLING, no conversion needed
+    if token_decimals == BLING_DECIMALS && price_in_bling == 1 {
+        return Ok(token_lamports);
+    }
+
+    // u128: a large amount times price and 10^9 does not fit in u64
+    let bling = (token_lamports as u128)
+        .checked_mul(price_in_bling as u128)
+        .and_then(|v| v.checked_mul(10u128.pow(BLING_DECIMALS as u32)))
+        .ok_or_else(|| Error::from(ErrorCode::MathOverflow))?
+        / 10u128.pow(token_decimals as u32);
+
+    u64::try_from(bling).map_err(|_| Error::from(ErrorCode::MathOverflow))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,4 +175,21 @@ mod tests {
         // 1.8 BLING / 10,000 = 0.00018 USDC = 180 lamports
         assert_eq!(pot_token, 180u64);
     }
+
+    #[test]
+    fn test_convert_token_to_bling() {
+        // 200 USDC lamports at 1 USDC = 10,000 BLING is 2 BLING
+        let bling = convert_token_to_bling_lamports(200, 10_000, USDC_DECIMALS).unwrap();
+        assert_eq!(bling, 2_000_000_000u64);
+        assert_eq!(
+            convert_bling_to_usdc_lamports(bling, 10_000).unwrap(),
+            200u64
+        );
+
+        let bling_lamports = 1_000_000_000u64;
+        assert_eq!(
+            convert_token_to_bling_lamports(bling_lamports, 1, BLING_DECIMALS).unwrap(),
+            bling_lamports
+        );
+    }
 }
@@ -1,5 +1,6 @@
-use crate::constants::{MAX_VOTE_COUNT_CAP, PARAMS, SMACK_TO_PUMP_PRICE_RATIO};
-use crate::state::{PostType, Side, UserAccount};
+use crate::constants::{CURVE_BASE_MULT_BPS, MAX_VOTE_COUNT_CAP, SMACK_TO_PUMP_PRICE_RATIO};
+use crate::math::curves::{post_curve_mult_bps, user_curve_factor};
+use crate::state::{CurveKind, PostType, Side, UserAccount};
 use crate::ErrorCode;
 use anchor_lang::prelude::*;
 
@@ -40,11 +41,13 @@ pub fn social_score_multiplier(user_account: &UserAccount) -> Result<u64> {
 /// - votes: Number of votes being cast
 /// - prev: Previous votes on this side (for this user on this post)
 /// - side: Pump or Smack
+/// - curve: Post curve, decides how `prev` raises the price
 /// - user_account: User account for social score
 pub fn base_user_cost(
     votes: u64,
     prev: u64,
     side: Side,
+    curve: CurveKind,
     user_account: &UserAccount,
 ) -> Result<u64> {
     // ---- FIXED CAPS (core overflow prevention) ----
@@ -59,9 +62,9 @@ pub fn base_user_cost(
     };
 
     // ---- CORE RAW COST ----
-    // raw = votes * side_mult * (prev + 1)
-    // max raw = MAX_VOTE_COUNT_CAP * SMACK_TO_PUMP_PRICE_RATIO * (MAX_VOTE_COUNT_CAP + 1) ≈ 1e13 → safe in u64
-    let raw = votes * side_mult * (prev + 1);
+    // raw = votes * side_mult * user_curve_factor(prev)   (Linear: prev + 1)
+    // max raw = MAX_VOTE_COUNT_CAP * SMACK_TO_PUMP_PRICE_RATIO * MAX_USER_CURVE_FACTOR ≈ 1e13 → safe in u64
+    let raw = votes * side_mult * user_curve_factor(curve, prev)?;
 
     // ---- APPLY SOCIAL MULTIPLIER (BPS) ----
     // max (raw * 20_000) < 2e17 → safe
@@ -79,12 +82,14 @@ pub fn base_user_cost(
 /// - post_downvotes: Number of downvotes on the post
 /// - side: Pump or Smack (determines which vote count to use)
 /// - post_type: Original or Child (child posts get +10%)
+/// - curve: Post curve, see `math::curves::post_curve_mult_bps`
 pub fn post_curve_cost(
     unadjusted_cost: u64,
     post_upvotes: u64,
     post_downvotes: u64,
     side: Side,
     post_type: PostType,
+    curve: CurveKind,
 ) -> Result<u64> {
     let post_votes = match side {
         Side::Pump => post_upvotes,
@@ -92,26 +97,28 @@ pub fn post_curve_cost(
     }
     .min(MAX_VOTE_COUNT_CAP);
 
-    // Bonding curve: 10_000 → 10_000 + post_votes*5
-    let curve_mult_bps = (10_000 + post_votes * 5).clamp(10_000, MAX_VOTE_COUNT_CAP);
+    // Bonding curve: 10_000 BPS → MAX_POST_CURVE_MULT_BPS
+    let curve_mult_bps = post_curve_mult_bps(curve, post_votes)?;
 
-    let mut cost = (unadjusted_cost * curve_mult_bps) / 10_000;
+    // Up to 100x on a ≈2e17 base would overflow u64, so multiply in u128
+    let mut cost = (unadjusted_cost as u128 * curve_mult_bps as u128) / CURVE_BASE_MULT_BPS as u128;
 
     // Child posts incur +10%
     if matches!(post_type, PostType::Child { .. }) {
         cost = (cost * 11_000) / 10_000;
     }
 
+    let cost = u64::try_from(cost).map_err(|_| ErrorCode::MathOverflow)?;
     Ok(cost.max(1))
 }
 
 /// Convert vote cost from vote units to BLING lamports
-/// Applies the base cost multiplier and ensures minimum cost
-pub fn cost_in_bling(raw_cost: u64) -> Result<u64> {
+/// Applies the base cost multiplier (`Config.bling_per_vote_base_cost`) and ensures minimum cost
+pub fn cost_in_bling(raw_cost: u64, bling_per_vote_base_cost: u64) -> Result<u64> {
     let cost = raw_cost
-        .checked_mul(PARAMS.bling_per_vote_base_cost)
+        .checked_mul(bling_per_vote_base_cost)
         .ok_or(ErrorCode::MathOverflow)?;
 
-    // Minimum cost is 1 SOL worth of BLING
-    Ok(cost.max(PARAMS.bling_per_vote_base_cost))
+    // Minimum cost is one base vote worth of BLING
+    Ok(cost.max(bling_per_vote_base_cost))
 }
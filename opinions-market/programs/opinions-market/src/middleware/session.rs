@@ -5,12 +5,123 @@ use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_check
 
 use crate::state::SessionAuthority;
 
+/// Actions a user can authorize with a signed session message
+pub const SESSION_ACTION_REGISTER: &str = "SESSION";
+pub const SESSION_ACTION_REVOKE: &str = "REVOKE";
+pub const SESSION_ACTION_ROTATE: &str = "ROTATE";
+
+/// Message the user signs to authorize a session action:
+/// `{action}:{program_id}:{session_key}:{expires_at}:{privileges_hash in hex}:{nonce}`
+///
+/// `nonce` is the user's `UserAccount::session_nonce`, bumped by every session action,
+/// so each signed message is good for one use.
+pub fn session_message(
+    action: &str,
+    program_id: &Pubkey,
+    session_key: &Pubkey,
+    expires_at: i64,
+    privileges_hash: &[u8; 32],
+    nonce: u64,
+) -> String {
+    let privileges_hex: String = privileges_hash
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!(
+        "{}:{}:{}:{}:{}:{}",
+        action, program_id, session_key, expires_at, privileges_hex, nonce
+    )
+}
+
+/// Message the user signs to rotate `old_session_key` out for `new_session_key`: the session
+/// message for ROTATE naming the new key, followed by `:{old_session_key}`, so a signature
+/// over one rotation can't be replayed against another session with the same privileges
+/// and expiry.
+pub fn rotate_session_message(
+    program_id: &Pubkey,
+    old_session_key: &Pubkey,
+    new_session_key: &Pubkey,
+    expires_at: i64,
+    privileges_hash: &[u8; 32],
+    nonce: u64,
+) -> String {
+    format!(
+        "{}:{}",
+        session_message(
+            SESSION_ACTION_ROTATE,
+            program_id,
+            new_session_key,
+            expires_at,
+            privileges_hash,
+            nonce
+        ),
+        old_session_key
+    )
+}
+
+/// The `expires_at` a session message commits to
+pub fn session_message_expires_at(message: &[u8]) -> Option<i64> {
+    let message = core::str::from_utf8(message).ok()?;
+    message.split(':').nth(3)?.parse().ok()
+}
+
+/// Signer pubkey and message of an Ed25519 verify instruction.
+///
+/// Layout (see the ed25519 native program):
+/// [0]      num_signatures (must be 1)
+/// [1]      padding
+/// [2..16]  signature_offset, signature_instruction_index, public_key_offset,
+///          public_key_instruction_index, message_data_offset, message_data_size,
+///          message_instruction_index (u16 LE each)
+///
+/// All instruction indexes must be u16::MAX ("this instruction"), otherwise the
+/// verified bytes could live in another instruction than the ones we read.
+pub fn parse_ed25519_instruction(data: &[u8]) -> Result<(Pubkey, &[u8])> {
+    const OFFSETS_START: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+
+    require!(
+        data.len() >= OFFSETS_START + OFFSETS_LEN && data[0] == 1,
+        SessionError::InvalidSignatureInstruction
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_instruction_index = read_u16(OFFSETS_START + 2);
+    let public_key_offset = read_u16(OFFSETS_START + 4) as usize;
+    let public_key_instruction_index = read_u16(OFFSETS_START + 6);
+    let message_data_offset = read_u16(OFFSETS_START + 8) as usize;
+    let message_data_size = read_u16(OFFSETS_START + 10) as usize;
+    let message_instruction_index = read_u16(OFFSETS_START + 12);
+
+    require!(
+        signature_instruction_index == u16::MAX
+            && public_key_instruction_index == u16::MAX
+            && message_instruction_index == u16::MAX,
+        SessionError::InvalidSignatureInstruction
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(SessionError::InvalidSignatureInstruction)?;
+    let message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(SessionError::InvalidSignatureInstruction)?;
+
+    let signer_pubkey =
+        Pubkey::try_from(public_key).map_err(|_| SessionError::UnauthorizedSigner)?;
+
+    Ok((signer_pubkey, message))
+}
+
+/// Checks that the Ed25519 verify instruction at `expected_index` is the user's signature over
+/// `expected_message(expires_at)`, for the `expires_at` the signed message commits to.
+/// Returns that `expires_at`.
 pub fn validate_session_signature<'info>(
     user: &Pubkey,
     expected_index: u8,
     instructions_sysvar: &UncheckedAccount<'info>,
-    expires_at: i64,
-) -> Result<()> {
+    expected_message: impl FnOnce(i64) -> String,
+) -> Result<i64> {
     // ---- Load Ed25519 verify instruction from tx instruction list ----
     let ix = load_instruction_at_checked(expected_index as usize, instructions_sysvar)?;
 
@@ -20,47 +131,69 @@ pub fn validate_session_signature<'info>(
         SessionError::InvalidSignatureInstruction
     );
 
-    //
-    // ---- Extract verifying pubkey from instruction data ----
-    //
-    // Ed25519Verify instruction structure:
-    // see solana docs: https://docs.solana.com/developing/runtime-facilities/programs#ed25519-program
-    //
-    // layout:
-    // [0..1]  num_signatures
-    // [1..x]  struct describing signatures
-    // pubkey is commonly at bytes 16..48
-    //
-    let signer_pubkey_bytes = &ix.data[16..48];
-    let signer_pubkey =
-        Pubkey::try_from(signer_pubkey_bytes).map_err(|_| SessionError::UnauthorizedSigner)?;
+    let (signer_pubkey, message) = parse_ed25519_instruction(&ix.data)?;
 
     // Ensure the validated signature came from the user we expect
     require!(signer_pubkey == *user, SessionError::UnauthorizedSigner);
 
+    // Ensure the user signed exactly this action
+    let expires_at =
+        session_message_expires_at(message).ok_or(SessionError::InvalidSessionMessage)?;
+    let expected = expected_message(expires_at);
+    require!(
+        message == expected.as_bytes(),
+        SessionError::InvalidSessionMessage
+    );
+
+    Ok(expires_at)
+}
+
+/// Wallet authorization for managing sessions: either the user signs the transaction,
+/// or an Ed25519 verify instruction at `expected_index` carries the user's signature
+/// over `expected_message`, built by the caller from the session it acts on.
+pub fn assert_wallet_authorized<'info>(
+    user: &UncheckedAccount<'info>,
+    expected_index: Option<u8>,
+    instructions_sysvar: &UncheckedAccount<'info>,
+    expected_message: String,
+) -> Result<()> {
+    if user.is_signer {
+        return Ok(());
+    }
+
+    let expected_index = expected_index.ok_or(SessionError::UnauthorizedSigner)?;
+    validate_session_signature(&user.key(), expected_index, instructions_sysvar, |_| {
+        expected_message
+    })?;
+
     Ok(())
 }
 
+/// Passes when `user` signed, or when `session_key` signed for an unexpired session of `user`.
 pub fn assert_session_or_wallet<'info>(
-    signer: &Pubkey,
-    user: &Pubkey,
+    user: &AccountInfo<'info>,
+    session_key: &AccountInfo<'info>,
     session_opt: Option<&Account<SessionAuthority>>,
     now: i64,
 ) -> Result<()> {
     // Normal wallet signing → allowed
-    if signer == user {
+    if user.is_signer {
         return Ok(());
     }
 
-    // Session flow
+    // Session flow: the delegated key has to sign in the user's place
+    require!(session_key.is_signer, SessionError::UnauthorizedSigner);
     let session = match session_opt {
         Some(s) => s,
         None => return Err(SessionError::InvalidSessionKey.into()),
     };
 
-    require!(session.user == *user, SessionError::InvalidSessionOwner);
     require!(
-        session.session_key == *signer,
+        session.user == user.key(),
+        SessionError::InvalidSessionOwner
+    );
+    require!(
+        session.session_key == session_key.key(),
         SessionError::InvalidSessionKey
     );
     require!(now < session.expires_at, SessionError::SessionExpired);
@@ -68,6 +201,21 @@ pub fn assert_session_or_wallet<'info>(
     Ok(())
 }
 
+/// Enforces the session's privileges when the user did not sign with their own wallet.
+pub fn assert_session_privileges(
+    user_signed: bool,
+    session: &mut Account<SessionAuthority>,
+    privilege: u32,
+    mint: Option<&Pubkey>,
+    bling_spend: u64,
+) -> Result<()> {
+    if user_signed {
+        return Ok(());
+    }
+
+    session.check_privileges(privilege, mint, bling_spend)
+}
+
 #[error_code]
 pub enum SessionError {
     #[msg("Invalid session key")]
@@ -80,4 +228,223 @@ pub enum SessionError {
     InvalidSignatureInstruction,
     #[msg("Unauthorized signer")]
     UnauthorizedSigner,
+    #[msg("Session is not allowed to call this instruction")]
+    InstructionNotAllowed,
+    #[msg("Session is not allowed to use this mint")]
+    MintNotAllowed,
+    #[msg("Session BLING spend cap exceeded")]
+    SpendCapExceeded,
+    #[msg("Signed session message does not match this session")]
+    InvalidSessionMessage,
+    #[msg("Session expiry out of range")]
+    InvalidSessionExpiry,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ed25519_data(pubkey: &Pubkey, message: &[u8], instruction_index: u16) -> Vec<u8> {
+        // header, offsets, pubkey, signature, message - as the SDK lays it out
+        let public_key_offset: u16 = 16;
+        let signature_offset: u16 = public_key_offset + 32;
+        let message_data_offset: u16 = signature_offset + 64;
+
+        let mut data = vec![1u8, 0];
+        for v in [
+            signature_offset,
+            instruction_index,
+            public_key_offset,
+            instruction_index,
+            message_data_offset,
+            message.len() as u16,
+            instruction_index,
+        ] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(&[7u8; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_parse_ed25519_instruction() {
+        let user = Pubkey::new_unique();
+        let message = session_message(
+            SESSION_ACTION_REGISTER,
+            &crate::ID,
+            &Pubkey::new_unique(),
+            1_700_000_000,
+            &[3u8; 32],
+            0,
+        );
+
+        let data = ed25519_data(&user, message.as_bytes(), u16::MAX);
+        let (signer, signed) = parse_ed25519_instruction(&data).unwrap();
+        assert_eq!(signer, user);
+        assert_eq!(signed, message.as_bytes());
+        assert_eq!(session_message_expires_at(signed), Some(1_700_000_000));
+
+        // data pointing into another instruction is rejected
+        let data = ed25519_data(&user, message.as_bytes(), 0);
+        assert!(parse_ed25519_instruction(&data).is_err());
+
+        // truncated data is rejected
+        let data = ed25519_data(&user, message.as_bytes(), u16::MAX);
+        assert!(parse_ed25519_instruction(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_session_message_commits_to_every_field() {
+        let session_key = Pubkey::new_unique();
+        let base = session_message(
+            SESSION_ACTION_REGISTER,
+            &crate::ID,
+            &session_key,
+            100,
+            &[0u8; 32],
+            0,
+        );
+
+        assert_ne!(
+            base,
+            session_message(
+                SESSION_ACTION_REVOKE,
+                &crate::ID,
+                &session_key,
+                100,
+                &[0u8; 32],
+                0
+            )
+        );
+        assert_ne!(
+            base,
+            session_message(
+                SESSION_ACTION_REGISTER,
+                &Pubkey::new_unique(),
+                &session_key,
+                100,
+                &[0u8; 32],
+                0
+            )
+        );
+        assert_ne!(
+            base,
+            session_message(
+                SESSION_ACTION_REGISTER,
+                &crate::ID,
+                &session_key,
+                101,
+                &[0u8; 32],
+                0
+            )
+        );
+        assert_ne!(
+            base,
+            session_message(
+                SESSION_ACTION_REGISTER,
+                &crate::ID,
+                &session_key,
+                100,
+                &[1u8; 32],
+                0
+            )
+        );
+        assert_ne!(
+            base,
+            session_message(
+                SESSION_ACTION_REGISTER,
+                &crate::ID,
+                &session_key,
+                100,
+                &[0u8; 32],
+                1
+            )
+        );
+
+        // A rotation names the old key as well as the new one
+        let old_session_key = Pubkey::new_unique();
+        let rotate = rotate_session_message(
+            &crate::ID,
+            &old_session_key,
+            &session_key,
+            100,
+            &[0u8; 32],
+            0,
+        );
+        assert_ne!(
+            rotate,
+            session_message(
+                SESSION_ACTION_ROTATE,
+                &crate::ID,
+                &session_key,
+                100,
+                &[0u8; 32],
+                0
+            )
+        );
+        assert_ne!(
+            rotate,
+            rotate_session_message(
+                &crate::ID,
+                &Pubkey::new_unique(),
+                &session_key,
+                100,
+                &[0u8; 32],
+                0
+            )
+        );
+        assert_ne!(
+            rotate,
+            rotate_session_message(
+                &crate::ID,
+                &old_session_key,
+                &Pubkey::new_unique(),
+                100,
+                &[0u8; 32],
+                0
+            )
+        );
+        assert_eq!(session_message_expires_at(rotate.as_bytes()), Some(100));
+    }
+
+    #[test]
+    fn test_session_or_wallet_requires_a_signer() {
+        let user = Pubkey::new_unique();
+        let third_party = Pubkey::new_unique();
+        let owner = Pubkey::default();
+        let (mut user_lamports, mut key_lamports) = (0u64, 0u64);
+        let (mut user_data, mut key_data) = ([0u8; 0], [0u8; 0]);
+
+        let mut user_info = AccountInfo::new(
+            &user,
+            false,
+            false,
+            &mut user_lamports,
+            &mut user_data,
+            &owner,
+            false,
+            0,
+        );
+        let session_key_info = AccountInfo::new(
+            &third_party,
+            false,
+            false,
+            &mut key_lamports,
+            &mut key_data,
+            &owner,
+            false,
+            0,
+        );
+
+        // Naming a key that did not sign is not enough to act for the user
+        assert_eq!(
+            assert_session_or_wallet(&user_info, &session_key_info, None, 0).unwrap_err(),
+            SessionError::UnauthorizedSigner.into()
+        );
+
+        user_info.is_signer = true;
+        assert!(assert_session_or_wallet(&user_info, &session_key_info, None, 0).is_ok());
+    }
 }
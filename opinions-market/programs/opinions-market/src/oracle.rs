@@ -0,0 +1,239 @@
+use crate::constants::MAX_BPS;
+use crate::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+/// Pyth Solana receiver, owner of every `PriceUpdateV2` account, sponsored price feeds included
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Anchor discriminator of `PriceUpdateV2`: sha256("account:PriceUpdateV2")[..8]
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Layout of Pyth's `PriceUpdateV2` account, after the discriminator.
+/// Mirrors pyth-solana-receiver-sdk, read by hand so the program doesn't pull in the SDK.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PriceUpdateV2 {
+    pub write_authority: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub price_message: PriceFeedMessage,
+    pub posted_slot: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+/// A price read off a feed account: `price * 10^exponent` units of the feed's quote
+/// currency (USD for Pyth's crypto feeds) for one whole token, `conf` in the same unit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FeedPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl FeedPrice {
+    /// Reads a Pyth `PriceUpdateV2` account. Only fully verified updates are accepted: a
+    /// partially verified one was checked against fewer Wormhole guardian signatures.
+    /// With the `pushed-price-feed` feature, a PushedPriceFeed of this program is read too.
+    pub fn read(owner: &Pubkey, data: &[u8]) -> Result<Self> {
+        if *owner == PYTH_RECEIVER_PROGRAM_ID {
+            require!(
+                data.len() >= 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+                ErrorCode::InvalidPriceFeed
+            );
+            let update = PriceUpdateV2::deserialize(&mut &data[8..])
+                .map_err(|_| ErrorCode::InvalidPriceFeed)?;
+            require!(
+                update.verification_level == VerificationLevel::Full,
+                ErrorCode::InvalidPriceFeed
+            );
+
+            let message = update.price_message;
+            return Ok(Self {
+                price: message.price,
+                conf: message.conf,
+                exponent: message.exponent,
+                publish_time: message.publish_time,
+            });
+        }
+
+        #[cfg(feature = "pushed-price-feed")]
+        if *owner == crate::ID {
+            let feed = crate::state::PushedPriceFeed::try_deserialize(&mut &data[..])?;
+            return Ok(Self {
+                price: feed.price,
+                conf: feed.conf,
+                exponent: feed.exponent,
+                publish_time: feed.publish_time,
+            });
+        }
+
+        err!(ErrorCode::InvalidPriceFeed)
+    }
+
+    /// Rejects a price published more than `max_staleness_secs` ago, or whose confidence
+    /// interval is wider than `max_confidence_bps` of the price
+    pub fn check(&self, now: i64, max_staleness_secs: u32, max_confidence_bps: u16) -> Result<()> {
+        require!(self.price > 0, ErrorCode::InvalidPrice);
+        require!(
+            self.publish_time.saturating_add(max_staleness_secs as i64) >= now,
+            ErrorCode::StalePrice
+        );
+
+        // conf / price <= max_confidence_bps / 10_000
+        require!(
+            (self.conf as u128) * (MAX_BPS as u128)
+                <= (self.price as u128) * (max_confidence_bps as u128),
+            ErrorCode::PriceConfidenceTooWide
+        );
+
+        Ok(())
+    }
+
+    /// The price as `ValidPayment.price_in_bling`, with one unit of the quote currency
+    /// worth `bling_per_feed_unit` BLING
+    pub fn price_in_bling(&self, bling_per_feed_unit: u64) -> Result<u64> {
+        require!(self.price > 0, ErrorCode::InvalidPrice);
+
+        let scale = 10u128
+            .checked_pow(self.exponent.unsigned_abs())
+            .ok_or(ErrorCode::MathOverflow)?;
+        let value = (self.price as u128)
+            .checked_mul(bling_per_feed_unit as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let price_in_bling = if self.exponent < 0 {
+            value / scale
+        } else {
+            value.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?
+        };
+
+        // A token worth less than one BLING can't be priced in whole BLING
+        require!(price_in_bling > 0, ErrorCode::InvalidPrice);
+        u64::try_from(price_in_bling).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyth_account_data(verification_level: VerificationLevel, price: i64, conf: u64) -> Vec<u8> {
+        let update = PriceUpdateV2 {
+            write_authority: Pubkey::new_unique(),
+            verification_level,
+            price_message: PriceFeedMessage {
+                feed_id: [7; 32],
+                price,
+                conf,
+                exponent: -8,
+                publish_time: 1_000,
+                prev_publish_time: 999,
+                ema_price: price,
+                ema_conf: conf,
+            },
+            posted_slot: 42,
+        };
+        let mut data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        update.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_read_pyth_price_update() {
+        // $1.0002 ± $0.0001
+        let data = pyth_account_data(VerificationLevel::Full, 100_020_000, 10_000);
+        let price = FeedPrice::read(&PYTH_RECEIVER_PROGRAM_ID, &data).unwrap();
+        assert_eq!(price.price, 100_020_000);
+        assert_eq!(price.exponent, -8);
+        assert_eq!(price.publish_time, 1_000);
+
+        // Same bytes under another owner are not a Pyth account
+        assert!(FeedPrice::read(&Pubkey::new_unique(), &data).is_err());
+
+        // Partially verified updates are refused
+        let data = pyth_account_data(
+            VerificationLevel::Partial { num_signatures: 5 },
+            100_020_000,
+            10_000,
+        );
+        assert!(FeedPrice::read(&PYTH_RECEIVER_PROGRAM_ID, &data).is_err());
+
+        // Wrong discriminator
+        let mut data = pyth_account_data(VerificationLevel::Full, 100_020_000, 10_000);
+        data[0] ^= 1;
+        assert!(FeedPrice::read(&PYTH_RECEIVER_PROGRAM_ID, &data).is_err());
+    }
+
+    #[test]
+    fn test_feed_price_checks() {
+        let price = FeedPrice {
+            price: 100_000_000,
+            conf: 10_000,
+            exponent: -8,
+            publish_time: 1_000,
+        };
+
+        // fresh and tight → ok
+        assert!(price.check(1_030, 60, 100).is_ok());
+
+        // too old
+        assert!(price.check(1_061, 60, 100).is_err());
+
+        // confidence 10_000 / 100_000_000 = 1 bps > 0 bps
+        assert!(price.check(1_030, 60, 0).is_err());
+
+        // never published
+        let unpublished = FeedPrice { price: 0, ..price };
+        assert!(unpublished.check(1_030, 60, 100).is_err());
+    }
+
+    #[test]
+    fn test_feed_price_in_bling() {
+        // $1.00 with 1 USD = 10_000 BLING
+        let price = FeedPrice {
+            price: 100_000_000,
+            conf: 0,
+            exponent: -8,
+            publish_time: 0,
+        };
+        assert_eq!(price.price_in_bling(10_000).unwrap(), 10_000);
+
+        // $150.25, and a price at a positive exponent
+        let price = FeedPrice {
+            price: 15_025,
+            exponent: -2,
+            ..price
+        };
+        assert_eq!(price.price_in_bling(10_000).unwrap(), 1_502_500);
+        let price = FeedPrice {
+            price: 3,
+            exponent: 2,
+            ..price
+        };
+        assert_eq!(price.price_in_bling(10).unwrap(), 3_000);
+
+        // Worth less than one BLING
+        let price = FeedPrice {
+            price: 1,
+            exponent: -8,
+            ..price
+        };
+        assert!(price.price_in_bling(10_000).is_err());
+    }
+}
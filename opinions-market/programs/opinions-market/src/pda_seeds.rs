@@ -3,6 +3,8 @@ pub const CONFIG_SEED: &[u8] = b"config";
 pub const SESSION_AUTHORITY_SEED: &[u8] = b"session_authority";
 
 pub const VALID_PAYMENT_SEED: &[u8] = b"valid_payment";
+#[cfg(feature = "pushed-price-feed")]
+pub const PUSHED_PRICE_FEED_SEED: &[u8] = b"pushed_price_feed";
 pub const USER_ACCOUNT_SEED: &[u8] = b"user_account";
 pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault_authority";
 pub const USER_VAULT_TOKEN_ACCOUNT_SEED: &[u8] = b"user_vault_token_account";
@@ -16,3 +18,8 @@ pub const PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED: &[u8] = b"protocol_treasury_toke
 pub const POST_MINT_PAYOUT_SEED: &[u8] = b"post_mint_payout";
 
 pub const USER_POST_MINT_CLAIM_SEED: &[u8] = b"user_post_mint_claim";
+
+pub const VOTE_COMMITMENT_SEED: &[u8] = b"vote_commitment";
+
+// One share mint per post and side: [POST_SHARE_MINT_SEED, post, &[side as u8]]
+pub const POST_SHARE_MINT_SEED: &[u8] = b"post_share_mint";
@@ -1,7 +1,17 @@
-use crate::constants::{MAX_VOTE_COUNT_CAP, PARAMS};
+use crate::constants::{
+    CREATOR_PUMP_WIN_SOCIAL_SCORE, MAX_BPS, MAX_MOTHER_FEE_BPS, MAX_MOTHER_FEE_LEVELS,
+    MAX_POST_POT_MINTS, MAX_SESSION_ALLOWED_MINTS, MAX_SOCIAL_SCORE_DELTA_PER_POST,
+    MAX_VOTE_COUNT_CAP, MAX_VOTE_FEE_BPS, MAX_WITHDRAW_PENALTY_BPS, PARAMS, PRECISION,
+    SOCIAL_SCORE_PER_LOSING_SMACK_VOTE, SOCIAL_SCORE_PER_WINNING_VOTE,
+    WITHDRAW_PENALTY_AT_ZERO_SCORE_BPS,
+};
+use crate::math::token_conversion::{convert_bling_fees_to_token, convert_bling_to_token_lamports};
 use crate::math::vote_cost::{base_user_cost, cost_in_bling, post_curve_cost};
+use crate::middleware::session::SessionError;
+use crate::oracle::FeedPrice;
 use crate::ErrorCode;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
 
 // -----------------------------------------------------------------------------
 // ACCOUNTS
@@ -10,28 +20,49 @@ use anchor_lang::prelude::*;
 #[account]
 #[derive(InitSpace, Copy, PartialEq, Eq, Debug)]
 pub struct Config {
+    /// Any signer works, including a multisig vault PDA signing through CPI
     pub admin: Pubkey,
+    /// Set by propose_admin, becomes `admin` once it signs accept_admin. Default when none
+    pub pending_admin: Pubkey,
     pub payer_authroity: Pubkey,
     pub bling_mint: Pubkey,
 
-    // pub protocol_vote_fee_bps: u16,
-    // pub protocol_vote_settlement_fee_bps: u16,
-
-    // pub creator_pump_vote_fee_bps: u16,
-    // pub creator_vote_settlement_fee_bps: u16,
     pub base_duration_secs: u32,
     pub max_duration_secs: u32,
     pub extension_per_vote_secs: u32,
 
-    // pub vote_per_bling_base_cost: u64,
-    // /// 1 vote = 1 * LAMPORTS_PER_SOL by default
-    // pub user_initial_social_score: i64,
+    pub protocol_vote_fee_bps: u16,
+    pub protocol_vote_settlement_fee_bps: u16,
+    pub creator_pump_fee_bps: u16,
+    pub creator_pump_win_settlement_fee_bps: u16,
+    /// Share of an exit_position refund that stays in the pot
+    pub exit_fee_bps: u16,
+    /// Parent's share of a settling child post's pot
+    pub mother_fee_bps: u16,
+    /// Each ancestor further up the reply tree gets this share of the bps of the level below
+    pub mother_fee_decay_bps: u16,
+
+    /// 1 vote = 1 * LAMPORTS_PER_SOL by default
+    pub bling_per_vote_base_cost: u64,
     /// 10_000 by default
+    pub user_initial_social_score: i64,
+    /// How long after settlement rewards can be claimed before the pot can be swept
+    pub claim_window_secs: u32,
+    /// How long after end_time the votes on a commit–reveal post can be revealed
+    pub reveal_window_secs: u32,
+    /// How many ancestors a settling post pays a mother fee to, 0 turns it off
+    pub mother_fee_levels: u8,
+    /// Bitmap of `CurveKind::bit()` a post may be created with
+    pub allowed_curves: u8,
+    /// Bitmap of PAUSE_* flags, set by the admin through set_pause
+    pub paused: u8,
+
     pub bump: u8,
     pub padding: [u8; 7], // 7
 }
 
 impl Config {
+    /// Fees start from the defaults in `PARAMS`; the admin can change them later via `update_config`.
     pub fn new(
         admin: Pubkey,
         payer_authroity: Pubkey,
@@ -44,15 +75,180 @@ impl Config {
     ) -> Self {
         Self {
             admin,
+            pending_admin: Pubkey::default(),
             payer_authroity,
             bling_mint,
             base_duration_secs,
             max_duration_secs,
             extension_per_vote_secs,
+            protocol_vote_fee_bps: PARAMS.protocol_vote_fee_bps,
+            protocol_vote_settlement_fee_bps: PARAMS.protocol_vote_settlement_fee_bps,
+            creator_pump_fee_bps: PARAMS.creator_pump_fee_bps,
+            creator_pump_win_settlement_fee_bps: PARAMS.creator_pump_win_settlement_fee_bps,
+            exit_fee_bps: PARAMS.exit_fee_bps,
+            mother_fee_bps: PARAMS.mother_fee_bps,
+            mother_fee_decay_bps: PARAMS.mother_fee_decay_bps,
+            bling_per_vote_base_cost: PARAMS.bling_per_vote_base_cost,
+            user_initial_social_score: PARAMS.user_initial_social_score,
+            claim_window_secs: PARAMS.claim_window_secs,
+            reveal_window_secs: PARAMS.reveal_window_secs,
+            mother_fee_levels: PARAMS.mother_fee_levels,
+            allowed_curves: PARAMS.allowed_curves,
+            paused: 0,
             bump,
             padding,
         }
     }
+
+    /// Overwrite every field that is set in `params`. Call `validate` afterwards.
+    pub fn apply_update(&mut self, params: &UpdateConfigParams) {
+        if let Some(v) = params.base_duration_secs {
+            self.base_duration_secs = v;
+        }
+        if let Some(v) = params.max_duration_secs {
+            self.max_duration_secs = v;
+        }
+        if let Some(v) = params.extension_per_vote_secs {
+            self.extension_per_vote_secs = v;
+        }
+        if let Some(v) = params.protocol_vote_fee_bps {
+            self.protocol_vote_fee_bps = v;
+        }
+        if let Some(v) = params.protocol_vote_settlement_fee_bps {
+            self.protocol_vote_settlement_fee_bps = v;
+        }
+        if let Some(v) = params.creator_pump_fee_bps {
+            self.creator_pump_fee_bps = v;
+        }
+        if let Some(v) = params.creator_pump_win_settlement_fee_bps {
+            self.creator_pump_win_settlement_fee_bps = v;
+        }
+        if let Some(v) = params.exit_fee_bps {
+            self.exit_fee_bps = v;
+        }
+        if let Some(v) = params.bling_per_vote_base_cost {
+            self.bling_per_vote_base_cost = v;
+        }
+        if let Some(v) = params.user_initial_social_score {
+            self.user_initial_social_score = v;
+        }
+        if let Some(v) = params.claim_window_secs {
+            self.claim_window_secs = v;
+        }
+        if let Some(v) = params.reveal_window_secs {
+            self.reveal_window_secs = v;
+        }
+        if let Some(v) = params.mother_fee_bps {
+            self.mother_fee_bps = v;
+        }
+        if let Some(v) = params.mother_fee_decay_bps {
+            self.mother_fee_decay_bps = v;
+        }
+        if let Some(v) = params.mother_fee_levels {
+            self.mother_fee_levels = v;
+        }
+        if let Some(v) = params.allowed_curves {
+            self.allowed_curves = v;
+        }
+    }
+
+    /// Mother fee bps for each ancestor of a post at `depth`, parent first.
+    /// Zero past the post's depth and past `mother_fee_levels`.
+    pub fn mother_fee_schedule(&self, depth: u8) -> [u16; MAX_MOTHER_FEE_LEVELS] {
+        let levels = (self.mother_fee_levels.min(depth) as usize).min(MAX_MOTHER_FEE_LEVELS);
+        let mut schedule = [0; MAX_MOTHER_FEE_LEVELS];
+        let mut bps = self.mother_fee_bps as u32;
+        for level_bps in schedule.iter_mut().take(levels) {
+            *level_bps = bps as u16;
+            bps = bps * self.mother_fee_decay_bps as u32 / MAX_BPS as u32;
+        }
+        schedule
+    }
+
+    pub fn allows_curve(&self, curve: CurveKind) -> bool {
+        self.allowed_curves & curve.bit() != 0
+    }
+
+    /// Errors if any of the PAUSE_* `flags` is set
+    pub fn require_not_paused(&self, flags: u8) -> Result<()> {
+        require!(self.paused & flags == 0, ErrorCode::Paused);
+        Ok(())
+    }
+
+    /// Bounds checks shared by `initialize` and `update_config`.
+    pub fn validate(&self) -> Result<()> {
+        require!(self.base_duration_secs > 0, ErrorCode::InvalidDuration);
+        require!(
+            self.base_duration_secs <= self.max_duration_secs,
+            ErrorCode::InvalidDuration
+        );
+        require!(
+            self.extension_per_vote_secs <= self.max_duration_secs,
+            ErrorCode::InvalidDuration
+        );
+
+        // Vote-time fees come out of the same BLING cost, so the pot increment must stay positive
+        require!(
+            (self.protocol_vote_fee_bps as u32 + self.creator_pump_fee_bps as u32)
+                <= MAX_VOTE_FEE_BPS as u32,
+            ErrorCode::InvalidFeeBps
+        );
+        require!(
+            self.protocol_vote_settlement_fee_bps <= MAX_BPS,
+            ErrorCode::InvalidFeeBps
+        );
+        require!(
+            self.creator_pump_win_settlement_fee_bps <= MAX_BPS,
+            ErrorCode::InvalidFeeBps
+        );
+        require!(self.exit_fee_bps <= MAX_BPS, ErrorCode::InvalidFeeBps);
+        // With the decay capped at 100%, every level together stays below the whole pot
+        require!(
+            self.mother_fee_bps <= MAX_MOTHER_FEE_BPS,
+            ErrorCode::InvalidFeeBps
+        );
+        require!(
+            self.mother_fee_decay_bps <= MAX_BPS,
+            ErrorCode::InvalidFeeBps
+        );
+        require!(
+            self.mother_fee_levels as usize <= MAX_MOTHER_FEE_LEVELS,
+            ErrorCode::InvalidFeeBps
+        );
+
+        require!(self.bling_per_vote_base_cost > 0, ErrorCode::InvalidFeeBps);
+        require!(self.claim_window_secs > 0, ErrorCode::InvalidDuration);
+        require!(self.reveal_window_secs > 0, ErrorCode::InvalidDuration);
+
+        // At least one known curve, so posts can still be created
+        require!(
+            self.allowed_curves != 0 && self.allowed_curves & !CurveKind::ALL_BITS == 0,
+            ErrorCode::InvalidAllowedCurves
+        );
+
+        Ok(())
+    }
+}
+
+/// Partial update for `Config`. `None` leaves the current value untouched.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct UpdateConfigParams {
+    pub base_duration_secs: Option<u32>,
+    pub max_duration_secs: Option<u32>,
+    pub extension_per_vote_secs: Option<u32>,
+    pub protocol_vote_fee_bps: Option<u16>,
+    pub protocol_vote_settlement_fee_bps: Option<u16>,
+    pub creator_pump_fee_bps: Option<u16>,
+    pub creator_pump_win_settlement_fee_bps: Option<u16>,
+    pub exit_fee_bps: Option<u16>,
+    pub bling_per_vote_base_cost: Option<u64>,
+    pub user_initial_social_score: Option<i64>,
+    pub claim_window_secs: Option<u32>,
+    pub reveal_window_secs: Option<u32>,
+    pub mother_fee_bps: Option<u16>,
+    pub mother_fee_decay_bps: Option<u16>,
+    pub mother_fee_levels: Option<u8>,
+    pub allowed_curves: Option<u8>,
 }
 
 #[account]
@@ -61,8 +257,79 @@ pub struct SessionAuthority {
     pub user: Pubkey,              // wallet being delegated
     pub session_key: Pubkey,       // ephemeral pubkey authorized to act
     pub expires_at: i64,           // timestamp
-    pub privileges_hash: [u8; 32], // optional whitelist hash
+    pub privileges_hash: [u8; 32], // hash of `privileges`
     pub bump: u8,
+    pub privileges: SessionPrivileges,
+    pub bling_spent: u64,   // BLING spent on votes through this session so far
+    pub rent_payer: Pubkey, // who funded the account, gets the rent back on revoke/rotate
+}
+
+impl SessionAuthority {
+    /// Scope checks for an action taken through this session (not signed by the user's wallet).
+    /// `bling_spend` counts against the session's spend cap.
+    pub fn check_privileges(
+        &mut self,
+        privilege: u32,
+        mint: Option<&Pubkey>,
+        bling_spend: u64,
+    ) -> Result<()> {
+        require!(
+            self.privileges.allows_instruction(privilege),
+            SessionError::InstructionNotAllowed
+        );
+        if let Some(mint) = mint {
+            require!(
+                self.privileges.allows_mint(mint),
+                SessionError::MintNotAllowed
+            );
+        }
+
+        let bling_spent = self
+            .bling_spent
+            .checked_add(bling_spend)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            bling_spent <= self.privileges.max_bling_spend,
+            SessionError::SpendCapExceeded
+        );
+        self.bling_spent = bling_spent;
+
+        Ok(())
+    }
+}
+
+/// What a session key may do on the user's behalf.
+/// Stored on the SessionAuthority, with its hash in `privileges_hash`.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug, Default,
+)]
+pub struct SessionPrivileges {
+    /// Bitmap of SESSION_PRIVILEGE_* flags
+    pub allowed_instructions: u32,
+    /// Maximum BLING the session may spend on votes over its lifetime
+    pub max_bling_spend: u64,
+    /// Mints the session may use. Unused slots are Pubkey::default(); all unused = any mint
+    pub allowed_mints: [Pubkey; MAX_SESSION_ALLOWED_MINTS],
+}
+
+impl SessionPrivileges {
+    pub fn allows_instruction(&self, privilege: u32) -> bool {
+        privilege != 0 && self.allowed_instructions & privilege == privilege
+    }
+
+    pub fn allows_mint(&self, mint: &Pubkey) -> bool {
+        let mut listed = self
+            .allowed_mints
+            .iter()
+            .filter(|m| **m != Pubkey::default())
+            .peekable();
+        listed.peek().is_none() || listed.any(|m| m == mint)
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        // Borsh serialization of a fixed-size struct cannot fail
+        hash(&self.try_to_vec().unwrap()).to_bytes()
+    }
 }
 
 #[account]
@@ -73,9 +340,20 @@ pub struct ValidPayment {
     /// 1 USDC = 10_000 BLING for example
     /// 1 SOL = 1_000_000_000 BLING for example
     /// This value is lamport-free. So 1 BLING = 1 BLING
+    /// Only used when no price feed is attached.
     pub price_in_bling: u64,
     pub enabled: bool,
     pub bump: u8,
+    /// Optional price feed account, a Pyth `PriceUpdateV2` (or a PushedPriceFeed in
+    /// `pushed-price-feed` builds). When set, the feed price replaces `price_in_bling`.
+    pub price_feed: Option<Pubkey>,
+    /// Maximum age of the feed price, in seconds
+    pub max_staleness_secs: u32,
+    /// Maximum confidence interval, as bps of the feed price
+    pub max_confidence_bps: u16,
+    /// BLING worth one unit of the feed's quote currency (1 USD for Pyth's USD feeds).
+    /// BLING has no market price, so this peg stays with the admin.
+    pub bling_per_feed_unit: u64,
 }
 impl ValidPayment {
     pub fn new(token_mint: Pubkey, price_in_bling: u64, enabled: bool) -> Self {
@@ -84,8 +362,47 @@ impl ValidPayment {
             price_in_bling,
             enabled,
             bump: 0,
+            price_feed: None,
+            max_staleness_secs: 0,
+            max_confidence_bps: 0,
+            bling_per_feed_unit: 0,
         }
     }
+
+    /// Price used to convert BLING costs into this token.
+    /// With a feed attached, the matching feed account must be passed and pass the staleness and confidence checks.
+    pub fn resolve_price_in_bling(
+        &self,
+        price_feed: Option<&AccountInfo>,
+        now: i64,
+    ) -> Result<u64> {
+        let Some(feed_key) = self.price_feed else {
+            return Ok(self.price_in_bling);
+        };
+
+        let feed = price_feed.ok_or(ErrorCode::MissingPriceFeed)?;
+        require_keys_eq!(feed.key(), feed_key, ErrorCode::MissingPriceFeed);
+
+        let price = FeedPrice::read(feed.owner, &feed.try_borrow_data()?)?;
+        price.check(now, self.max_staleness_secs, self.max_confidence_bps)?;
+        price.price_in_bling(self.bling_per_feed_unit)
+    }
+}
+
+/// Program-owned price for a payment mint, pushed by an authority the admin picked, for
+/// localnet and tests where no Pyth account exists. Same fields as a Pyth price message and
+/// read the same way, but not an oracle: whoever holds `authority` sets the price.
+#[cfg(feature = "pushed-price-feed")]
+#[account]
+#[derive(InitSpace, Copy, PartialEq, Eq, Debug)]
+pub struct PushedPriceFeed {
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub bump: u8,
 }
 
 #[account]
@@ -94,25 +411,74 @@ pub struct UserAccount {
     pub user: Pubkey,      // user wallet pubkey
     pub social_score: i64, // can drive withdraw penalty etc.
     pub bump: u8,
+    pub session_nonce: u64, // bumped by every session register, revoke and rotate
 }
 impl UserAccount {
-    pub fn new(user: Pubkey, bump: u8) -> Self {
+    pub fn new(user: Pubkey, social_score: i64, bump: u8) -> Self {
         Self {
             user,
-            social_score: PARAMS.user_initial_social_score,
+            social_score,
             bump,
+            session_nonce: 0,
         }
     }
 
+    /// Consumes the nonce a session message committed to, so that message cannot be replayed
+    pub fn bump_session_nonce(&mut self) -> Result<()> {
+        self.session_nonce = self
+            .session_nonce
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn apply_social_score_delta(&mut self, delta: i64) {
+        self.social_score = self.social_score.saturating_add(delta);
+    }
+
+    /// Share of every withdrawal kept by the protocol, in BPS.
+    /// Mirrors the breakpoints of `social_score_multiplier`:
+    /// - score >= 10_000 → 0
+    /// - score 0 → WITHDRAW_PENALTY_AT_ZERO_SCORE_BPS
+    /// - score <= -100 → MAX_WITHDRAW_PENALTY_BPS
+    pub fn withdraw_penalty_bps(&self) -> u16 {
+        let score = self.social_score;
+        let at_zero = WITHDRAW_PENALTY_AT_ZERO_SCORE_BPS as i64;
+        let max = MAX_WITHDRAW_PENALTY_BPS as i64;
+
+        let penalty = if score >= 0 {
+            let score_clamped = score.min(10_000);
+            at_zero * (10_000 - score_clamped) / 10_000
+        } else {
+            let neg_score_clamped = -(score.max(-100));
+            at_zero + (max - at_zero) * neg_score_clamped / 100
+        };
+
+        penalty as u16
+    }
+
+    /// Splits a withdrawal into (amount sent to the user, penalty sent to the treasury)
+    pub fn split_withdrawal(&self, amount: u64) -> Result<(u64, u64)> {
+        let penalty = (amount as u128)
+            .checked_mul(self.withdraw_penalty_bps() as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / MAX_BPS as u128;
+        let penalty = penalty as u64;
+        let effective_amount = amount.checked_sub(penalty).ok_or(ErrorCode::MathOverflow)?;
+        Ok((effective_amount, penalty))
+    }
+
     /// Calculate canonical vote cost for this user
     /// This is the cost of voting on a "boring" post (0 votes) with no previous votes,
     /// but using the user's actual social score. This is a pure user attribute.
-    pub fn canonical_cost(&self, side: Side) -> Result<u64> {
+    pub fn canonical_cost(&self, side: Side, config: &Config) -> Result<u64> {
         // Canonical scenario: 1 vote, no previous votes, boring post (0 votes, original type)
         let base_cost = base_user_cost(
             1, // 1 vote
             0, // no previous votes
-            side, self, // user account (for social score)
+            side,
+            CurveKind::Linear, // canonical price is quoted on the default curve
+            self,              // user account (for social score)
         )?;
 
         // Apply post curve adjustments (for canonical: 0 votes, original type)
@@ -122,10 +488,11 @@ impl UserAccount {
             0, // post_downvotes
             side,
             PostType::Original,
+            CurveKind::Linear,
         )?;
 
         // Convert to BLING lamports
-        cost_in_bling(post_cost)
+        cost_in_bling(post_cost, config.bling_per_vote_base_cost)
     }
 }
 
@@ -147,12 +514,51 @@ pub struct PostAccount {
     pub creator_user: Pubkey, // wallet key
     pub post_id_hash: [u8; 32],
     pub post_type: PostType, // <-- NEW
+    /// Price curve the votes on this post are charged along
+    pub curve: CurveKind,
+    pub vote_mode: VoteMode,
     pub start_time: i64,
     pub end_time: i64,
+    /// Commit–reveal posts only: votes can be revealed from end_time until this time
+    pub reveal_end_time: i64,
     pub state: PostState,
     pub upvotes: u64,
     pub downvotes: u64,
     pub winning_side: Option<Side>,
+    /// Set once the creator's social score has been updated for this post
+    pub creator_score_applied: bool,
+    /// Bump of the post pot authority PDA, so claims can sign without re-deriving it
+    pub pot_authority_bump: u8,
+    /// Mints this post has a pot in, in the order they were first voted with.
+    /// Unused slots are Pubkey::default(); the bitmaps below index into this list.
+    pub pot_mints: [Pubkey; MAX_POST_POT_MINTS],
+    /// Bit i set once the pot in `pot_mints[i]` has been settled
+    pub settled_pot_mints: u8,
+    /// Bit i set once the pot in `pot_mints[i]` has been swept and closed
+    pub swept_pot_mints: u8,
+    /// Tokenized posts only: the Pump and Smack share mints, indexed by `Side as usize`.
+    /// Votes mint shares, claims burn them; default keys when the post has no shares.
+    pub share_mints: [Pubkey; 2],
+    /// How the payout is split between the winning side's voters, chosen at create_post
+    pub payout_weighting: PayoutWeighting,
+    /// Cumulative BLING value paid for the counted votes on each side, indexed by `Side as usize`
+    pub paid_bling: [u64; 2],
+    /// Replies created with this post as their parent
+    pub child_count: u32,
+    /// 0 for an original post, one more than its parent for a reply
+    pub depth: u8,
+    /// Parent first, then grandparent and so on; default keys above the root
+    pub ancestors: [Pubkey; MAX_MOTHER_FEE_LEVELS],
+    /// Sum of the positions' `pot_contributions`, indexed like `pot_mints`
+    pub pot_contributions: [u64; MAX_POST_POT_MINTS],
+    /// Pump votes the creator holds on their own post, left out of the creator's score
+    pub creator_upvotes: u64,
+    /// Who funded the pot token account in `pot_mints[i]`, refunded its rent when it's closed
+    pub pot_rent_payers: [Pubkey; MAX_POST_POT_MINTS],
+    /// Escrows of the commitments not revealed yet, indexed like `pot_mints`
+    pub pot_escrows: [u64; MAX_POST_POT_MINTS],
+    /// Cancelled posts: refunds are open until then, the pots can be swept after
+    pub refund_deadline: i64,
 }
 
 impl PostAccount {
@@ -160,20 +566,223 @@ impl PostAccount {
         creator_user: Pubkey,
         post_id_hash: [u8; 32],
         post_type: PostType,
+        curve: CurveKind,
+        vote_mode: VoteMode,
         now: i64,
         config: &Config,
     ) -> Self {
         let end_time = now + config.base_duration_secs as i64;
+        let reveal_end_time = match vote_mode {
+            VoteMode::Open => 0,
+            VoteMode::CommitReveal => end_time + config.reveal_window_secs as i64,
+        };
         Self {
             creator_user,
             post_id_hash,
             post_type,
+            curve,
+            vote_mode,
             start_time: now,
             end_time,
+            reveal_end_time,
             state: PostState::Open,
             upvotes: 0,
             downvotes: 0,
             winning_side: None,
+            creator_score_applied: false,
+            pot_authority_bump: 0,
+            pot_mints: [Pubkey::default(); MAX_POST_POT_MINTS],
+            settled_pot_mints: 0,
+            swept_pot_mints: 0,
+            share_mints: [Pubkey::default(); 2],
+            payout_weighting: PayoutWeighting::PerVote,
+            paid_bling: [0; 2],
+            child_count: 0,
+            depth: 0,
+            ancestors: [Pubkey::default(); MAX_MOTHER_FEE_LEVELS],
+            pot_contributions: [0; MAX_POST_POT_MINTS],
+            creator_upvotes: 0,
+            pot_rent_payers: [Pubkey::default(); MAX_POST_POT_MINTS],
+            pot_escrows: [0; MAX_POST_POT_MINTS],
+            refund_deadline: 0,
+        }
+    }
+
+    /// Ancestors of a reply to this post, which lives at `self_key`
+    pub fn child_ancestors(&self, self_key: Pubkey) -> [Pubkey; MAX_MOTHER_FEE_LEVELS] {
+        let mut ancestors = [Pubkey::default(); MAX_MOTHER_FEE_LEVELS];
+        ancestors[0] = self_key;
+        ancestors[1..].copy_from_slice(&self.ancestors[..MAX_MOTHER_FEE_LEVELS - 1]);
+        ancestors
+    }
+
+    /// Ancestor `level` steps up the reply tree, 1 being the parent
+    pub fn ancestor(&self, level: u8) -> Option<Pubkey> {
+        let ancestor = *self.ancestors.get((level as usize).checked_sub(1)?)?;
+        (ancestor != Pubkey::default()).then_some(ancestor)
+    }
+
+    pub fn parent(&self) -> Option<Pubkey> {
+        match self.post_type {
+            PostType::Child { parent } => Some(parent),
+            PostType::Original => None,
+        }
+    }
+
+    /// Positions are held as share tokens rather than in UserPostPosition
+    pub fn tokenized(&self) -> bool {
+        self.share_mints[Side::Pump as usize] != Pubkey::default()
+    }
+
+    pub fn share_mint(&self, side: Side) -> Pubkey {
+        self.share_mints[side as usize]
+    }
+
+    pub fn paid_bling_on(&self, side: Side) -> u64 {
+        self.paid_bling[side as usize]
+    }
+
+    /// Cancelled posts: what `contribution` to the pot in `mint`, a position's or an unrevealed
+    /// escrow, gets back out of `pot_amount`. The whole contribution while the pot covers every
+    /// position and escrow, a pro rata cut of what is left otherwise, so the order refunds are
+    /// cranked in doesn't matter.
+    pub fn refund_for_contribution(
+        &self,
+        mint: &Pubkey,
+        contribution: u64,
+        pot_amount: u64,
+    ) -> Result<u64> {
+        let index = self.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        let total = self.pot_contributions[index]
+            .saturating_add(self.pot_escrows[index])
+            .max(contribution);
+        if pot_amount >= total {
+            return Ok(contribution);
+        }
+        Ok((contribution as u128 * pot_amount as u128 / total as u128) as u64)
+    }
+
+    /// Cancelled tokenized posts: what burning `shares` of `side` out of `share_supply` gets
+    /// back out of `pot_amount`, pro rata to the BLING value paid for them against what was
+    /// paid on both sides. Returns the refund and that value.
+    pub fn refund_for_shares(
+        &self,
+        side: Side,
+        shares: u64,
+        share_supply: u64,
+        pot_amount: u64,
+    ) -> Result<(u64, u64)> {
+        if shares == 0 || share_supply == 0 {
+            return Ok((0, 0));
+        }
+        let paid_bling = (self.paid_bling_on(side) as u128 * shares.min(share_supply) as u128
+            / share_supply as u128) as u64;
+        let total_paid_bling = self.paid_bling[0]
+            .checked_add(self.paid_bling[1])
+            .ok_or(ErrorCode::MathOverflow)?;
+        if total_paid_bling == 0 {
+            return Ok((0, 0));
+        }
+        let refund = (pot_amount as u128 * paid_bling as u128 / total_paid_bling as u128) as u64;
+        Ok((refund, paid_bling))
+    }
+
+    /// Records an escrow moved into the pot in `mint` by commit_vote
+    pub fn add_pot_escrow(&mut self, mint: &Pubkey, escrow: u64) -> Result<()> {
+        let index = self.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        self.pot_escrows[index] = self.pot_escrows[index].saturating_add(escrow);
+        Ok(())
+    }
+
+    /// Records an escrow in `mint` revealed, forfeited or refunded
+    pub fn remove_pot_escrow(&mut self, mint: &Pubkey, escrow: u64) -> Result<()> {
+        let index = self.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        self.pot_escrows[index] = self.pot_escrows[index].saturating_sub(escrow);
+        Ok(())
+    }
+
+    /// Cancelled posts: the pot in `mint` is gone, whatever wasn't refunded went to the treasury
+    pub fn pot_swept(&self, mint: &Pubkey) -> bool {
+        self.pot_mint_bit(mint)
+            .is_ok_and(|bit| self.swept_pot_mints & bit != 0)
+    }
+
+    pub fn pot_mint_index(&self, mint: &Pubkey) -> Option<usize> {
+        if *mint == Pubkey::default() {
+            return None;
+        }
+        self.pot_mints.iter().position(|m| m == mint)
+    }
+
+    /// Who funded the pot token account in `mint`
+    pub fn pot_rent_payer(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.pot_mint_index(mint)
+            .map(|index| self.pot_rent_payers[index])
+    }
+
+    /// Bit of `mint` in the pot bitmaps, registering it in the first free slot if it's new.
+    /// `rent_payer` is recorded as the funder of a new pot, created in the same instruction.
+    pub fn register_pot_mint(&mut self, mint: Pubkey, rent_payer: Pubkey) -> Result<u8> {
+        let index = match self.pot_mint_index(&mint) {
+            Some(index) => index,
+            None => {
+                // A share is a claim on one pot, so tokenized posts only take one mint
+                require!(
+                    !self.tokenized() || self.pot_mints_mask() == 0,
+                    ErrorCode::TokenizedPostSingleMint
+                );
+                let free = self
+                    .pot_mints
+                    .iter()
+                    .position(|m| *m == Pubkey::default())
+                    .ok_or(ErrorCode::TooManyPotMints)?;
+                self.pot_mints[free] = mint;
+                self.pot_rent_payers[free] = rent_payer;
+                free
+            }
+        };
+        Ok(1 << index)
+    }
+
+    /// Bit of `mint` in the pot bitmaps; errors if the post never had a pot in it
+    pub fn pot_mint_bit(&self, mint: &Pubkey) -> Result<u8> {
+        let index = self.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        Ok(1 << index)
+    }
+
+    /// One bit per registered pot mint
+    pub fn pot_mints_mask(&self) -> u8 {
+        self.pot_mints
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| **m != Pubkey::default())
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+
+    /// Settled, with a frozen payout for every mint it has a pot in
+    pub fn fully_settled(&self) -> bool {
+        self.state == PostState::Settled && self.settled_pot_mints == self.pot_mints_mask()
+    }
+
+    /// Social score the creator earns from this post: only when Pump won on other voters'
+    /// pump votes, so the creator can't pump their own post to a win for the score
+    pub fn creator_social_score_delta(&self) -> i64 {
+        let others_upvotes = self.upvotes.saturating_sub(self.creator_upvotes);
+        match self.winning_side {
+            Some(Side::Pump) if others_upvotes > 0 && others_upvotes >= self.downvotes => {
+                CREATOR_PUMP_WIN_SOCIAL_SCORE
+            }
+            _ => 0,
+        }
+    }
+
+    /// Keeps `creator_upvotes` in step with the Pump votes `voter` holds on the post
+    pub fn track_creator_upvotes(&mut self, voter: &Pubkey, added: u64, removed: u64) {
+        if *voter == self.creator_user {
+            self.creator_upvotes = self
+                .creator_upvotes
+                .saturating_add(added)
+                .saturating_sub(removed);
         }
     }
 
@@ -195,9 +804,33 @@ impl PostAccount {
         Ok(new_end)
     }
 
+    /// Votes that can be applied on `side` without overflowing the post's counter
+    pub fn capped_votes(&self, side: Side, requested: u64) -> u64 {
+        let current = match side {
+            Side::Pump => self.upvotes,
+            Side::Smack => self.downvotes,
+        };
+        requested.min(u64::MAX - current)
+    }
+
     pub fn within_time_limit(&self, current_time: i64) -> bool {
         (current_time < self.end_time)
     }
+
+    /// Votes of a commit–reveal post can be revealed in [end_time, reveal_end_time)
+    pub fn within_reveal_window(&self, current_time: i64) -> bool {
+        self.vote_mode == VoteMode::CommitReveal
+            && current_time >= self.end_time
+            && current_time < self.reveal_end_time
+    }
+
+    /// No more votes can be counted: the post can be settled
+    pub fn votes_final(&self, current_time: i64) -> bool {
+        match self.vote_mode {
+            VoteMode::Open => !self.within_time_limit(current_time),
+            VoteMode::CommitReveal => current_time >= self.reveal_end_time,
+        }
+    }
 }
 
 #[account]
@@ -210,33 +843,94 @@ pub struct PostMintPayout {
     pub payout_per_winning_vote: u64,
     pub creator_fee: u64,
     pub protocol_fee: u64,
+    /// Sum of `mother_fees`
     pub mother_fee: u64,
     pub frozen: bool, // Prevents re-settlement
     pub bump: u8,
+    /// Who `total_payout` went to; see PayoutPolicy
+    pub policy: PayoutPolicy,
+    /// Claims close at this time; afterwards the pot can be swept and closed
+    pub claim_deadline: i64,
+    /// How claims split `total_payout`; under Stake, `payout_per_winning_vote` is only the average
+    pub weighting: PayoutWeighting,
+    /// Stake weighting only: BLING paid by the whole winning side, the denominator of every claim
+    pub paid_stake: u64,
+    /// Share of each ancestor's pot, parent first, in the order of `PostAccount.ancestors`
+    pub mother_fees: [u64; MAX_MOTHER_FEE_LEVELS],
+    /// Bit i set once `mother_fees[i]` has been sent to its ancestor
+    pub distributed_mother_fees: u8,
+    /// Set once `creator_fee` has been sent to the creator's vault
+    pub creator_fee_distributed: bool,
+    /// Set once `protocol_fee` has been sent to the protocol treasury
+    pub protocol_fee_distributed: bool,
+    /// Funded the payout at settle_post, gets its rent back when the pot is swept
+    pub rent_payer: Pubkey,
 }
 impl PostMintPayout {
-    pub fn new(
-        post: Pubkey,
-        token_mint: Pubkey,
-        initial_pot: u64,
-        total_payout: u64,
-        payout_per_winning_vote: u64,
-        creator_fee: u64,
-        protocol_fee: u64,
-        mother_fee: u64,
-        bump: u8,
-    ) -> Self {
-        Self {
-            post,
-            token_mint,
-            initial_pot,
-            total_payout,
-            payout_per_winning_vote,
-            creator_fee,
-            protocol_fee,
-            mother_fee,
-            frozen: true, // Always frozen when created
-            bump,
+    /// Marks the creator fee as distributed and returns it.
+    /// 0 when it was already distributed, so a repeated crank moves nothing.
+    pub fn take_creator_fee(&mut self) -> u64 {
+        if std::mem::replace(&mut self.creator_fee_distributed, true) {
+            return 0;
+        }
+        self.creator_fee
+    }
+
+    /// Same as take_creator_fee for the protocol fee
+    pub fn take_protocol_fee(&mut self) -> u64 {
+        if std::mem::replace(&mut self.protocol_fee_distributed, true) {
+            return 0;
+        }
+        self.protocol_fee
+    }
+
+    /// Every fee charged at settlement has left the pot, so only unclaimed rewards and
+    /// rounding dust are left in it
+    pub fn fees_distributed(&self) -> bool {
+        (self.creator_fee == 0 || self.creator_fee_distributed)
+            && (self.protocol_fee == 0 || self.protocol_fee_distributed)
+            && self
+                .mother_fees
+                .iter()
+                .enumerate()
+                .all(|(i, fee)| *fee == 0 || self.distributed_mother_fees & (1 << i) != 0)
+    }
+
+    /// Marks the mother fee of ancestor `level` (1 = parent) as distributed and returns it.
+    /// 0 when it was already distributed, so a repeated crank moves nothing.
+    pub fn take_mother_fee(&mut self, level: u8) -> Result<u64> {
+        let index = (level as usize)
+            .checked_sub(1)
+            .filter(|i| *i < MAX_MOTHER_FEE_LEVELS)
+            .ok_or(ErrorCode::InvalidAncestorPost)?;
+        if self.distributed_mother_fees & (1 << index) != 0 {
+            return Ok(0);
+        }
+        self.distributed_mother_fees |= 1 << index;
+        Ok(self.mother_fees[index])
+    }
+
+    /// Reward for `winning_votes`, unscaled from PRECISION. None on overflow.
+    pub fn reward_for_votes(&self, winning_votes: u64) -> Option<u64> {
+        winning_votes
+            .checked_mul(self.payout_per_winning_vote)?
+            .checked_div(PRECISION)
+    }
+
+    /// Reward for a position holding `votes` and having paid `paid_bling` on the winning side.
+    /// None on overflow.
+    pub fn reward_for(&self, votes: u64, paid_bling: u64) -> Option<u64> {
+        match self.weighting {
+            PayoutWeighting::PerVote => self.reward_for_votes(votes),
+            PayoutWeighting::Stake => {
+                if self.paid_stake == 0 {
+                    return Some(0);
+                }
+                let reward = (self.total_payout as u128)
+                    .checked_mul(paid_bling as u128)?
+                    .checked_div(self.paid_stake as u128)?;
+                u64::try_from(reward).ok()
+            }
         }
     }
 }
@@ -248,6 +942,16 @@ pub struct UserPostPosition {
     pub post: Pubkey,
     pub upvotes: u64,
     pub downvotes: u64,
+    /// Set once this position has moved the user's social score
+    pub score_applied: bool,
+    /// Bit i set once the reward in the post's `pot_mints[i]` has been claimed
+    pub claimed_pot_mints: u8,
+    pub rent_payer: Pubkey, // who funded the account, gets the rent back on close
+    /// Cumulative BLING value paid for the votes held on each side, indexed by `Side as usize`
+    pub paid_bling: [u64; 2],
+    /// What this position put in each of the post's pots, indexed like `PostAccount::pot_mints`.
+    /// Net of fees and exits; refunded if the post is cancelled.
+    pub pot_contributions: [u64; MAX_POST_POT_MINTS],
 }
 
 impl UserPostPosition {
@@ -257,8 +961,156 @@ impl UserPostPosition {
             post,
             upvotes: 0,
             downvotes: 0,
+            score_applied: false,
+            claimed_pot_mints: 0,
+            rent_payer: Pubkey::default(),
+            paid_bling: [0; 2],
+            pot_contributions: [0; MAX_POST_POT_MINTS],
         }
     }
+
+    /// Records `amount` of `mint` put in the post's pot, on the position and the post's total
+    pub fn add_pot_contribution(
+        &mut self,
+        post: &mut PostAccount,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let index = post.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        self.pot_contributions[index] = self.pot_contributions[index].saturating_add(amount);
+        post.pot_contributions[index] = post.pot_contributions[index].saturating_add(amount);
+        Ok(())
+    }
+
+    /// Records `amount` of `mint` taken back out of the post's pot
+    pub fn reduce_pot_contribution(
+        &mut self,
+        post: &mut PostAccount,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let index = post.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        let amount = amount.min(self.pot_contributions[index]);
+        self.pot_contributions[index] -= amount;
+        post.pot_contributions[index] = post.pot_contributions[index].saturating_sub(amount);
+        Ok(())
+    }
+
+    /// What this position has in the pot in `mint`
+    pub fn pot_contribution(&self, post: &PostAccount, mint: &Pubkey) -> Result<u64> {
+        let index = post.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        Ok(self.pot_contributions[index])
+    }
+
+    /// Clears and returns the contribution to the pot in `mint`, for a refund
+    pub fn take_pot_contribution(&mut self, post: &mut PostAccount, mint: &Pubkey) -> Result<u64> {
+        let index = post.pot_mint_index(mint).ok_or(ErrorCode::UnknownPotMint)?;
+        let contribution = std::mem::take(&mut self.pot_contributions[index]);
+        post.pot_contributions[index] = post.pot_contributions[index].saturating_sub(contribution);
+        Ok(contribution)
+    }
+
+    /// Nothing left to claim: every pot of the fully settled post is claimed or swept,
+    /// or every contribution to a cancelled post refunded or swept
+    pub fn fully_claimed(&self, post: &PostAccount) -> bool {
+        // Shares, not positions, are refunded on tokenized posts
+        if post.state == PostState::Cancelled {
+            return post.tokenized()
+                || self
+                    .pot_contributions
+                    .iter()
+                    .enumerate()
+                    .all(|(i, c)| *c == 0 || post.swept_pot_mints & (1 << i) != 0);
+        }
+        // Shares, not positions, are claimed on tokenized posts
+        post.fully_settled()
+            && (post.tokenized()
+                || post.pot_mints_mask() & !(self.claimed_pot_mints | post.swept_pot_mints) == 0)
+    }
+
+    /// Votes this position holds on `side`
+    pub fn votes_on(&self, side: Side) -> u64 {
+        match side {
+            Side::Pump => self.upvotes,
+            Side::Smack => self.downvotes,
+        }
+    }
+
+    pub fn paid_bling_on(&self, side: Side) -> u64 {
+        self.paid_bling[side as usize]
+    }
+
+    /// Pro rata part of the BLING paid on `side` that `votes` of the held votes account for
+    pub fn paid_bling_for(&self, side: Side, votes: u64) -> u64 {
+        let held = self.votes_on(side);
+        if held == 0 {
+            return 0;
+        }
+        ((self.paid_bling_on(side) as u128) * (votes.min(held) as u128) / held as u128) as u64
+    }
+
+    /// Social score change for this position once the post is settled.
+    /// Winners gain, losing smackers lose (losing pumpers don't), each side bounded per post.
+    pub fn social_score_delta(&self, winning_side: Side) -> i64 {
+        let per_post = |votes: u64, per_vote: i64| {
+            (votes.min(MAX_VOTE_COUNT_CAP) as i64)
+                .saturating_mul(per_vote)
+                .min(MAX_SOCIAL_SCORE_DELTA_PER_POST)
+        };
+
+        match winning_side {
+            Side::Pump => {
+                per_post(self.upvotes, SOCIAL_SCORE_PER_WINNING_VOTE)
+                    - per_post(self.downvotes, SOCIAL_SCORE_PER_LOSING_SMACK_VOTE)
+            }
+            Side::Smack => per_post(self.downvotes, SOCIAL_SCORE_PER_WINNING_VOTE),
+        }
+    }
+
+    /// Per-post part of a reward claim in `payout.token_mint`, shared by claim_post_reward
+    /// and claim_post_rewards: moves the user's social score on the first claim of the post,
+    /// marks the pot claimed and works out the reward. `shares` stands in for the position's
+    /// votes on tokenized posts.
+    pub fn claim_reward(
+        &mut self,
+        post: &PostAccount,
+        payout: &PostMintPayout,
+        user_account: &mut UserAccount,
+        shares: Option<u64>,
+    ) -> Result<RewardClaim> {
+        let winning_side = post.winning_side.ok_or(ErrorCode::NoWinner)?;
+        let pot_mint_bit = post.pot_mint_bit(&payout.token_mint)?;
+
+        // Social score moves once per post, whichever mint is claimed first
+        let mut social_score_delta = 0;
+        if !self.score_applied {
+            social_score_delta = self.social_score_delta(winning_side);
+            user_account.apply_social_score_delta(social_score_delta);
+            self.score_applied = true;
+        }
+
+        let winning_votes = shares.unwrap_or(self.votes_on(winning_side));
+        let reward = payout
+            .reward_for(winning_votes, self.paid_bling_on(winning_side))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.claimed_pot_mints |= pot_mint_bit;
+
+        Ok(RewardClaim {
+            reward,
+            winning_votes,
+            social_score_delta,
+        })
+    }
+}
+
+/// What a position gets from claiming one settled pot, see `UserPostPosition::claim_reward`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RewardClaim {
+    /// Paid out of the pot to the user's vault, in the payout mint
+    pub reward: u64,
+    pub winning_votes: u64,
+    pub social_score_delta: i64,
 }
 
 // For reward claims - token mint specific
@@ -270,6 +1122,7 @@ pub struct UserPostMintClaim {
     pub mint: Pubkey,
     pub claimed: bool,
     pub bump: u8,
+    pub rent_payer: Pubkey, // who funded the account, gets the rent back on close
 }
 
 impl UserPostMintClaim {
@@ -280,24 +1133,151 @@ impl UserPostMintClaim {
             mint,
             claimed: false,
             bump,
+            rent_payer: Pubkey::default(),
         }
     }
 }
 
+/// A hidden vote on a commit–reveal post. The escrow already sits in the post pot:
+/// the reveal charges the vote from it and refunds the rest, an unrevealed escrow stays there.
+#[account]
+#[derive(InitSpace, Copy, PartialEq, Eq, Debug)]
+pub struct VoteCommitment {
+    pub user: Pubkey,
+    pub post: Pubkey,
+    pub token_mint: Pubkey,
+    /// VoteCommitment::hash of the hidden side and votes
+    pub commitment: [u8; 32],
+    /// Amount of token_mint escrowed in the post pot
+    pub escrow: u64,
+    pub bump: u8,
+    pub rent_payer: Pubkey, // who funded the account, gets the rent back on close
+}
+
+impl VoteCommitment {
+    pub fn new(
+        user: Pubkey,
+        post: Pubkey,
+        token_mint: Pubkey,
+        commitment: [u8; 32],
+        escrow: u64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            user,
+            post,
+            token_mint,
+            commitment,
+            escrow,
+            bump,
+            rent_payer: Pubkey::default(),
+        }
+    }
+
+    /// sha256(side || votes (LE) || salt || user); the user is included so a
+    /// commitment can't be copied by someone else
+    pub fn hash(user: &Pubkey, side: Side, votes: u64, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&[side as u8], &votes.to_le_bytes(), salt, user.as_ref()]).to_bytes()
+    }
+
+    pub fn matches(&self, side: Side, votes: u64, salt: &[u8; 32]) -> bool {
+        Self::hash(&self.user, side, votes, salt) == self.commitment
+    }
+}
+
 // -----------------------------------------------------------------------------
 // ENUMS
 // -----------------------------------------------------------------------------
 
+/// Price dynamics of a post, chosen at create_post among `Config.allowed_curves`.
+/// The math lives in `math::curves`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum CurveKind {
+    /// Price grows linearly with votes (the original curve)
+    Linear,
+    /// Price grows with the square of votes
+    Quadratic,
+    /// Price doubles every fixed number of votes, up to the curve caps
+    ExponentialCapped,
+    /// Every vote costs the same
+    Flat,
+}
+
+impl CurveKind {
+    pub const ALL_BITS: u8 = 0b1111;
+
+    pub const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// How votes on a post are cast, chosen at create_post
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum VoteMode {
+    /// vote_on_post: votes are public and counted right away
+    Open,
+    /// commit_vote before end_time, reveal_vote during the reveal window.
+    /// Only revealed votes count; unrevealed escrows are forfeited to the pot.
+    CommitReveal,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
 pub enum Side {
     Pump,
     Smack,
 }
 
+impl Side {
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Pump => Side::Smack,
+            Side::Smack => Side::Pump,
+        }
+    }
+}
+
+/// Where a post's voter payout goes at settlement.
+/// Only `Winners` is normal; the rest keep the pot from being locked when nobody
+/// voted on the winning side. The winner has the most votes, so that only happens when
+/// nobody voted at all and the tie rule picked Pump.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum PayoutPolicy {
+    /// Split across winning votes
+    Winners,
+    /// No votes at all on a child post with an open parent: added to the mother fee
+    ParentRollover,
+    /// No votes at all otherwise: added to the protocol fee for the treasury
+    TreasurySweep,
+}
+
+impl PayoutPolicy {
+    pub fn for_settlement(winning_votes: u64, parent_open: bool) -> Self {
+        if winning_votes > 0 {
+            PayoutPolicy::Winners
+        } else if parent_open {
+            PayoutPolicy::ParentRollover
+        } else {
+            PayoutPolicy::TreasurySweep
+        }
+    }
+}
+
+/// How the voter payout is split across the winning side, chosen at create_post
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum PayoutWeighting {
+    /// The same amount for every vote, however much it cost along the curve
+    PerVote,
+    /// In proportion to the BLING each voter paid, so early cheap votes earn less than late ones
+    Stake,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
 pub enum PostState {
     Open,
     Settled,
+    /// Taken down by the admin before settlement: no payouts, voters get their pot
+    /// contributions back through refund_position
+    Cancelled,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
@@ -326,6 +1306,7 @@ impl Vote {
         post: &PostAccount,
         user_position: &UserPostPosition,
         user_account: &UserAccount,
+        config: &Config,
     ) -> Result<u64> {
         // Calculate base user-adjusted cost
         let prev = match self.side {
@@ -333,7 +1314,8 @@ impl Vote {
             Side::Smack => user_position.downvotes as u64,
         };
 
-        let base_cost = base_user_cost(self.votes as u64, prev, self.side, user_account)?;
+        let base_cost =
+            base_user_cost(self.votes as u64, prev, self.side, post.curve, user_account)?;
 
         // Apply post curve adjustments
         let post_cost = post_curve_cost(
@@ -342,9 +1324,1098 @@ impl Vote {
             post.downvotes as u64,
             self.side,
             post.post_type,
+            post.curve,
         )?;
 
         // Convert to BLING lamports
-        cost_in_bling(post_cost)
+        cost_in_bling(post_cost, config.bling_per_vote_base_cost)
+    }
+
+    /// Full breakdown of what this vote charges: BLING cost, fee split, and the same amounts
+    /// in the payment mint. `price_in_bling` is None when paying in BLING.
+    pub fn quote(
+        &self,
+        post: &PostAccount,
+        user_position: &UserPostPosition,
+        user_account: &UserAccount,
+        config: &Config,
+        price_in_bling: Option<u64>,
+        token_decimals: u8,
+    ) -> Result<VoteQuote> {
+        let cost_bling = self.compute_cost_in_bling(post, user_position, user_account, config)?;
+
+        let protocol_fee = cost_bling
+            .checked_mul(config.protocol_vote_fee_bps as u64)
+            .ok_or(ErrorCode::MathOverflow)?
+            / 10_000;
+        let creator_pump_fee = match self.side {
+            Side::Pump => {
+                cost_bling
+                    .checked_mul(config.creator_pump_fee_bps as u64)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    / 10_000
+            }
+            Side::Smack => 0,
+        };
+
+        let pot_increment = cost_bling
+            .checked_sub(protocol_fee + creator_pump_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let (protocol_fee_token, creator_pump_fee_token, pot_increment_token) = match price_in_bling
+        {
+            // Already in BLING, no conversion needed
+            None => (protocol_fee, creator_pump_fee, pot_increment),
+            Some(price_in_bling) => convert_bling_fees_to_token(
+                protocol_fee,
+                creator_pump_fee,
+                pot_increment,
+                price_in_bling,
+                token_decimals,
+            )?,
+        };
+
+        let cost_in_token = protocol_fee_token
+            .checked_add(creator_pump_fee_token)
+            .and_then(|c| c.checked_add(pot_increment_token))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(VoteQuote {
+            votes: self.votes,
+            cost_bling,
+            protocol_fee,
+            creator_pump_fee,
+            pot_increment,
+            protocol_fee_token,
+            creator_pump_fee_token,
+            pot_increment_token,
+            cost_in_token,
+        })
+    }
+
+    /// Inverse of `quote`: selling the last `votes` of the position back along the curve.
+    /// The votes are valued at what they would cost from the post and position without them,
+    /// and only the pot share comes back: the vote fees already left, and `exit_fee_bps` of
+    /// it stays in the pot for the remaining voters.
+    ///
+    /// Valued in BLING; `ExitQuote::in_token` converts it into the payout mint.
+    pub fn exit_quote(
+        &self,
+        post: &PostAccount,
+        user_position: &UserPostPosition,
+        user_account: &UserAccount,
+        config: &Config,
+    ) -> Result<ExitQuote> {
+        require!(
+            user_position.votes_on(self.side) >= self.votes,
+            ErrorCode::InsufficientPositionVotes
+        );
+
+        let mut post_before = *post;
+        let mut position_before = *user_position;
+        match self.side {
+            Side::Pump => {
+                post_before.upvotes = post.upvotes.saturating_sub(self.votes);
+                position_before.upvotes -= self.votes;
+            }
+            Side::Smack => {
+                post_before.downvotes = post.downvotes.saturating_sub(self.votes);
+                position_before.downvotes -= self.votes;
+            }
+        }
+
+        // Only the BLING pot share is used, the payment mint doesn't matter here
+        let pot_value = self
+            .quote(
+                &post_before,
+                &position_before,
+                user_account,
+                config,
+                None,
+                0,
+            )?
+            .pot_increment;
+
+        let exit_fee = pot_value
+            .checked_mul(config.exit_fee_bps as u64)
+            .ok_or(ErrorCode::MathOverflow)?
+            / 10_000;
+        let refund = pot_value - exit_fee;
+
+        Ok(ExitQuote {
+            votes: self.votes,
+            pot_value,
+            exit_fee,
+            refund,
+            exit_fee_token: exit_fee,
+            refund_token: refund,
+        })
+    }
+}
+
+/// What selling votes back returns, as paid by exit_position
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExitQuote {
+    pub votes: u64,
+    /// Pot share the votes would cost if bought back right now, in BLING
+    pub pot_value: u64,
+    pub exit_fee: u64,
+    pub refund: u64,
+    pub exit_fee_token: u64,
+    /// Paid out of the pot to the user's vault, in the payout mint
+    pub refund_token: u64,
+}
+
+impl ExitQuote {
+    /// The quote paid in a mint priced at `price_in_bling` (None for BLING), and never more
+    /// than `contribution`, what the position put in that mint's pot, so an exit can't draw
+    /// on what others paid in another mint.
+    pub fn in_token(
+        self,
+        contribution: u64,
+        price_in_bling: Option<u64>,
+        token_decimals: u8,
+        exit_fee_bps: u16,
+    ) -> Result<Self> {
+        require!(contribution > 0, ErrorCode::NothingToRefund);
+
+        let pot_value_token = match price_in_bling {
+            // Already in BLING, no conversion needed
+            None => self.pot_value,
+            Some(price_in_bling) => {
+                convert_bling_to_token_lamports(self.pot_value, price_in_bling, token_decimals)?
+            }
+        }
+        .min(contribution);
+        let exit_fee_token = pot_value_token
+            .checked_mul(exit_fee_bps as u64)
+            .ok_or(ErrorCode::MathOverflow)?
+            / 10_000;
+
+        Ok(Self {
+            exit_fee_token,
+            refund_token: pot_value_token - exit_fee_token,
+            ..self
+        })
+    }
+}
+
+/// What a vote costs, as charged by vote_on_post and returned by quote_vote
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VoteQuote {
+    /// Votes actually applied, capped so the post counters can't overflow
+    pub votes: u64,
+    pub cost_bling: u64,
+    pub protocol_fee: u64,
+    pub creator_pump_fee: u64,
+    pub pot_increment: u64,
+    pub protocol_fee_token: u64,
+    pub creator_pump_fee_token: u64,
+    pub pot_increment_token: u64,
+    /// Total charged in the payment mint: the three token amounts above
+    pub cost_in_token: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> Config {
+        Config::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            60,
+            3_600,
+            10,
+            255,
+            [0; 7],
+        )
+    }
+
+    fn test_payout() -> PostMintPayout {
+        PostMintPayout {
+            post: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            initial_pot: 0,
+            total_payout: 0,
+            payout_per_winning_vote: 0,
+            creator_fee: 0,
+            protocol_fee: 0,
+            mother_fee: 0,
+            frozen: true,
+            bump: 0,
+            policy: PayoutPolicy::Winners,
+            claim_deadline: 0,
+            weighting: PayoutWeighting::PerVote,
+            paid_stake: 0,
+            mother_fees: [0; MAX_MOTHER_FEE_LEVELS],
+            distributed_mother_fees: 0,
+            creator_fee_distributed: false,
+            protocol_fee_distributed: false,
+            rent_payer: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_withdraw_penalty_bps() {
+        let user = |social_score| UserAccount::new(Pubkey::new_unique(), social_score, 255);
+
+        assert_eq!(user(20_000).withdraw_penalty_bps(), 0);
+        assert_eq!(user(10_000).withdraw_penalty_bps(), 0);
+        assert_eq!(user(5_000).withdraw_penalty_bps(), 1_000);
+        assert_eq!(user(0).withdraw_penalty_bps(), 2_000);
+        assert_eq!(user(-50).withdraw_penalty_bps(), 3_500);
+        assert_eq!(user(-100).withdraw_penalty_bps(), 5_000);
+        assert_eq!(user(i64::MIN).withdraw_penalty_bps(), 5_000);
+
+        let (effective_amount, penalty) = user(0).split_withdrawal(1_000).unwrap();
+        assert_eq!((effective_amount, penalty), (800, 200));
+        let (effective_amount, penalty) = user(-100).split_withdrawal(u64::MAX).unwrap();
+        assert_eq!(effective_amount + penalty, u64::MAX);
+    }
+
+    #[test]
+    fn test_position_social_score_delta() {
+        let position = |upvotes, downvotes| UserPostPosition {
+            upvotes,
+            downvotes,
+            ..UserPostPosition::new(Pubkey::new_unique(), Pubkey::new_unique())
+        };
+
+        // winners gain, bounded per post
+        assert_eq!(position(10, 0).social_score_delta(Side::Pump), 10);
+        assert_eq!(position(0, 10).social_score_delta(Side::Smack), 10);
+        assert_eq!(
+            position(u64::MAX, 0).social_score_delta(Side::Pump),
+            MAX_SOCIAL_SCORE_DELTA_PER_POST
+        );
+
+        // losing smackers lose, losing pumpers don't
+        assert_eq!(position(0, 10).social_score_delta(Side::Pump), -10);
+        assert_eq!(
+            position(0, u64::MAX).social_score_delta(Side::Pump),
+            -MAX_SOCIAL_SCORE_DELTA_PER_POST
+        );
+        assert_eq!(position(10, 0).social_score_delta(Side::Smack), 0);
+
+        // hedging on both sides nets out
+        assert_eq!(position(10, 10).social_score_delta(Side::Pump), 0);
+    }
+
+    #[test]
+    fn test_creator_social_score_delta() {
+        let creator = Pubkey::new_unique();
+        let mut post = PostAccount::new(
+            creator,
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &test_config(),
+        );
+        post.winning_side = Some(Side::Pump);
+        assert_eq!(post.creator_social_score_delta(), 0);
+
+        // pumping their own post to a win earns the creator nothing
+        post.upvotes = 13;
+        post.downvotes = 5;
+        post.track_creator_upvotes(&creator, 10, 0);
+        post.track_creator_upvotes(&Pubkey::new_unique(), 3, 0);
+        assert_eq!(post.creator_upvotes, 10);
+        assert_eq!(post.creator_social_score_delta(), 0);
+
+        // other voters' pumps have to carry the win; selling their own votes nets out
+        post.upvotes = 13 + 4 - 4;
+        post.track_creator_upvotes(&Pubkey::new_unique(), 4, 0);
+        post.track_creator_upvotes(&creator, 0, 4);
+        assert_eq!(post.creator_upvotes, 6);
+        assert_eq!(
+            post.creator_social_score_delta(),
+            CREATOR_PUMP_WIN_SOCIAL_SCORE
+        );
+
+        post.winning_side = Some(Side::Smack);
+        assert_eq!(post.creator_social_score_delta(), 0);
+    }
+
+    #[test]
+    fn test_reward_for_votes() {
+        let position = UserPostPosition {
+            upvotes: 3,
+            downvotes: 5,
+            ..UserPostPosition::new(Pubkey::new_unique(), Pubkey::new_unique())
+        };
+        assert_eq!(position.votes_on(Side::Pump), 3);
+        assert_eq!(position.votes_on(Side::Smack), 5);
+
+        let payout = PostMintPayout {
+            payout_per_winning_vote: 2 * PRECISION + PRECISION / 2,
+            ..test_payout()
+        };
+        assert_eq!(payout.reward_for_votes(0), Some(0));
+        assert_eq!(
+            payout.reward_for_votes(position.votes_on(Side::Smack)),
+            Some(12)
+        );
+        assert_eq!(payout.reward_for_votes(u64::MAX), None);
+        // per vote, whatever was paid
+        assert_eq!(payout.reward_for(5, 1_000), Some(12));
+    }
+
+    #[test]
+    fn test_reward_for_stake() {
+        // 2 votes bought early for 100, 1 vote bought late for 300, all on Pump
+        let early = UserPostPosition {
+            upvotes: 2,
+            paid_bling: [100, 0],
+            ..UserPostPosition::new(Pubkey::new_unique(), Pubkey::new_unique())
+        };
+        let late = UserPostPosition {
+            upvotes: 1,
+            paid_bling: [300, 0],
+            ..UserPostPosition::new(Pubkey::new_unique(), Pubkey::new_unique())
+        };
+
+        let payout = PostMintPayout {
+            total_payout: 1_000,
+            payout_per_winning_vote: 1_000 * PRECISION / 3,
+            weighting: PayoutWeighting::Stake,
+            paid_stake: 400,
+            ..test_payout()
+        };
+        let reward = |pos: &UserPostPosition| {
+            payout
+                .reward_for(pos.votes_on(Side::Pump), pos.paid_bling_on(Side::Pump))
+                .unwrap()
+        };
+        assert_eq!(reward(&early), 250);
+        assert_eq!(reward(&late), 750);
+        assert!(reward(&early) + reward(&late) <= payout.total_payout);
+
+        // selling one of the early votes takes half of what they cost with it
+        assert_eq!(early.paid_bling_for(Side::Pump, 1), 50);
+        assert_eq!(early.paid_bling_for(Side::Pump, 5), 100);
+        assert_eq!(early.paid_bling_for(Side::Smack, 1), 0);
+
+        let nobody_paid = PostMintPayout {
+            paid_stake: 0,
+            ..payout
+        };
+        assert_eq!(nobody_paid.reward_for(1, 0), Some(0));
+    }
+
+    #[test]
+    fn test_claim_reward() {
+        let bling = Pubkey::new_unique();
+        let mut post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &test_config(),
+        );
+        post.register_pot_mint(bling, Pubkey::new_unique()).unwrap();
+        let payout = PostMintPayout {
+            token_mint: bling,
+            total_payout: 1_000,
+            payout_per_winning_vote: 10 * PRECISION,
+            ..test_payout()
+        };
+        let mut user = UserAccount::new(Pubkey::new_unique(), 0, 255);
+        let mut position = UserPostPosition {
+            upvotes: 3,
+            ..UserPostPosition::new(user.user, Pubkey::new_unique())
+        };
+
+        // not settled yet
+        assert!(position
+            .claim_reward(&post, &payout, &mut user, None)
+            .is_err());
+
+        post.state = PostState::Settled;
+        post.winning_side = Some(Side::Pump);
+        let claim = position
+            .claim_reward(&post, &payout, &mut user, None)
+            .unwrap();
+        assert_eq!(
+            claim,
+            RewardClaim {
+                reward: 30,
+                winning_votes: 3,
+                social_score_delta: 3,
+            }
+        );
+        assert_eq!(user.social_score, 3);
+        assert!(position.score_applied);
+        assert_eq!(position.claimed_pot_mints, 0b01);
+
+        // the score only moves once per post; shares stand in for votes
+        let claim = position
+            .claim_reward(&post, &payout, &mut user, Some(5))
+            .unwrap();
+        assert_eq!(claim.reward, 50);
+        assert_eq!(claim.social_score_delta, 0);
+        assert_eq!(user.social_score, 3);
+
+        // a mint the post has no pot in
+        let other = PostMintPayout {
+            token_mint: Pubkey::new_unique(),
+            ..payout
+        };
+        assert!(position
+            .claim_reward(&post, &other, &mut user, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_payout_policy() {
+        assert_eq!(
+            PayoutPolicy::for_settlement(1, false),
+            PayoutPolicy::Winners
+        );
+        assert_eq!(PayoutPolicy::for_settlement(1, true), PayoutPolicy::Winners);
+        assert_eq!(
+            PayoutPolicy::for_settlement(0, true),
+            PayoutPolicy::ParentRollover
+        );
+        assert_eq!(
+            PayoutPolicy::for_settlement(0, false),
+            PayoutPolicy::TreasurySweep
+        );
+    }
+
+    #[test]
+    fn test_position_fully_claimed() {
+        let (bling, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &test_config(),
+        );
+        let (old_payer, new_payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(post.register_pot_mint(bling, old_payer).unwrap(), 0b01);
+        assert_eq!(post.register_pot_mint(usdc, new_payer).unwrap(), 0b10);
+        assert_eq!(post.register_pot_mint(bling, new_payer).unwrap(), 0b01);
+        assert_eq!(post.pot_mints_mask(), 0b11);
+        // The pot's rent goes back to whoever created it, not to later voters
+        assert_eq!(post.pot_rent_payer(&bling), Some(old_payer));
+        assert_eq!(post.pot_rent_payer(&usdc), Some(new_payer));
+        assert_eq!(post.pot_rent_payer(&Pubkey::new_unique()), None);
+        for _ in 2..MAX_POST_POT_MINTS {
+            post.register_pot_mint(Pubkey::new_unique(), Pubkey::new_unique())
+                .unwrap();
+        }
+        assert!(post
+            .register_pot_mint(Pubkey::new_unique(), Pubkey::new_unique())
+            .is_err());
+        assert!(post.pot_mint_bit(&Pubkey::default()).is_err());
+
+        let mut post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &test_config(),
+        );
+        post.register_pot_mint(bling, Pubkey::new_unique()).unwrap();
+        post.register_pot_mint(usdc, Pubkey::new_unique()).unwrap();
+        let mut position = UserPostPosition::new(Pubkey::new_unique(), Pubkey::new_unique());
+        position.claimed_pot_mints = post.pot_mint_bit(&bling).unwrap();
+
+        // settled in one mint only
+        post.state = PostState::Settled;
+        post.settled_pot_mints = 0b01;
+        assert!(!post.fully_settled());
+        assert!(!position.fully_claimed(&post));
+
+        // fully settled, the other pot still claimable
+        post.settled_pot_mints = 0b11;
+        assert!(post.fully_settled());
+        assert!(!position.fully_claimed(&post));
+
+        // the unclaimed pot was swept
+        post.swept_pot_mints = 0b10;
+        assert!(position.fully_claimed(&post));
+    }
+
+    #[test]
+    fn test_vote_quote() {
+        let config = test_config();
+        let post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &config,
+        );
+        let user = UserAccount::new(Pubkey::new_unique(), 0, 255);
+        let position = UserPostPosition::new(user.user, Pubkey::new_unique());
+
+        let vote = Vote::new(Side::Pump, 3, user.user, Pubkey::new_unique());
+        let quote = vote
+            .quote(&post, &position, &user, &config, None, 9)
+            .unwrap();
+        assert_eq!(
+            quote.cost_bling,
+            vote.compute_cost_in_bling(&post, &position, &user, &config)
+                .unwrap()
+        );
+        assert_eq!(
+            quote.protocol_fee + quote.creator_pump_fee + quote.pot_increment,
+            quote.cost_bling
+        );
+        // paying in BLING: token amounts are the BLING amounts
+        assert_eq!(quote.cost_in_token, quote.cost_bling);
+        assert_eq!(quote.pot_increment_token, quote.pot_increment);
+
+        // smackers pay no creator fee
+        let smack = Vote::new(Side::Smack, 3, user.user, Pubkey::new_unique());
+        let quote = smack
+            .quote(&post, &position, &user, &config, Some(10_000), 6)
+            .unwrap();
+        assert_eq!(quote.creator_pump_fee, 0);
+        assert_eq!(
+            quote.cost_in_token,
+            quote.protocol_fee_token + quote.creator_pump_fee_token + quote.pot_increment_token
+        );
+        assert!(quote.cost_in_token < quote.cost_bling);
+    }
+
+    #[test]
+    fn test_session_privileges() {
+        use crate::constants::{SESSION_PRIVILEGE_CREATE_POST, SESSION_PRIVILEGE_VOTE_ON_POST};
+
+        let bling = Pubkey::new_unique();
+        let mut allowed_mints = [Pubkey::default(); MAX_SESSION_ALLOWED_MINTS];
+        allowed_mints[0] = bling;
+
+        let mut session = SessionAuthority {
+            user: Pubkey::new_unique(),
+            session_key: Pubkey::new_unique(),
+            expires_at: 0,
+            privileges_hash: [0; 32],
+            bump: 255,
+            privileges: SessionPrivileges {
+                allowed_instructions: SESSION_PRIVILEGE_VOTE_ON_POST,
+                max_bling_spend: 100,
+                allowed_mints,
+            },
+            bling_spent: 0,
+            rent_payer: Pubkey::new_unique(),
+        };
+
+        // instruction bitmap
+        assert!(session
+            .check_privileges(SESSION_PRIVILEGE_CREATE_POST, None, 0)
+            .is_err());
+
+        // mint list
+        assert!(session
+            .check_privileges(
+                SESSION_PRIVILEGE_VOTE_ON_POST,
+                Some(&Pubkey::new_unique()),
+                0
+            )
+            .is_err());
+
+        // spend cap is cumulative
+        session
+            .check_privileges(SESSION_PRIVILEGE_VOTE_ON_POST, Some(&bling), 60)
+            .unwrap();
+        assert!(session
+            .check_privileges(SESSION_PRIVILEGE_VOTE_ON_POST, Some(&bling), 41)
+            .is_err());
+        assert_eq!(session.bling_spent, 60);
+        session
+            .check_privileges(SESSION_PRIVILEGE_VOTE_ON_POST, Some(&bling), 40)
+            .unwrap();
+
+        // no listed mints → any mint
+        assert!(SessionPrivileges::default().allows_mint(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let cfg = test_config();
+        assert!(cfg.validate().is_ok());
+        assert_eq!(cfg.protocol_vote_fee_bps, PARAMS.protocol_vote_fee_bps);
+        assert_eq!(
+            cfg.bling_per_vote_base_cost,
+            PARAMS.bling_per_vote_base_cost
+        );
+    }
+
+    #[test]
+    fn test_apply_update_only_touches_set_fields() {
+        let mut cfg = test_config();
+        let before = cfg;
+        cfg.apply_update(&UpdateConfigParams {
+            protocol_vote_fee_bps: Some(250),
+            ..Default::default()
+        });
+        assert_eq!(cfg.protocol_vote_fee_bps, 250);
+        assert_eq!(cfg.creator_pump_fee_bps, before.creator_pump_fee_bps);
+        assert_eq!(cfg.base_duration_secs, before.base_duration_secs);
+        assert!(cfg.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_bounds() {
+        let mut cfg = test_config();
+        cfg.protocol_vote_fee_bps = MAX_VOTE_FEE_BPS;
+        cfg.creator_pump_fee_bps = 1;
+        assert!(cfg.validate().is_err());
+
+        let mut cfg = test_config();
+        cfg.creator_pump_win_settlement_fee_bps = MAX_BPS + 1;
+        assert!(cfg.validate().is_err());
+
+        let mut cfg = test_config();
+        cfg.base_duration_secs = cfg.max_duration_secs + 1;
+        assert!(cfg.validate().is_err());
+
+        let mut cfg = test_config();
+        cfg.bling_per_vote_base_cost = 0;
+        assert!(cfg.validate().is_err());
+
+        let mut cfg = test_config();
+        cfg.claim_window_secs = 0;
+        assert!(cfg.validate().is_err());
+        let mut cfg = test_config();
+        cfg.allowed_curves = 0;
+        assert!(cfg.validate().is_err());
+
+        let mut cfg = test_config();
+        cfg.allowed_curves = CurveKind::ALL_BITS + 1;
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_allowed_curves() {
+        let mut cfg = test_config();
+        assert!(cfg.allows_curve(CurveKind::Linear));
+        assert!(!cfg.allows_curve(CurveKind::Quadratic));
+
+        cfg.apply_update(&UpdateConfigParams {
+            allowed_curves: Some(CurveKind::ALL_BITS),
+            ..Default::default()
+        });
+        assert!(cfg.validate().is_ok());
+        assert!(cfg.allows_curve(CurveKind::ExponentialCapped));
+        assert!(cfg.allows_curve(CurveKind::Flat));
+    }
+
+    #[test]
+    fn test_mother_fee_schedule() {
+        let mut cfg = test_config();
+        assert_eq!(cfg.mother_fee_schedule(0), [0; MAX_MOTHER_FEE_LEVELS]);
+        assert_eq!(cfg.mother_fee_schedule(1), [1_000, 0, 0, 0]);
+        assert_eq!(cfg.mother_fee_schedule(2), [1_000, 500, 0, 0]);
+        // Capped at mother_fee_levels however deep the reply is
+        assert_eq!(cfg.mother_fee_schedule(10), [1_000, 500, 250, 0]);
+
+        cfg.apply_update(&UpdateConfigParams {
+            mother_fee_decay_bps: Some(MAX_BPS),
+            mother_fee_levels: Some(MAX_MOTHER_FEE_LEVELS as u8),
+            ..Default::default()
+        });
+        assert!(cfg.validate().is_ok());
+        assert_eq!(cfg.mother_fee_schedule(10), [1_000; MAX_MOTHER_FEE_LEVELS]);
+
+        cfg.mother_fee_levels = 0;
+        assert_eq!(cfg.mother_fee_schedule(10), [0; MAX_MOTHER_FEE_LEVELS]);
+
+        let mut cfg = test_config();
+        cfg.mother_fee_bps = MAX_MOTHER_FEE_BPS + 1;
+        assert!(cfg.validate().is_err());
+        let mut cfg = test_config();
+        cfg.mother_fee_levels = MAX_MOTHER_FEE_LEVELS as u8 + 1;
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_child_ancestors() {
+        let cfg = test_config();
+        let root_key = Pubkey::new_unique();
+        let root = PostAccount::new(
+            Pubkey::new_unique(),
+            [1; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &cfg,
+        );
+        assert_eq!(root.ancestor(1), None);
+
+        let mut reply = root;
+        reply.ancestors = root.child_ancestors(root_key);
+        assert_eq!(reply.ancestor(0), None);
+        assert_eq!(reply.ancestor(1), Some(root_key));
+        assert_eq!(reply.ancestor(2), None);
+
+        // The oldest ancestor falls off once the chain is longer than MAX_MOTHER_FEE_LEVELS
+        let mut post = reply;
+        let mut keys = vec![root_key];
+        for _ in 0..MAX_MOTHER_FEE_LEVELS {
+            let key = Pubkey::new_unique();
+            post.ancestors = post.child_ancestors(key);
+            keys.insert(0, key);
+        }
+        for level in 1..=MAX_MOTHER_FEE_LEVELS {
+            assert_eq!(post.ancestor(level as u8), Some(keys[level - 1]));
+        }
+        assert_eq!(post.ancestor(MAX_MOTHER_FEE_LEVELS as u8 + 1), None);
+    }
+
+    #[test]
+    fn test_take_mother_fee() {
+        let mut payout = PostMintPayout {
+            initial_pot: 1_000,
+            mother_fees: [100, 50, 0, 0],
+            mother_fee: 150,
+            ..test_payout()
+        };
+        assert_eq!(payout.take_mother_fee(2).unwrap(), 50);
+        assert_eq!(payout.take_mother_fee(2).unwrap(), 0);
+        assert_eq!(payout.take_mother_fee(1).unwrap(), 100);
+        assert_eq!(payout.take_mother_fee(1).unwrap(), 0);
+        assert!(payout.take_mother_fee(0).is_err());
+        assert!(payout
+            .take_mother_fee(MAX_MOTHER_FEE_LEVELS as u8 + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_fees_distributed() {
+        let mut payout = PostMintPayout {
+            initial_pot: 1_000,
+            creator_fee: 300,
+            protocol_fee: 10,
+            mother_fees: [100, 0, 0, 0],
+            mother_fee: 100,
+            ..test_payout()
+        };
+        assert!(!payout.fees_distributed());
+
+        assert_eq!(payout.take_creator_fee(), 300);
+        assert_eq!(payout.take_creator_fee(), 0);
+        assert_eq!(payout.take_protocol_fee(), 10);
+        assert_eq!(payout.take_protocol_fee(), 0);
+        assert!(!payout.fees_distributed());
+
+        payout.take_mother_fee(1).unwrap();
+        assert!(payout.fees_distributed());
+
+        // fees of 0 need no distribution
+        let nothing_charged = test_payout();
+        assert!(nothing_charged.fees_distributed());
+    }
+
+    #[test]
+    fn test_pause_flags() {
+        use crate::constants::{PAUSE_CLAIMS, PAUSE_VOTING, PAUSE_WITHDRAWALS};
+
+        let mut cfg = test_config();
+        assert!(cfg.require_not_paused(PAUSE_VOTING).is_ok());
+
+        // stop voting and claims, withdrawals stay open
+        cfg.paused = PAUSE_VOTING | PAUSE_CLAIMS;
+        assert!(cfg.require_not_paused(PAUSE_VOTING).is_err());
+        assert!(cfg.require_not_paused(PAUSE_CLAIMS).is_err());
+        assert!(cfg.require_not_paused(PAUSE_WITHDRAWALS).is_ok());
+    }
+
+    #[test]
+    fn test_tokenized_post() {
+        let mut post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &test_config(),
+        );
+        assert!(!post.tokenized());
+
+        let (pump_shares, smack_shares) = (Pubkey::new_unique(), Pubkey::new_unique());
+        post.share_mints = [pump_shares, smack_shares];
+        assert!(post.tokenized());
+        assert_eq!(post.share_mint(Side::Pump), pump_shares);
+        assert_eq!(post.share_mint(Side::Smack), smack_shares);
+
+        // one pot only
+        let bling = Pubkey::new_unique();
+        assert_eq!(
+            post.register_pot_mint(bling, Pubkey::new_unique()).unwrap(),
+            0b01
+        );
+        assert_eq!(
+            post.register_pot_mint(bling, Pubkey::new_unique()).unwrap(),
+            0b01
+        );
+        assert!(post
+            .register_pot_mint(Pubkey::new_unique(), Pubkey::new_unique())
+            .is_err());
+
+        // positions have nothing to claim once the post is settled
+        let position = UserPostPosition::new(Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(!position.fully_claimed(&post));
+        post.state = PostState::Settled;
+        post.settled_pot_mints = 0b01;
+        assert!(position.fully_claimed(&post));
+    }
+
+    #[test]
+    fn test_pot_contributions() {
+        let mut post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &test_config(),
+        );
+        let (bling, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        post.register_pot_mint(bling, Pubkey::new_unique()).unwrap();
+        post.register_pot_mint(usdc, Pubkey::new_unique()).unwrap();
+
+        let mut position = UserPostPosition::new(Pubkey::new_unique(), Pubkey::new_unique());
+        position
+            .add_pot_contribution(&mut post, &bling, 90)
+            .unwrap();
+        position.add_pot_contribution(&mut post, &usdc, 7).unwrap();
+        position
+            .add_pot_contribution(&mut post, &bling, 10)
+            .unwrap();
+        position
+            .reduce_pot_contribution(&mut post, &bling, 30)
+            .unwrap();
+        assert_eq!(post.pot_contributions[..2], [70, 7]);
+        assert!(position
+            .add_pot_contribution(&mut post, &Pubkey::new_unique(), 1)
+            .is_err());
+
+        // cancelled: the position can only be closed once every pot is refunded
+        post.state = PostState::Cancelled;
+        assert!(!position.fully_claimed(&post));
+        assert_eq!(
+            position.take_pot_contribution(&mut post, &bling).unwrap(),
+            70
+        );
+        assert_eq!(
+            position.take_pot_contribution(&mut post, &bling).unwrap(),
+            0
+        );
+        assert!(!position.fully_claimed(&post));
+        // or swept once the refund deadline passed
+        post.swept_pot_mints = post.pot_mint_bit(&usdc).unwrap();
+        assert!(position.fully_claimed(&post));
+        post.swept_pot_mints = 0;
+        assert_eq!(position.take_pot_contribution(&mut post, &usdc).unwrap(), 7);
+        assert!(position.fully_claimed(&post));
+        assert_eq!(post.pot_contributions, [0; MAX_POST_POT_MINTS]);
+
+        // selling above cost doesn't go negative
+        position
+            .reduce_pot_contribution(&mut post, &usdc, 5)
+            .unwrap();
+        assert_eq!(position.pot_contributions, [0; MAX_POST_POT_MINTS]);
+    }
+
+    #[test]
+    fn test_refunds_of_cancelled_post() {
+        let mut post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &test_config(),
+        );
+        let bling = Pubkey::new_unique();
+        post.register_pot_mint(bling, Pubkey::new_unique()).unwrap();
+        post.pot_contributions[0] = 100;
+
+        // a pot covering every contribution refunds each in full
+        assert_eq!(post.refund_for_contribution(&bling, 30, 100).unwrap(), 30);
+        assert_eq!(post.refund_for_contribution(&bling, 30, 120).unwrap(), 30);
+        // a short pot is shared pro rata, whoever cranks first
+        assert_eq!(post.refund_for_contribution(&bling, 30, 50).unwrap(), 15);
+        assert_eq!(post.refund_for_contribution(&bling, 70, 50).unwrap(), 35);
+        assert!(post
+            .refund_for_contribution(&Pubkey::new_unique(), 30, 50)
+            .is_err());
+
+        // unrevealed escrows share the same pot: 100 contributed and 100 escrowed
+        post.add_pot_escrow(&bling, 100).unwrap();
+        assert_eq!(post.refund_for_contribution(&bling, 30, 200).unwrap(), 30);
+        assert_eq!(post.refund_for_contribution(&bling, 30, 100).unwrap(), 15);
+        assert_eq!(post.refund_for_contribution(&bling, 100, 100).unwrap(), 50);
+        post.remove_pot_escrow(&bling, 100).unwrap();
+        assert_eq!(post.pot_escrows[0], 0);
+        assert!(!post.pot_swept(&bling));
+        post.swept_pot_mints = post.pot_mint_bit(&bling).unwrap();
+        assert!(post.pot_swept(&bling));
+
+        // shares refund what was paid for them, against what was paid on both sides
+        post.paid_bling = [300, 100];
+        assert_eq!(
+            post.refund_for_shares(Side::Pump, 5, 10, 200).unwrap(),
+            (75, 150)
+        );
+        assert_eq!(
+            post.refund_for_shares(Side::Smack, 10, 10, 200).unwrap(),
+            (50, 100)
+        );
+        assert_eq!(
+            post.refund_for_shares(Side::Smack, 0, 10, 200).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_exit_quote() {
+        let config = test_config();
+        let mut post = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &config,
+        );
+        let user = UserAccount::new(Pubkey::new_unique(), 0, 255);
+        let mut position = UserPostPosition::new(user.user, Pubkey::new_unique());
+        let (bling, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        post.register_pot_mint(bling, Pubkey::new_unique()).unwrap();
+        post.register_pot_mint(usdc, Pubkey::new_unique()).unwrap();
+
+        let vote = Vote::new(Side::Pump, 3, user.user, Pubkey::new_unique());
+        let bought = vote
+            .quote(&post, &position, &user, &config, None, 9)
+            .unwrap();
+        post.upvotes = 3;
+        position.upvotes = 3;
+        position
+            .add_pot_contribution(&mut post, &bling, bought.pot_increment)
+            .unwrap();
+
+        // Selling straight back values the votes at the pot share they paid
+        let exit = vote
+            .exit_quote(&post, &position, &user, &config)
+            .and_then(|q| q.in_token(bought.pot_increment, None, 9, config.exit_fee_bps))
+            .unwrap();
+        assert_eq!(exit.pot_value, bought.pot_increment);
+        assert_eq!(
+            exit.exit_fee,
+            exit.pot_value * config.exit_fee_bps as u64 / 10_000
+        );
+        assert_eq!(exit.refund + exit.exit_fee, exit.pot_value);
+        assert_eq!(exit.refund_token, exit.refund);
+
+        // Later votes on the same side raise the exit value, but the payout stays within
+        // what the position put in the pot
+        post.upvotes = 1_000;
+        let later = vote
+            .exit_quote(&post, &position, &user, &config)
+            .and_then(|q| q.in_token(bought.pot_increment, None, 9, config.exit_fee_bps))
+            .unwrap();
+        assert!(later.pot_value > exit.pot_value);
+        assert_eq!(
+            later.refund_token + later.exit_fee_token,
+            bought.pot_increment
+        );
+
+        // Nothing to take from a pot the position never paid into
+        let usdc_contribution = position.pot_contribution(&post, &usdc).unwrap();
+        assert!(vote
+            .exit_quote(&post, &position, &user, &config)
+            .and_then(|q| q.in_token(usdc_contribution, None, 9, config.exit_fee_bps))
+            .is_err());
+
+        // Can't sell more than the position holds
+        let too_many = Vote::new(Side::Pump, 4, user.user, Pubkey::new_unique());
+        assert!(too_many
+            .exit_quote(&post, &position, &user, &config)
+            .is_err());
+    }
+
+    #[test]
+    fn test_commit_reveal_windows() {
+        let config = test_config();
+        let open = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::Open,
+            0,
+            &config,
+        );
+        assert!(!open.votes_final(open.end_time - 1));
+        assert!(open.votes_final(open.end_time));
+        assert!(!open.within_reveal_window(open.end_time));
+
+        let hidden = PostAccount::new(
+            Pubkey::new_unique(),
+            [0; 32],
+            PostType::Original,
+            CurveKind::Linear,
+            VoteMode::CommitReveal,
+            0,
+            &config,
+        );
+        assert_eq!(
+            hidden.reveal_end_time,
+            hidden.end_time + config.reveal_window_secs as i64
+        );
+        assert!(!hidden.within_reveal_window(hidden.end_time - 1));
+        assert!(hidden.within_reveal_window(hidden.end_time));
+        assert!(!hidden.votes_final(hidden.end_time));
+        assert!(!hidden.within_reveal_window(hidden.reveal_end_time));
+        assert!(hidden.votes_final(hidden.reveal_end_time));
+    }
+
+    #[test]
+    fn test_vote_commitment_matches() {
+        let user = Pubkey::new_unique();
+        let salt = [7; 32];
+        let commitment = VoteCommitment::hash(&user, Side::Smack, 3, &salt);
+        let committed = VoteCommitment::new(
+            user,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            commitment,
+            1_000,
+            255,
+        );
+
+        assert!(committed.matches(Side::Smack, 3, &salt));
+        assert!(!committed.matches(Side::Pump, 3, &salt));
+        assert!(!committed.matches(Side::Smack, 4, &salt));
+        assert!(!committed.matches(Side::Smack, 3, &[8; 32]));
+
+        // someone else's commitment can't be replayed
+        assert_ne!(
+            VoteCommitment::hash(&Pubkey::new_unique(), Side::Smack, 3, &salt),
+            commitment
+        );
     }
 }
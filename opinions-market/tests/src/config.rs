@@ -4,18 +4,24 @@ pub struct TimeConfig {
     pub base_duration_secs: u32,
     pub max_duration_secs: u32,
     pub extension_per_vote_secs: u32,
+    pub claim_window_secs: u32,
+    pub reveal_window_secs: u32,
 }
 
 pub const TIME_CONFIG_FAST: TimeConfig = TimeConfig {
     base_duration_secs: 10,     // 10 seconds
     max_duration_secs: 60,      // 1 min
     extension_per_vote_secs: 5, // 5 seconds
+    claim_window_secs: 30,      // 30 seconds
+    reveal_window_secs: 30,     // 30 seconds
 };
 
 pub const TIME_CONFIG_NORMAL: TimeConfig = TimeConfig {
-    base_duration_secs: 24 * 3600,    // 1 day
-    max_duration_secs: 7 * 24 * 3600, // 7 days
-    extension_per_vote_secs: 60,      // 1 min
+    base_duration_secs: 24 * 3600,     // 1 day
+    max_duration_secs: 7 * 24 * 3600,  // 7 days
+    extension_per_vote_secs: 60,       // 1 min
+    claim_window_secs: 30 * 24 * 3600, // 30 days
+    reveal_window_secs: 24 * 3600,     // 1 day
 };
 
 pub struct Rates {
@@ -14,16 +14,26 @@ use solana_sdk::{
 
 use crate::config::TIME_CONFIG_FAST;
 use crate::utils::phenomena::{
-    test_phenomena_add_valid_payment, test_phenomena_claim_post_reward, test_phenomena_create_post,
-    test_phenomena_create_user, test_phenomena_deposit, test_phenomena_settle_post,
-    test_phenomena_vote_on_post, test_phenomena_withdraw,
+    test_phenomena_add_valid_payment, test_phenomena_cancel_post, test_phenomena_claim_post_reward,
+    test_phenomena_claim_post_rewards, test_phenomena_close_user_post_mint_claim,
+    test_phenomena_close_user_post_position, test_phenomena_close_vote_commitment,
+    test_phenomena_commit_vote, test_phenomena_create_post, test_phenomena_create_user,
+    test_phenomena_deposit, test_phenomena_exit_position, test_phenomena_modify_accepted_mint,
+    test_phenomena_refund_position, test_phenomena_reveal_vote, test_phenomena_revoke_session,
+    test_phenomena_rotate_session, test_phenomena_set_pause, test_phenomena_set_payer_authority,
+    test_phenomena_set_price_feed, test_phenomena_set_pushed_price_feed,
+    test_phenomena_settle_post, test_phenomena_sweep_cancelled_post_pot,
+    test_phenomena_sweep_post_pot, test_phenomena_transfer_admin, test_phenomena_update_config,
+    test_phenomena_update_pushed_price_feed, test_phenomena_vote_on_post, test_phenomena_withdraw,
 };
+use crate::utils::rates::RATES;
 use crate::utils::utils::{
     airdrop_sol_to_users, send_tx, setup_token_mint, setup_token_mint_ata_and_mint_to,
     setup_token_mint_ata_and_mint_to_many_users, wait_for_post_to_expire,
 };
-use opinions_market::constants::USDC_LAMPORTS_PER_USDC;
+use opinions_market::constants::{PAUSE_DEPOSITS, PAUSE_VOTING, USDC_LAMPORTS_PER_USDC};
 use opinions_market::pda_seeds::*;
+use opinions_market::state::{CurveKind, PayoutWeighting, VoteMode};
 use std::collections::HashMap;
 
 // #[tokio::test]
@@ -225,6 +235,21 @@ async fn test_setup() {
             .unwrap();
         println!("initialize tx: {:?}", initialize_tx);
 
+        test_phenomena_update_config(
+            &rpc,
+            &opinions_market,
+            &payer,
+            &admin,
+            opinions_market::state::UpdateConfigParams {
+                creator_pump_fee_bps: Some(200),
+                claim_window_secs: Some(TIME_CONFIG_FAST.claim_window_secs),
+                reveal_window_secs: Some(TIME_CONFIG_FAST.reveal_window_secs),
+                allowed_curves: Some(CurveKind::Linear.bit() | CurveKind::Quadratic.bit()),
+                ..Default::default()
+            },
+        )
+        .await;
+
         test_phenomena_add_valid_payment(&rpc, &opinions_market, &payer, &admin, &usdc_pubkey)
             .await;
 
@@ -238,6 +263,63 @@ async fn test_setup() {
         )
         .await;
 
+        // Delist Stablecoin, then relist it at the same price
+        test_phenomena_modify_accepted_mint(
+            &rpc,
+            &opinions_market,
+            &payer,
+            &admin,
+            &stablecoin_pubkey,
+            false,
+            None,
+        )
+        .await;
+        test_phenomena_modify_accepted_mint(
+            &rpc,
+            &opinions_market,
+            &payer,
+            &admin,
+            &stablecoin_pubkey,
+            true,
+            Some(RATES.usdc_to_bling),
+        )
+        .await;
+
+        // Price Stablecoin off a feed pushed by admin, standing in for Pyth on localnet:
+        // $1 a token at 1 USD = usdc_to_bling BLING
+        let stablecoin_price_feed = test_phenomena_set_pushed_price_feed(
+            &rpc,
+            &opinions_market,
+            &payer,
+            &admin,
+            &stablecoin_pubkey,
+            admin.pubkey(),
+        )
+        .await;
+        test_phenomena_update_pushed_price_feed(
+            &rpc,
+            &opinions_market,
+            &payer,
+            &admin,
+            &stablecoin_pubkey,
+            100_000_000,
+            0,
+            -8,
+        )
+        .await;
+        test_phenomena_set_price_feed(
+            &rpc,
+            &opinions_market,
+            &payer,
+            &admin,
+            &stablecoin_pubkey,
+            Some(stablecoin_price_feed),
+            3_600,
+            100,
+            RATES.usdc_to_bling,
+        )
+        .await;
+
         test_phenomena_create_user(
             &rpc,
             &opinions_market,
@@ -312,6 +394,18 @@ async fn test_setup() {
             .await;
         }
 
+        {
+            println!("admin pausing deposits and voting, withdrawals stay open");
+            test_phenomena_set_pause(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &admin,
+                PAUSE_DEPOSITS | PAUSE_VOTING,
+            )
+            .await;
+        }
+
         {
             println!("user 2 withdrawing 900 usdc from their vault to their wallet");
             test_phenomena_withdraw(
@@ -327,6 +421,11 @@ async fn test_setup() {
             .await;
         }
 
+        {
+            println!("admin unpausing everything");
+            test_phenomena_set_pause(&rpc, &opinions_market, &payer, &admin, 0).await;
+        }
+
         {
             println!("user 2 depositing 1_000_000 bling to their vault");
             test_phenomena_deposit(
@@ -385,6 +484,10 @@ async fn test_setup() {
                 &session_key,
                 &config_pda,
                 None, // Original post
+                CurveKind::Linear,
+                VoteMode::Open,
+                false,
+                PayoutWeighting::PerVote,
             )
             .await
         };
@@ -399,10 +502,139 @@ async fn test_setup() {
                 &session_key,
                 &config_pda,
                 Some(post_p1_pda), // Child post
+                CurveKind::Linear,
+                VoteMode::Open,
+                false,
+                PayoutWeighting::PerVote,
+            )
+            .await
+        };
+
+        let (post_p7_pda, _post_p7_id_hash) = {
+            println!("user 1 replies to P2 with P7, a grandchild of P1");
+            test_phenomena_create_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1,
+                &session_key,
+                &config_pda,
+                Some(post_p2_pda), // Grandchild post
+                CurveKind::Linear,
+                VoteMode::Open,
+                false,
+                PayoutWeighting::PerVote,
+            )
+            .await
+        };
+
+        {
+            println!("user 2 upvoting user 1's reply P7");
+            test_phenomena_vote_on_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &post_p7_pda,
+                opinions_market::state::Side::Pump,
+                3,
+                &bling_pubkey,
+                &bling_atas,
+                &config_pda,
+            )
+            .await;
+        }
+
+        let (post_p3_pda, _post_p3_id_hash) = {
+            println!("user 1 creating another original post P3 on the quadratic curve, paying out by stake");
+            test_phenomena_create_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1,
+                &session_key,
+                &config_pda,
+                None, // Original post
+                CurveKind::Quadratic,
+                VoteMode::Open,
+                false,
+                PayoutWeighting::Stake,
+            )
+            .await
+        };
+
+        let post_p4_pda = {
+            println!("user 2 creating a commit–reveal post P4");
+            let (post_p4_pda, _) = test_phenomena_create_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &config_pda,
+                None, // Original post
+                CurveKind::Linear,
+                VoteMode::CommitReveal,
+                false,
+                PayoutWeighting::PerVote,
+            )
+            .await;
+            post_p4_pda
+        };
+
+        let (post_p5_pda, _post_p5_id_hash) = {
+            println!("user 1 creating a tokenized post P5, its votes held as share tokens");
+            test_phenomena_create_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1,
+                &session_key,
+                &config_pda,
+                None, // Original post
+                CurveKind::Linear,
+                VoteMode::Open,
+                true,
+                PayoutWeighting::PerVote,
             )
             .await
         };
 
+        {
+            println!("user 1 and user 2 commit hidden votes on P4");
+            test_phenomena_commit_vote(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1,
+                &session_key,
+                &post_p4_pda,
+                opinions_market::state::Side::Smack,
+                1,
+                [1; 32],
+                100 * LAMPORTS_PER_SOL,
+                &bling_pubkey,
+                &config_pda,
+            )
+            .await;
+            test_phenomena_commit_vote(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &post_p4_pda,
+                opinions_market::state::Side::Pump,
+                2,
+                [2; 32],
+                100 * LAMPORTS_PER_SOL,
+                &bling_pubkey,
+                &config_pda,
+            )
+            .await;
+        }
+
         {
             println!("user 2 upvoting user 1's post P1");
             test_phenomena_vote_on_post(
@@ -421,6 +653,116 @@ async fn test_setup() {
             .await;
         }
 
+        {
+            println!("user 2 upvoting user 1's post P3");
+            test_phenomena_vote_on_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &post_p3_pda,
+                opinions_market::state::Side::Pump,
+                1,
+                &bling_pubkey,
+                &bling_atas,
+                &config_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("user 2 upvoting user 1's tokenized post P5, receiving Pump shares");
+            test_phenomena_vote_on_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &post_p5_pda,
+                opinions_market::state::Side::Pump,
+                2,
+                &bling_pubkey,
+                &bling_atas,
+                &config_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("user 2 creating a post P6 that the admin takes down after a vote");
+            let (post_p6_pda, _) = test_phenomena_create_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &config_pda,
+                None, // Original post
+                CurveKind::Linear,
+                VoteMode::Open,
+                false,
+                PayoutWeighting::PerVote,
+            )
+            .await;
+            test_phenomena_vote_on_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1,
+                &session_key,
+                &post_p6_pda,
+                opinions_market::state::Side::Smack,
+                1,
+                &bling_pubkey,
+                &bling_atas,
+                &config_pda,
+            )
+            .await;
+
+            test_phenomena_cancel_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &admin,
+                &post_p6_pda,
+                &config_pda,
+            )
+            .await;
+
+            println!("\n user 1 gets their P6 pot contribution back, then the position is closed");
+            test_phenomena_refund_position(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1.pubkey(),
+                &post_p6_pda,
+                &bling_pubkey,
+                &tokens,
+            )
+            .await;
+            test_phenomena_close_user_post_position(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1.pubkey(),
+                &post_p6_pda,
+            )
+            .await;
+
+            println!("\n after the refund deadline, anyone closes P6's BLING pot");
+            test_phenomena_sweep_cancelled_post_pot(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &post_p6_pda,
+                &bling_pubkey,
+                &tokens,
+                &config_pda,
+            )
+            .await;
+        }
+
         {
             println!("user 1 downvoting user 2's post P2");
             test_phenomena_vote_on_post(
@@ -517,10 +859,65 @@ async fn test_setup() {
             .await;
         }
 
+        {
+            println!("user 1 changes their mind and sells one P2 downvote back");
+            test_phenomena_exit_position(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1,
+                &session_key,
+                &post_p2_pda,
+                opinions_market::state::Side::Smack,
+                1,
+                &bling_pubkey,
+                &config_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("admin pausing voting, reveals stay open");
+            test_phenomena_set_pause(&rpc, &opinions_market, &payer, &admin, PAUSE_VOTING).await;
+        }
+
+        {
+            println!("P4 has ended: user 2 reveals their vote, user 1 never does");
+            test_phenomena_reveal_vote(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2.pubkey(),
+                &post_p4_pda,
+                opinions_market::state::Side::Pump,
+                2,
+                [2; 32],
+                &bling_pubkey,
+                &config_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("admin unpausing voting");
+            test_phenomena_set_pause(&rpc, &opinions_market, &payer, &admin, 0).await;
+        }
+
         //         Note: In a real test, you'd need to wait for the post to expire before settling
         // For now, we'll just show the settle function exists
         {
             // wait_seconds(TIME_CONFIG_FAST.max_duration_secs as u64).await;
+            println!("Settling P7 first: its mother fees go to P2 and, decayed, to P1");
+            test_phenomena_settle_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &post_p7_pda,
+                &tokens,
+                &config_pda,
+            )
+            .await;
+
             println!("Settling post P1");
             test_phenomena_settle_post(
                 &rpc,
@@ -531,6 +928,28 @@ async fn test_setup() {
                 &config_pda,
             )
             .await;
+
+            println!("Settling post P3");
+            test_phenomena_settle_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &post_p3_pda,
+                &tokens,
+                &config_pda,
+            )
+            .await;
+
+            println!("Settling post P5");
+            test_phenomena_settle_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &post_p5_pda,
+                &tokens,
+                &config_pda,
+            )
+            .await;
         }
 
         {
@@ -549,6 +968,147 @@ async fn test_setup() {
             .await;
         }
 
+        {
+            println!("\n user 2 burns their P5 shares for the reward, signing themselves");
+            test_phenomena_claim_post_reward(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &post_p5_pda,
+                &bling_pubkey,
+                &tokens,
+                &config_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("\n payer cranks user 2's remaining claims on P1 and P3 in one batch");
+            test_phenomena_claim_post_rewards(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2.pubkey(),
+                &[post_p1_pda, post_p3_pda],
+                &bling_pubkey,
+                &tokens,
+            )
+            .await;
+        }
+
+        {
+            println!(
+                "\n payer closes user 2's claim record and position on P3, getting the rent back"
+            );
+            test_phenomena_close_user_post_mint_claim(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2.pubkey(),
+                &post_p3_pda,
+                &bling_pubkey,
+            )
+            .await;
+            test_phenomena_close_user_post_position(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2.pubkey(),
+                &post_p3_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("\n after the claim window, anyone sweeps P1's BLING dust to the treasury");
+            test_phenomena_sweep_post_pot(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &post_p1_pda,
+                &bling_pubkey,
+                &[user_2.pubkey()],
+                &tokens,
+                &config_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("\n user 2's P1 position is fully claimed, so it can be closed too");
+            test_phenomena_close_user_post_position(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2.pubkey(),
+                &post_p1_pda,
+            )
+            .await;
+        }
+
+        {
+            println!(
+                "\n after the reveal window, user 1's unrevealed escrow is forfeited to P4's pot"
+            );
+            test_phenomena_close_vote_commitment(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_1.pubkey(),
+                &post_p4_pda,
+            )
+            .await;
+
+            println!("Settling post P4 on the revealed votes only");
+            test_phenomena_settle_post(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &post_p4_pda,
+                &tokens,
+                &config_pda,
+            )
+            .await;
+        }
+
+        {
+            println!("\n user 2 rotates their session key, then revokes it");
+            let rotated_session_key = Keypair::new();
+            test_phenomena_rotate_session(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &session_key,
+                &rotated_session_key,
+            )
+            .await;
+            test_phenomena_revoke_session(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &user_2,
+                &rotated_session_key.pubkey(),
+            )
+            .await;
+        }
+
+        {
+            println!("\n admin hands the protocol to a new admin, who rotates the payer");
+            let new_admin = Keypair::new();
+            test_phenomena_transfer_admin(&rpc, &opinions_market, &payer, &admin, &new_admin).await;
+            test_phenomena_set_payer_authority(
+                &rpc,
+                &opinions_market,
+                &payer,
+                &new_admin,
+                &Keypair::new().pubkey(),
+            )
+            .await;
+        }
+
         // {
         //     println!("user 3 trying to make a post");
         //     // This would Cause an error because user 3 is not a user in the system
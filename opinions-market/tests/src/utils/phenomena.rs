@@ -3,16 +3,21 @@ use std::collections::HashMap;
 use anchor_client::anchor_lang::require;
 use anchor_client::anchor_lang::solana_program::example_mocks::solana_sdk::system_program;
 use anchor_client::Program;
+use anchor_spl::associated_token::spl_associated_token_account::{
+    self, instruction::create_associated_token_account_idempotent,
+};
 use anchor_spl::token::spl_token;
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::{signature::Keypair, signer::Signer};
 
 use crate::config::TIME_CONFIG_FAST;
 use crate::utils::rates::RATES;
 use crate::utils::utils::{
-    create_ed25519_instruction_for_session, current_chain_timestamp, send_tx,
-    sign_message_for_session_registration, wait_for_post_to_expire, PRIVILEGES_HASH,
+    create_ed25519_instruction_for_session, current_chain_timestamp, send_tx, session_privileges,
+    sign_as_session_key, sign_message_for_session_registration, simulate_return_data,
+    wait_for_post_to_expire, wait_for_seconds,
 };
 use opinions_market::pda_seeds::*;
 
@@ -58,6 +63,7 @@ pub async fn test_phenomena_add_valid_payment(
         .accounts(opinions_market::accounts::RegisterValidPayment {
             config: config_pda,
             admin: admin.pubkey(),
+            payer: payer.pubkey(),
             token_mint: new_token_mint.clone(),
             valid_payment: valid_payment_pda,
             protocol_token_treasury_token_account: treasury_token_account_pda,
@@ -99,6 +105,437 @@ pub async fn test_phenomena_add_valid_payment(
     );
 }
 
+pub async fn test_phenomena_modify_accepted_mint(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    token_mint: &Pubkey,
+    enabled: bool,
+    price_in_bling: Option<u64>,
+) {
+    println!(
+        "modifying accepted mint {:}: enabled={}, price_in_bling={:?}",
+        token_mint, enabled, price_in_bling
+    );
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+    let valid_payment_pda = Pubkey::find_program_address(
+        &[VALID_PAYMENT_SEED, token_mint.as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+
+    let valid_payment_before = opinions_market
+        .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+        .await
+        .unwrap();
+
+    let modify_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::ModifyAcceptedMint {
+            config: config_pda,
+            admin: admin.pubkey(),
+            mint: *token_mint,
+            accepted_mint: valid_payment_pda,
+        })
+        .args(opinions_market::instruction::ModifyAcceptedMint {
+            enabled,
+            price_in_bling,
+        })
+        .instructions()
+        .unwrap();
+
+    let modify_tx = send_tx(&rpc, modify_ix, &payer.pubkey(), &[&payer, &admin])
+        .await
+        .unwrap();
+    println!("modify accepted mint tx: {:?}", modify_tx);
+
+    let valid_payment_after = opinions_market
+        .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+        .await
+        .unwrap();
+    assert_eq!(valid_payment_after.enabled, enabled);
+    assert_eq!(
+        valid_payment_after.price_in_bling,
+        price_in_bling.unwrap_or(valid_payment_before.price_in_bling),
+        "Price should only change when a new price is given"
+    );
+    println!("✅ Accepted mint modified successfully");
+}
+
+pub async fn test_phenomena_set_price_feed(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    token_mint: &Pubkey,
+    price_feed: Option<Pubkey>,
+    max_staleness_secs: u32,
+    max_confidence_bps: u16,
+    bling_per_feed_unit: u64,
+) {
+    println!(
+        "setting price feed for {:}: feed={:?}, max_staleness_secs={}, max_confidence_bps={}, bling_per_feed_unit={}",
+        token_mint, price_feed, max_staleness_secs, max_confidence_bps, bling_per_feed_unit
+    );
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+    let valid_payment_pda = Pubkey::find_program_address(
+        &[VALID_PAYMENT_SEED, token_mint.as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+
+    let set_price_feed_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::SetPriceFeed {
+            config: config_pda,
+            admin: admin.pubkey(),
+            token_mint: *token_mint,
+            valid_payment: valid_payment_pda,
+            price_feed,
+        })
+        .args(opinions_market::instruction::SetPriceFeed {
+            max_staleness_secs,
+            max_confidence_bps,
+            bling_per_feed_unit,
+        })
+        .instructions()
+        .unwrap();
+
+    let set_price_feed_tx = send_tx(&rpc, set_price_feed_ix, &payer.pubkey(), &[&payer, &admin])
+        .await
+        .unwrap();
+    println!("set price feed tx: {:?}", set_price_feed_tx);
+
+    let valid_payment_after = opinions_market
+        .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+        .await
+        .unwrap();
+    assert_eq!(valid_payment_after.price_feed, price_feed);
+    if price_feed.is_some() {
+        assert_eq!(valid_payment_after.max_staleness_secs, max_staleness_secs);
+        assert_eq!(valid_payment_after.max_confidence_bps, max_confidence_bps);
+        assert_eq!(valid_payment_after.bling_per_feed_unit, bling_per_feed_unit);
+    }
+    println!("✅ Price feed set successfully");
+}
+
+/// Creates the admin-pushed price feed of `token_mint` (localnet has no Pyth accounts)
+/// and returns its address
+pub async fn test_phenomena_set_pushed_price_feed(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    token_mint: &Pubkey,
+    feed_authority: Pubkey,
+) -> Pubkey {
+    println!(
+        "setting pushed price feed for {:}: authority={:}",
+        token_mint, feed_authority
+    );
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+    let pushed_price_feed_pda = Pubkey::find_program_address(
+        &[PUSHED_PRICE_FEED_SEED, token_mint.as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+
+    let set_pushed_price_feed_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::SetPushedPriceFeed {
+            config: config_pda,
+            admin: admin.pubkey(),
+            payer: payer.pubkey(),
+            token_mint: *token_mint,
+            pushed_price_feed: pushed_price_feed_pda,
+            system_program: system_program::ID,
+        })
+        .args(opinions_market::instruction::SetPushedPriceFeed { feed_authority })
+        .instructions()
+        .unwrap();
+
+    let set_pushed_price_feed_tx = send_tx(
+        &rpc,
+        set_pushed_price_feed_ix,
+        &payer.pubkey(),
+        &[&payer, &admin],
+    )
+    .await
+    .unwrap();
+    println!("set pushed price feed tx: {:?}", set_pushed_price_feed_tx);
+
+    let pushed_price_feed = opinions_market
+        .account::<opinions_market::state::PushedPriceFeed>(pushed_price_feed_pda)
+        .await
+        .unwrap();
+    assert_eq!(pushed_price_feed.token_mint, *token_mint);
+    assert_eq!(pushed_price_feed.authority, feed_authority);
+    println!("✅ Pushed price feed set successfully");
+
+    pushed_price_feed_pda
+}
+
+pub async fn test_phenomena_update_pushed_price_feed(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    feed_authority: &Keypair,
+    token_mint: &Pubkey,
+    price: i64,
+    conf: u64,
+    exponent: i32,
+) {
+    println!(
+        "updating pushed price feed for {:}: price={}, conf={}, exponent={}",
+        token_mint, price, conf, exponent
+    );
+    let pushed_price_feed_pda = Pubkey::find_program_address(
+        &[PUSHED_PRICE_FEED_SEED, token_mint.as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+
+    let update_pushed_price_feed_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::UpdatePushedPriceFeed {
+            authority: feed_authority.pubkey(),
+            pushed_price_feed: pushed_price_feed_pda,
+        })
+        .args(opinions_market::instruction::UpdatePushedPriceFeed {
+            price,
+            conf,
+            exponent,
+        })
+        .instructions()
+        .unwrap();
+
+    let update_pushed_price_feed_tx = send_tx(
+        &rpc,
+        update_pushed_price_feed_ix,
+        &payer.pubkey(),
+        &[&payer, &feed_authority],
+    )
+    .await
+    .unwrap();
+    println!(
+        "update pushed price feed tx: {:?}",
+        update_pushed_price_feed_tx
+    );
+
+    let pushed_price_feed = opinions_market
+        .account::<opinions_market::state::PushedPriceFeed>(pushed_price_feed_pda)
+        .await
+        .unwrap();
+    assert_eq!(pushed_price_feed.price, price);
+    assert_eq!(pushed_price_feed.conf, conf);
+    assert_eq!(pushed_price_feed.exponent, exponent);
+    println!("✅ Pushed price feed updated successfully");
+}
+
+pub async fn test_phenomena_update_config(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    params: opinions_market::state::UpdateConfigParams,
+) {
+    println!("updating config with {:?}", params);
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+
+    let config_before = opinions_market
+        .account::<opinions_market::state::Config>(config_pda)
+        .await
+        .unwrap();
+
+    let update_config_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::UpdateConfig {
+            config: config_pda,
+            admin: admin.pubkey(),
+        })
+        .args(opinions_market::instruction::UpdateConfig { params })
+        .instructions()
+        .unwrap();
+
+    let update_config_tx = send_tx(&rpc, update_config_ix, &payer.pubkey(), &[&payer, &admin])
+        .await
+        .unwrap();
+    println!("update config tx: {:?}", update_config_tx);
+
+    let config_after = opinions_market
+        .account::<opinions_market::state::Config>(config_pda)
+        .await
+        .unwrap();
+
+    let mut expected = config_before;
+    expected.apply_update(&params);
+    assert_eq!(
+        config_after, expected,
+        "Config should only change the fields set in the update"
+    );
+    println!("✅ Config updated successfully");
+}
+
+pub async fn test_phenomena_set_pause(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    paused: u8,
+) {
+    println!("setting pause flags to {:#07b}", paused);
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+
+    let set_pause_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::SetPause {
+            config: config_pda,
+            admin: admin.pubkey(),
+        })
+        .args(opinions_market::instruction::SetPause { paused })
+        .instructions()
+        .unwrap();
+
+    let set_pause_tx = send_tx(&rpc, set_pause_ix, &payer.pubkey(), &[&payer, &admin])
+        .await
+        .unwrap();
+    println!("set pause tx: {:?}", set_pause_tx);
+
+    let config_after = opinions_market
+        .account::<opinions_market::state::Config>(config_pda)
+        .await
+        .unwrap();
+    assert_eq!(config_after.paused, paused, "Pause flags should be stored");
+    println!("✅ Pause flags set successfully");
+}
+
+pub async fn test_phenomena_transfer_admin(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    new_admin: &Keypair,
+) {
+    println!(
+        "transferring admin {:} -> {:}",
+        admin.pubkey(),
+        new_admin.pubkey()
+    );
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+
+    let propose_admin_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::ProposeAdmin {
+            config: config_pda,
+            admin: admin.pubkey(),
+        })
+        .args(opinions_market::instruction::ProposeAdmin {
+            new_admin: new_admin.pubkey(),
+        })
+        .instructions()
+        .unwrap();
+
+    let propose_admin_tx = send_tx(&rpc, propose_admin_ix, &payer.pubkey(), &[&payer, &admin])
+        .await
+        .unwrap();
+    println!("propose admin tx: {:?}", propose_admin_tx);
+
+    let config_proposed = opinions_market
+        .account::<opinions_market::state::Config>(config_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        config_proposed.admin,
+        admin.pubkey(),
+        "Admin should not change on propose"
+    );
+    assert_eq!(config_proposed.pending_admin, new_admin.pubkey());
+
+    let accept_admin_ix = |new_admin: Pubkey| {
+        opinions_market
+            .request()
+            .accounts(opinions_market::accounts::AcceptAdmin {
+                config: config_pda,
+                new_admin,
+            })
+            .args(opinions_market::instruction::AcceptAdmin {})
+            .instructions()
+            .unwrap()
+    };
+
+    // Only the pending admin can accept
+    assert!(
+        send_tx(
+            &rpc,
+            accept_admin_ix(payer.pubkey()),
+            &payer.pubkey(),
+            &[&payer]
+        )
+        .await
+        .is_err(),
+        "Only the pending admin should be able to accept"
+    );
+
+    let accept_admin_tx = send_tx(
+        &rpc,
+        accept_admin_ix(new_admin.pubkey()),
+        &payer.pubkey(),
+        &[&payer, &new_admin],
+    )
+    .await
+    .unwrap();
+    println!("accept admin tx: {:?}", accept_admin_tx);
+
+    let config_after = opinions_market
+        .account::<opinions_market::state::Config>(config_pda)
+        .await
+        .unwrap();
+    assert_eq!(config_after.admin, new_admin.pubkey());
+    assert_eq!(config_after.pending_admin, Pubkey::default());
+    println!("✅ Admin transferred successfully");
+}
+
+pub async fn test_phenomena_set_payer_authority(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    payer_authority: &Pubkey,
+) {
+    println!("rotating payer authority to {:}", payer_authority);
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+
+    let set_payer_authority_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::SetPayerAuthority {
+            config: config_pda,
+            admin: admin.pubkey(),
+        })
+        .args(opinions_market::instruction::SetPayerAuthority {
+            payer_authority: *payer_authority,
+        })
+        .instructions()
+        .unwrap();
+
+    let set_payer_authority_tx = send_tx(
+        &rpc,
+        set_payer_authority_ix,
+        &payer.pubkey(),
+        &[&payer, &admin],
+    )
+    .await
+    .unwrap();
+    println!("set payer authority tx: {:?}", set_payer_authority_tx);
+
+    let config_after = opinions_market
+        .account::<opinions_market::state::Config>(config_pda)
+        .await
+        .unwrap();
+    assert_eq!(config_after.payer_authroity, *payer_authority);
+    println!("✅ Payer authority rotated successfully");
+}
+
 pub async fn test_phenomena_create_user(
     rpc: &RpcClient,
     opinions_market: &Program<&Keypair>,
@@ -150,11 +587,26 @@ pub async fn test_phenomena_create_user(
     // regisaer session
     // let now = Clock::get().unwrap().unix_timestamp;
     // user signs (for verification purposes, though ed25519_ix will re-sign)
-    let _signature_bytes = sign_message_for_session_registration(user, &session_key.pubkey());
+    let expires_at = current_chain_timestamp(&rpc).await + 60 * 60 * 24; // 1 day
+    let privileges_hash = session_privileges().hash();
+    let session_nonce = user_account.session_nonce;
+    let _signature_bytes = sign_message_for_session_registration(
+        user,
+        &session_key.pubkey(),
+        expires_at,
+        &privileges_hash,
+        session_nonce,
+    );
     // --------------------------
     // ED25519 VERIFY IX
     // --------------------------
-    let ed25519_ix = create_ed25519_instruction_for_session(user, &session_key.pubkey());
+    let ed25519_ix = create_ed25519_instruction_for_session(
+        user,
+        &session_key.pubkey(),
+        expires_at,
+        &privileges_hash,
+        session_nonce,
+    );
 
     // Derive session authority PDA
     let (session_authority_pda, _) = Pubkey::find_program_address(
@@ -175,11 +627,15 @@ pub async fn test_phenomena_create_user(
             payer: payer.pubkey(),
             user: user.pubkey(),
             session_key: session_key.pubkey(),
+            user_account: user_account_pda,
             session_authority: session_authority_pda,
             instructions_sysvar,
             system_program: system_program::ID,
         })
-        .args(opinions_market::instruction::RegisterSession { expected_index: 0 })
+        .args(opinions_market::instruction::RegisterSession {
+            expected_index: 0,
+            privileges: session_privileges(),
+        })
         .instructions()
         .unwrap();
 
@@ -189,7 +645,7 @@ pub async fn test_phenomena_create_user(
 
     let ed25519_and_register_session_tx = send_tx(
         &rpc,
-        ed25519_and_register_session_ix,
+        ed25519_and_register_session_ix.clone(),
         &payer.pubkey(),
         &[&payer],
     )
@@ -197,14 +653,247 @@ pub async fn test_phenomena_create_user(
     .unwrap();
     println!("register session tx: {:?}", ed25519_and_register_session_tx);
 
-    // verify session was registered
-    let session_authority = opinions_market
-        .account::<opinions_market::state::SessionAuthority>(session_authority_pda)
+    // The signed REGISTER message consumed the nonce, so it cannot be replayed
+    let user_account = opinions_market
+        .account::<opinions_market::state::UserAccount>(user_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(user_account.session_nonce, session_nonce + 1);
+    assert!(
+        send_tx(
+            &rpc,
+            ed25519_and_register_session_ix,
+            &payer.pubkey(),
+            &[&payer]
+        )
+        .await
+        .is_err(),
+        "Replaying the REGISTER message should fail"
+    );
+
+    // verify session was registered
+    let session_authority = opinions_market
+        .account::<opinions_market::state::SessionAuthority>(session_authority_pda)
         .await
         .unwrap();
 
     assert_eq!(session_authority.user, user.pubkey());
     assert_eq!(session_authority.session_key, session_key.pubkey());
+    assert_eq!(session_authority.privileges, session_privileges());
+    assert_eq!(
+        session_authority.privileges_hash,
+        session_privileges().hash()
+    );
+}
+
+pub async fn test_phenomena_rotate_session(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    user: &Keypair,
+    old_session_key: &Keypair,
+    new_session_key: &Keypair,
+) {
+    println!(
+        "rotating session for {:}: {:} -> {:}",
+        user.pubkey(),
+        old_session_key.pubkey(),
+        new_session_key.pubkey()
+    );
+    let old_session_authority_pda = Pubkey::find_program_address(
+        &[
+            SESSION_AUTHORITY_SEED,
+            user.pubkey().as_ref(),
+            old_session_key.pubkey().as_ref(),
+        ],
+        &opinions_market.id(),
+    )
+    .0;
+    let user_account_pda = Pubkey::find_program_address(
+        &[USER_ACCOUNT_SEED, user.pubkey().as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+    let new_session_authority_pda = Pubkey::find_program_address(
+        &[
+            SESSION_AUTHORITY_SEED,
+            user.pubkey().as_ref(),
+            new_session_key.pubkey().as_ref(),
+        ],
+        &opinions_market.id(),
+    )
+    .0;
+
+    let old_session_authority = opinions_market
+        .account::<opinions_market::state::SessionAuthority>(old_session_authority_pda)
+        .await
+        .unwrap();
+
+    let rotate_session_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::RotateSession {
+            payer: payer.pubkey(),
+            user: user.pubkey(),
+            old_session_key: old_session_key.pubkey(),
+            user_account: user_account_pda,
+            old_session_authority: old_session_authority_pda,
+            rent_payer: old_session_authority.rent_payer,
+            new_session_key: new_session_key.pubkey(),
+            new_session_authority: new_session_authority_pda,
+            instructions_sysvar: solana_sdk::sysvar::instructions::ID,
+            system_program: system_program::ID,
+        })
+        .args(opinions_market::instruction::RotateSession {
+            expected_index: None,
+        })
+        .instructions()
+        .unwrap();
+
+    // user signs the tx directly, no Ed25519 instruction needed
+    let rotate_session_tx = send_tx(&rpc, rotate_session_ix, &payer.pubkey(), &[&payer, &user])
+        .await
+        .unwrap();
+    println!("rotate session tx: {:?}", rotate_session_tx);
+
+    assert!(
+        rpc.get_account(&old_session_authority_pda).await.is_err(),
+        "Old session authority should be closed"
+    );
+
+    let new_session_authority = opinions_market
+        .account::<opinions_market::state::SessionAuthority>(new_session_authority_pda)
+        .await
+        .unwrap();
+    assert_eq!(new_session_authority.session_key, new_session_key.pubkey());
+    assert_eq!(
+        new_session_authority.expires_at,
+        old_session_authority.expires_at
+    );
+    assert_eq!(
+        new_session_authority.privileges,
+        old_session_authority.privileges
+    );
+    assert_eq!(
+        new_session_authority.bling_spent,
+        old_session_authority.bling_spent
+    );
+    println!("✅ Session rotated successfully");
+}
+
+pub async fn test_phenomena_revoke_session(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    user: &Keypair,
+    session_key: &Pubkey,
+) {
+    println!("revoking session {:} for {:}", session_key, user.pubkey());
+    let session_authority_pda = Pubkey::find_program_address(
+        &[
+            SESSION_AUTHORITY_SEED,
+            user.pubkey().as_ref(),
+            session_key.as_ref(),
+        ],
+        &opinions_market.id(),
+    )
+    .0;
+    let user_account_pda = Pubkey::find_program_address(
+        &[USER_ACCOUNT_SEED, user.pubkey().as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+
+    let session_authority = opinions_market
+        .account::<opinions_market::state::SessionAuthority>(session_authority_pda)
+        .await
+        .unwrap();
+    let session_nonce_before = opinions_market
+        .account::<opinions_market::state::UserAccount>(user_account_pda)
+        .await
+        .unwrap()
+        .session_nonce;
+    let rent_payer_balance_before = rpc
+        .get_balance(&session_authority.rent_payer)
+        .await
+        .unwrap();
+
+    let revoke_session_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::RevokeSession {
+            payer: payer.pubkey(),
+            user: user.pubkey(),
+            session_key: *session_key,
+            user_account: user_account_pda,
+            session_authority: session_authority_pda,
+            rent_payer: session_authority.rent_payer,
+            instructions_sysvar: solana_sdk::sysvar::instructions::ID,
+        })
+        .args(opinions_market::instruction::RevokeSession {
+            expected_index: None,
+        })
+        .instructions()
+        .unwrap();
+
+    // user signs the tx directly, no Ed25519 instruction needed
+    let revoke_session_tx = send_tx(&rpc, revoke_session_ix, &payer.pubkey(), &[&payer, &user])
+        .await
+        .unwrap();
+    println!("revoke session tx: {:?}", revoke_session_tx);
+
+    assert!(
+        rpc.get_account(&session_authority_pda).await.is_err(),
+        "Session authority should be closed"
+    );
+
+    // rent goes back to whoever funded the session (it outweighs the tx fee when that's the payer)
+    let rent_payer_balance_after = rpc
+        .get_balance(&session_authority.rent_payer)
+        .await
+        .unwrap();
+    assert!(
+        rent_payer_balance_after > rent_payer_balance_before,
+        "Session rent should go back to the rent payer"
+    );
+
+    // A REGISTER the user signed before the revoke must not bring the session back
+    let ed25519_ix = create_ed25519_instruction_for_session(
+        user,
+        session_key,
+        session_authority.expires_at,
+        &session_authority.privileges_hash,
+        session_nonce_before,
+    );
+    let mut replay_register_ix = vec![ed25519_ix];
+    replay_register_ix.extend(
+        opinions_market
+            .request()
+            .accounts(opinions_market::accounts::RegisterSession {
+                payer: payer.pubkey(),
+                user: user.pubkey(),
+                session_key: *session_key,
+                user_account: user_account_pda,
+                session_authority: session_authority_pda,
+                instructions_sysvar: solana_sdk::sysvar::instructions::ID,
+                system_program: system_program::ID,
+            })
+            .args(opinions_market::instruction::RegisterSession {
+                expected_index: 0,
+                privileges: session_authority.privileges,
+            })
+            .instructions()
+            .unwrap(),
+    );
+    assert!(
+        send_tx(&rpc, replay_register_ix, &payer.pubkey(), &[&payer])
+            .await
+            .is_err(),
+        "A REGISTER signed before the revoke should not restore the session"
+    );
+    assert!(
+        rpc.get_account(&session_authority_pda).await.is_err(),
+        "Session authority should stay closed"
+    );
+    println!("✅ Session revoked successfully");
 }
 
 pub async fn test_phenomena_deposit(
@@ -253,6 +942,7 @@ pub async fn test_phenomena_deposit(
     let deposit_ix = opinions_market
         .request()
         .accounts(opinions_market::accounts::Deposit {
+            config: *config_pda,
             user: user.pubkey(),
             payer: payer.pubkey(),
             user_account: user_account_pda,
@@ -326,17 +1016,51 @@ pub async fn test_phenomena_withdraw(
         .unwrap()
         .amount;
 
+    let config_pda = Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0;
+
+    let protocol_treasury_token_account_pda = Pubkey::find_program_address(
+        &[PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+
     let user_token_ata = token_atas.get(&user.pubkey()).unwrap();
 
+    let user_balance_original = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(*user_token_ata)
+        .await
+        .unwrap()
+        .amount;
+
+    let treasury_balance_original = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(protocol_treasury_token_account_pda)
+        .await
+        .unwrap()
+        .amount;
+
+    let user_account = opinions_market
+        .account::<opinions_market::state::UserAccount>(user_account_pda)
+        .await
+        .unwrap();
+    let (effective_amount, penalty) = user_account.split_withdrawal(amount).unwrap();
+    println!(
+        "expected penalty: {} ({} bps at social score {})",
+        penalty,
+        user_account.withdraw_penalty_bps(),
+        user_account.social_score
+    );
+
     let withdraw_ix = opinions_market
         .request()
         .accounts(opinions_market::accounts::Withdraw {
+            config: config_pda,
             user: user.pubkey(),
             payer: payer.pubkey(),
             user_account: user_account_pda,
             token_mint: token_mint.clone(),
             user_token_dest_ata: *user_token_ata,
             user_vault_token_account: vault_token_account_pda,
+            protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
             vault_authority: vault_authority_pda,
             token_program: spl_token::ID,
         })
@@ -365,7 +1089,18 @@ pub async fn test_phenomena_withdraw(
         .account::<anchor_spl::token::TokenAccount>(*user_token_ata)
         .await
         .unwrap();
+    assert_eq!(
+        user_balance.amount,
+        user_balance_original + effective_amount
+    );
     println!("✅ User wallet balance: {}", user_balance.amount);
+
+    // Verify the penalty landed in the protocol treasury
+    let treasury_balance = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(protocol_treasury_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(treasury_balance.amount, treasury_balance_original + penalty);
 }
 
 pub async fn test_phenomena_create_post(
@@ -377,6 +1112,10 @@ pub async fn test_phenomena_create_post(
     config_pda: &Pubkey,
 
     parent_post_pda: Option<Pubkey>,
+    curve: opinions_market::state::CurveKind,
+    vote_mode: opinions_market::state::VoteMode,
+    tokenized: bool,
+    payout_weighting: opinions_market::state::PayoutWeighting,
 ) -> (Pubkey, [u8; 32]) {
     let post_type_str = if parent_post_pda.is_some() {
         "child post"
@@ -405,6 +1144,37 @@ pub async fn test_phenomena_create_post(
         &opinions_market.id(),
     )
     .0;
+    let post_pot_authority_pda = Pubkey::find_program_address(
+        &[POST_POT_AUTHORITY_SEED, post_pda.as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+    let share_mint_pda = |side: opinions_market::state::Side| {
+        Pubkey::find_program_address(
+            &[POST_SHARE_MINT_SEED, post_pda.as_ref(), &[side as u8]],
+            &opinions_market.id(),
+        )
+        .0
+    };
+    let (pump_share_mint, smack_share_mint) = if tokenized {
+        (
+            Some(share_mint_pda(opinions_market::state::Side::Pump)),
+            Some(share_mint_pda(opinions_market::state::Side::Smack)),
+        )
+    } else {
+        (None, None)
+    };
+    let parent_child_count_before = match parent_post_pda {
+        Some(parent) => Some(
+            opinions_market
+                .account::<opinions_market::state::PostAccount>(parent)
+                .await
+                .unwrap()
+                .child_count,
+        ),
+        None => None,
+    };
+
     let create_post_ix = opinions_market
         .request()
         .accounts(opinions_market::accounts::CreatePost {
@@ -415,19 +1185,41 @@ pub async fn test_phenomena_create_post(
             session_authority: session_authority_pda,
             user_account: user_account_pda,
             post: post_pda,
+            parent_post: parent_post_pda,
+            post_pot_authority: post_pot_authority_pda,
+            pump_share_mint,
+            smack_share_mint,
+            token_program: spl_token::ID,
             system_program: system_program::ID,
         })
         .args(opinions_market::instruction::CreatePost {
             post_id_hash: hash,
-            parent_post_pda,
+            curve,
+            vote_mode,
+            tokenized,
+            payout_weighting,
         })
         .instructions()
         .unwrap();
 
-    // Both payer and creator (user) must sign
-    let create_post_tx = send_tx(&rpc, create_post_ix, &payer.pubkey(), &[&payer])
-        .await
-        .unwrap();
+    // Naming the session key without its signature must not act for the creator
+    assert!(
+        send_tx(&rpc, create_post_ix.clone(), &payer.pubkey(), &[&payer])
+            .await
+            .is_err(),
+        "Create post should fail when neither the creator nor the session key signs"
+    );
+
+    // The session key signs in place of the creator
+    let create_post_ix = sign_as_session_key(create_post_ix, &session_key.pubkey());
+    let create_post_tx = send_tx(
+        &rpc,
+        create_post_ix,
+        &payer.pubkey(),
+        &[&payer, session_key],
+    )
+    .await
+    .unwrap();
     println!("create post tx: {:?}", create_post_tx);
 
     // Verify post was created and all fields are correct
@@ -442,6 +1234,30 @@ pub async fn test_phenomena_create_post(
         creator.pubkey(),
         "Post creator_user should match creator wallet"
     );
+    assert_eq!(post_account.curve, curve, "Post curve should match");
+    assert_eq!(
+        post_account.vote_mode, vote_mode,
+        "Post vote mode should match"
+    );
+    assert_eq!(
+        post_account.payout_weighting, payout_weighting,
+        "Post payout weighting should match"
+    );
+    assert_eq!(
+        post_account.tokenized(),
+        tokenized,
+        "Post should have share mints exactly when tokenized"
+    );
+    if tokenized {
+        assert_eq!(
+            post_account.share_mint(opinions_market::state::Side::Pump),
+            pump_share_mint.unwrap()
+        );
+        assert_eq!(
+            post_account.share_mint(opinions_market::state::Side::Smack),
+            smack_share_mint.unwrap()
+        );
+    }
 
     // Verify post_id_hash
     assert_eq!(
@@ -499,6 +1315,17 @@ pub async fn test_phenomena_create_post(
                     stored_parent, parent,
                     "Child post parent PDA should match provided parent"
                 );
+                let parent_account = opinions_market
+                    .account::<opinions_market::state::PostAccount>(parent)
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    Some(parent_account.child_count),
+                    parent_child_count_before.map(|count| count + 1),
+                    "Parent post should count the new child"
+                );
+                assert_eq!(post_account.depth, parent_account.depth + 1);
+                assert_eq!(post_account.ancestor(1), Some(parent));
                 println!("✅ Post type is Child with correct parent");
             } else {
                 panic!(
@@ -706,6 +1533,12 @@ pub async fn test_phenomena_vote_on_post(
     )
     .0;
 
+    let price_feed = opinions_market
+        .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+        .await
+        .unwrap()
+        .price_feed;
+
     let session_authority_pda = Pubkey::find_program_address(
         &[
             SESSION_AUTHORITY_SEED,
@@ -716,41 +1549,168 @@ pub async fn test_phenomena_vote_on_post(
     )
     .0;
 
-    let vote_ix = opinions_market
+    // Tokenized posts mint the votes as shares into the voter's own token account
+    let share_accounts = if post_account_before.tokenized() {
+        Some(
+            get_or_create_share_token_account(
+                &rpc,
+                payer,
+                &post_account_before,
+                side,
+                &voter.pubkey(),
+            )
+            .await,
+        )
+    } else {
+        None
+    };
+    let shares_before = match share_accounts {
+        Some((_, share_token_account)) => {
+            opinions_market
+                .account::<anchor_spl::token::TokenAccount>(share_token_account)
+                .await
+                .unwrap()
+                .amount
+        }
+        None => 0,
+    };
+
+    let vote_ix = |max_cost_in_token: u64| {
+        let ixs = opinions_market
+            .request()
+            .accounts(opinions_market::accounts::VoteOnPost {
+                config: *config_pda,
+                voter: voter.pubkey(),
+                payer: payer.pubkey(),
+                session_key: session_key.pubkey(),
+                session_authority: session_authority_pda,
+                post: *post_pda,
+                voter_user_account: voter_user_account_pda,
+                position: position_pda,
+                vault_authority: vault_authority_pda,
+                voter_user_vault_token_account: user_vault_token_account_pda,
+                post_pot_token_account: post_pot_token_account_pda,
+                post_pot_authority: post_pot_authority_pda,
+                protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
+                creator_vault_token_account: creator_vault_token_account_pda,
+                valid_payment: valid_payment_pda,
+                price_feed,
+                share_mint: share_accounts.map(|(share_mint, _)| share_mint),
+                voter_share_token_account: share_accounts
+                    .map(|(_, share_token_account)| share_token_account),
+                token_mint: *token_mint,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            })
+            .args(opinions_market::instruction::VoteOnPost {
+                side,
+                votes,
+                post_id_hash,
+                max_cost_in_token,
+            })
+            .instructions()
+            .unwrap();
+        sign_as_session_key(ixs, &session_key.pubkey())
+    };
+
+    // Quote the vote on-chain, from the same math vote_on_post charges with
+    let position = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .ok()
+        .map(|_| position_pda);
+    let quote_ix = opinions_market
         .request()
-        .accounts(opinions_market::accounts::VoteOnPost {
+        .accounts(opinions_market::accounts::QuoteVote {
             config: *config_pda,
             voter: voter.pubkey(),
-            payer: payer.pubkey(),
-            session_key: session_key.pubkey(),
-            session_authority: session_authority_pda,
             post: *post_pda,
             voter_user_account: voter_user_account_pda,
-            position: position_pda,
-            vault_authority: vault_authority_pda,
-            voter_user_vault_token_account: user_vault_token_account_pda,
-            post_pot_token_account: post_pot_token_account_pda,
-            post_pot_authority: post_pot_authority_pda,
-            protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
-            creator_vault_token_account: creator_vault_token_account_pda,
+            position,
             valid_payment: valid_payment_pda,
+            price_feed,
             token_mint: *token_mint,
-            token_program: spl_token::ID,
-            system_program: system_program::ID,
         })
-        .args(opinions_market::instruction::VoteOnPost {
+        .args(opinions_market::instruction::QuoteVote {
             side,
             votes,
-            post_id_hash,
+            _post_id_hash: post_id_hash,
         })
         .instructions()
         .unwrap();
+    let quote: opinions_market::state::VoteQuote =
+        simulate_return_data(&rpc, quote_ix, &payer.pubkey(), &[&payer])
+            .await
+            .unwrap();
+    println!("vote quote: {:?}", quote);
 
-    // Voter is the payer, so only voter needs to sign
-    let vote_tx = send_tx(&rpc, vote_ix, &payer.pubkey(), &[&payer])
+    // One token unit under the quote must trip the slippage guard
+    assert!(
+        send_tx(
+            &rpc,
+            vote_ix(quote.cost_in_token.saturating_sub(1)),
+            &payer.pubkey(),
+            &[&payer, session_key]
+        )
         .await
-        .unwrap();
-    println!("vote tx: {:?}", vote_tx);
+        .is_err(),
+        "Vote should fail when its cost exceeds max_cost_in_token"
+    );
+
+    let voter_vault_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+
+    // The session key signs in place of the voter
+    let vote_tx = send_tx(
+        &rpc,
+        vote_ix(quote.cost_in_token),
+        &payer.pubkey(),
+        &[&payer, session_key],
+    )
+    .await
+    .unwrap();
+    println!("vote tx: {:?}", vote_tx);
+
+    let voter_vault_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        voter_vault_before.amount - voter_vault_after.amount,
+        quote.cost_in_token,
+        "Vote should charge exactly what quote_vote returned"
+    );
+
+    // The BLING paid is recorded for stake-weighted payouts
+    let paid_bling_before = position_before
+        .as_ref()
+        .map_or(0, |pos| pos.paid_bling_on(side));
+    let paid_bling_after = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .unwrap()
+        .paid_bling_on(side);
+    assert_eq!(
+        paid_bling_after - paid_bling_before,
+        quote.cost_bling,
+        "Position should record the BLING the vote cost"
+    );
+
+    if let Some((_, share_token_account)) = share_accounts {
+        let shares_after = opinions_market
+            .account::<anchor_spl::token::TokenAccount>(share_token_account)
+            .await
+            .unwrap()
+            .amount;
+        assert_eq!(
+            shares_after - shares_before,
+            votes,
+            "One share should be minted per vote"
+        );
+        println!("✅ {} shares minted to the voter", votes);
+    }
 
     // Verify position was updated AFTER vote
     let position_after = opinions_market
@@ -957,47 +1917,36 @@ pub async fn test_phenomena_settle_post(
             opinions_market::state::PostType::Original => None,
         };
 
-        // Derive parent post pot accounts if this is a child post
-        let (parent_post_pot_token_account_pda, parent_post_pot_authority_pda) = if let Some(parent_pda) = parent_post_pda {
-            let parent_pot_token_account = Pubkey::find_program_address(
-                &[
-                    POST_POT_TOKEN_ACCOUNT_SEED,
-                    parent_pda.as_ref(),
-                    token_mint.as_ref(),
-                ],
-                &opinions_market.id(),
-            )
-            .0;
-
-            let parent_pot_authority = Pubkey::find_program_address(
-                &[POST_POT_AUTHORITY_SEED, parent_pda.as_ref()],
-                &opinions_market.id(),
-            )
-            .0;
+        // Ancestors above the parent, grandparent first, for the mother fees further up
+        let ancestor_accounts: Vec<AccountMeta> = post_account.ancestors[1..]
+            .iter()
+            .filter(|ancestor| **ancestor != Pubkey::default())
+            .map(|ancestor| AccountMeta::new_readonly(*ancestor, false))
+            .collect();
 
-            (Some(parent_pot_token_account), parent_pot_authority)
-        } else {
-            // Use post_pot_authority as fallback when there's no parent (struct requires non-optional)
-            (None, post_pot_authority_pda)
-        };
+        let creator_user_account_pda = Pubkey::find_program_address(
+            &[USER_ACCOUNT_SEED, post_account.creator_user.as_ref()],
+            &opinions_market.id(),
+        )
+        .0;
 
         let settle_ix = opinions_market
             .request()
             .accounts(opinions_market::accounts::SettlePost {
                 post: *post_pda,
+                creator_user_account: creator_user_account_pda,
                 post_pot_token_account: post_pot_token_account_pda,
                 post_pot_authority: post_pot_authority_pda,
                 post_mint_payout: post_mint_payout_pda,
                 protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
                 parent_post: parent_post_pda,
-                parent_post_pot_token_account: parent_post_pot_token_account_pda,
-                parent_post_pot_authority: parent_post_pot_authority_pda,
                 config: *config_pda,
                 token_mint: *token_mint,
                 payer: payer.pubkey(),
                 token_program: spl_token::ID,
                 system_program: system_program::ID,
             })
+            .accounts(ancestor_accounts)
             .args(opinions_market::instruction::SettlePost {
                 post_id_hash: post_id_hash,
             })
@@ -1075,6 +2024,7 @@ pub async fn test_phenomena_settle_post(
             let distribute_creator_ix = opinions_market
                 .request()
                 .accounts(opinions_market::accounts::DistributeCreatorReward {
+                    config: *config_pda,
                     payer: payer.pubkey(),
                     post: *post_pda,
                     post_pot_token_account: post_pot_token_account_pda,
@@ -1120,48 +2070,75 @@ pub async fn test_phenomena_settle_post(
             println!("  Added distribute_protocol_fee instruction");
         }
 
-        // 3. Distribute parent post share (if mother fee > 0 and it's a child post)
-        if payout_account.mother_fee > 0 && parent_post_pda.is_some() {
-            let parent_post_pda_unwrapped = parent_post_pda.unwrap();
-            
-            let parent_post_pot_token_account_pda = Pubkey::find_program_address(
+        // 3. Distribute the mother fee of every ancestor it was charged for, parent first
+        assert_eq!(
+            payout_account.mother_fee,
+            payout_account.mother_fees.iter().sum::<u64>()
+        );
+        for (i, mother_fee) in payout_account.mother_fees.iter().enumerate() {
+            if *mother_fee == 0 {
+                continue;
+            }
+            let level = i as u8 + 1;
+            let ancestor_pda = post_account.ancestors[i];
+            let ancestor_pot_token_account_pda = Pubkey::find_program_address(
                 &[
                     POST_POT_TOKEN_ACCOUNT_SEED,
-                    parent_post_pda_unwrapped.as_ref(),
+                    ancestor_pda.as_ref(),
                     token_mint.as_ref(),
                 ],
                 &opinions_market.id(),
             )
             .0;
 
-            let parent_post_pot_authority_pda = Pubkey::find_program_address(
-                &[POST_POT_AUTHORITY_SEED, parent_post_pda_unwrapped.as_ref()],
-                &opinions_market.id(),
-            )
-            .0;
-
-            let distribute_parent_ix = opinions_market
-                .request()
-                .accounts(opinions_market::accounts::DistributeParentPostShare {
-                    payer: payer.pubkey(),
-                    post: *post_pda,
-                    post_pot_token_account: post_pot_token_account_pda,
-                    post_pot_authority: post_pot_authority_pda,
-                    post_mint_payout: post_mint_payout_pda,
-                    parent_post: parent_post_pda,
-                    parent_post_pot_token_account: Some(parent_post_pot_token_account_pda),
-                    parent_post_pot_authority: Some(parent_post_pot_authority_pda),
-                    token_mint: *token_mint,
-                    token_program: spl_token::ID,
-                })
-                .args(opinions_market::instruction::DistributeParentPostShare {
-                    post_id_hash: post_id_hash,
-                })
-                .instructions()
-                .unwrap();
-
-            distribution_ixs.push(distribute_parent_ix);
-            println!("  Added distribute_parent_post_share instruction");
+            let distribute_ancestor_ix = if level == 1 {
+                opinions_market
+                    .request()
+                    .accounts(opinions_market::accounts::DistributeParentPostShare {
+                        config: *config_pda,
+                        payer: payer.pubkey(),
+                        post: *post_pda,
+                        post_pot_token_account: post_pot_token_account_pda,
+                        post_pot_authority: post_pot_authority_pda,
+                        post_mint_payout: post_mint_payout_pda,
+                        parent_post: ancestor_pda,
+                        parent_post_pot_token_account: ancestor_pot_token_account_pda,
+                        token_mint: *token_mint,
+                        token_program: spl_token::ID,
+                    })
+                    .args(opinions_market::instruction::DistributeParentPostShare {
+                        post_id_hash: post_id_hash,
+                    })
+                    .instructions()
+                    .unwrap()
+            } else {
+                opinions_market
+                    .request()
+                    .accounts(opinions_market::accounts::DistributeAncestorPostShare {
+                        config: *config_pda,
+                        payer: payer.pubkey(),
+                        post: *post_pda,
+                        post_pot_token_account: post_pot_token_account_pda,
+                        post_pot_authority: post_pot_authority_pda,
+                        post_mint_payout: post_mint_payout_pda,
+                        ancestor_post: ancestor_pda,
+                        ancestor_post_pot_token_account: ancestor_pot_token_account_pda,
+                        token_mint: *token_mint,
+                        token_program: spl_token::ID,
+                    })
+                    .args(opinions_market::instruction::DistributeAncestorPostShare {
+                        _post_id_hash: post_id_hash,
+                        level,
+                    })
+                    .instructions()
+                    .unwrap()
+            };
+
+            distribution_ixs.push(distribute_ancestor_ix);
+            println!(
+                "  Added level {} mother fee instruction for {}",
+                level, ancestor_pda
+            );
         }
 
         // Send all distribution instructions in one transaction
@@ -1234,11 +2211,18 @@ pub async fn test_phenomena_claim_post_reward(
             USER_POST_MINT_CLAIM_SEED,
             post_pda.as_ref(),
             token_mint.as_ref(),
+            user.pubkey().as_ref(),
         ],
         &opinions_market.id(),
     )
     .0;
 
+    let user_account_pda = Pubkey::find_program_address(
+        &[USER_ACCOUNT_SEED, user.pubkey().as_ref()],
+        &opinions_market.id(),
+    )
+    .0;
+
     let post_mint_payout_pda = Pubkey::find_program_address(
         &[
             POST_MINT_PAYOUT_SEED,
@@ -1275,6 +2259,9 @@ pub async fn test_phenomena_claim_post_reward(
     )
     .0;
 
+    let vault_authority_pda =
+        Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &opinions_market.id()).0;
+
     let session_authority_pda = Pubkey::find_program_address(
         &[
             SESSION_AUTHORITY_SEED,
@@ -1314,13 +2301,20 @@ pub async fn test_phenomena_claim_post_reward(
         .await
         .unwrap();
 
+    let social_score_before = opinions_market
+        .account::<opinions_market::state::UserAccount>(user_account_pda)
+        .await
+        .unwrap()
+        .social_score;
+
     // Check if already claimed
     let claim_before = opinions_market
         .account::<opinions_market::state::UserPostMintClaim>(user_post_mint_claim_pda)
         .await;
 
+    // Shares of a tokenized post can be claimed again by whoever holds them
     if let Ok(claim) = claim_before {
-        if claim.claimed {
+        if claim.claimed && !post_account.tokenized() {
             println!("⚠️  Reward already claimed for this token mint, skipping...");
             return;
         }
@@ -1328,24 +2322,40 @@ pub async fn test_phenomena_claim_post_reward(
 
     // Determine expected reward
     let winning_side = post_account.winning_side.unwrap();
-    let user_votes = match winning_side {
-        opinions_market::state::Side::Pump => position.upvotes as u64,
-        opinions_market::state::Side::Smack => position.downvotes as u64,
-    };
 
-    let expected_reward = if user_votes == 0 {
-        0
+    // On tokenized posts the user's shares are the votes, and the claim burns them
+    let share_accounts = if post_account.tokenized() {
+        Some(
+            get_or_create_share_token_account(
+                &rpc,
+                payer,
+                &post_account,
+                winning_side,
+                &user.pubkey(),
+            )
+            .await,
+        )
     } else {
-        let scaled = user_votes
-            .checked_mul(post_mint_payout.payout_per_winning_vote)
-            .unwrap();
-        scaled
-            .checked_div(opinions_market::constants::PRECISION)
-            .unwrap()
+        None
+    };
+    let user_votes = match share_accounts {
+        Some((_, share_token_account)) => {
+            opinions_market
+                .account::<anchor_spl::token::TokenAccount>(share_token_account)
+                .await
+                .unwrap()
+                .amount
+        }
+        None => position.votes_on(winning_side),
     };
 
+    let expected_reward = post_mint_payout
+        .reward_for(user_votes, position.paid_bling_on(winning_side))
+        .unwrap();
+
     println!("📊 Claim details:");
     println!("   - Winning side: {:?}", winning_side);
+    println!("   - Payout policy: {:?}", post_mint_payout.policy);
     println!("   - User votes on winning side: {}", user_votes);
     println!(
         "   - Payout per winning vote: {}",
@@ -1356,34 +2366,97 @@ pub async fn test_phenomena_claim_post_reward(
     println!("   - User vault before: {}", user_vault_before.amount);
 
     // Call claim_post_reward instruction
-    let claim_ix = opinions_market
-        .request()
-        .accounts(opinions_market::accounts::ClaimPostReward {
-            config: *config_pda,
-            user: user.pubkey(),
-            payer: payer.pubkey(),
-            session_key: session_key.pubkey(),
-            session_authority: session_authority_pda,
-            post: *post_pda,
-            position: position_pda,
-            user_post_mint_claim: user_post_mint_claim_pda,
-            post_mint_payout: post_mint_payout_pda,
-            post_pot_token_account: post_pot_token_account_pda,
-            post_pot_authority: post_pot_authority_pda,
-            user_vault_token_account: user_vault_token_account_pda,
-            token_mint: *token_mint,
-            token_program: spl_token::ID,
-            system_program: system_program::ID,
-        })
-        .args(opinions_market::instruction::ClaimPostReward { post_id_hash })
-        .instructions()
-        .unwrap();
+    let claim_ix = |user_vault_token_account: Pubkey| {
+        let ixs = opinions_market
+            .request()
+            .accounts(opinions_market::accounts::ClaimPostReward {
+                config: *config_pda,
+                user: user.pubkey(),
+                payer: payer.pubkey(),
+                session_key: session_key.pubkey(),
+                session_authority: session_authority_pda,
+                post: *post_pda,
+                user_account: user_account_pda,
+                position: position_pda,
+                user_post_mint_claim: user_post_mint_claim_pda,
+                post_mint_payout: post_mint_payout_pda,
+                post_pot_token_account: post_pot_token_account_pda,
+                post_pot_authority: post_pot_authority_pda,
+                vault_authority: vault_authority_pda,
+                user_vault_token_account,
+                share_mint: share_accounts.map(|(share_mint, _)| share_mint),
+                user_share_token_account: share_accounts
+                    .map(|(_, share_token_account)| share_token_account),
+                token_mint: *token_mint,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            })
+            .args(opinions_market::instruction::ClaimPostReward { post_id_hash })
+            .instructions()
+            .unwrap();
+        sign_as_session_key(ixs, &session_key.pubkey())
+    };
+    // Burning shares needs the holder's own signature
+    let claim_signers: Vec<&Keypair> = if share_accounts.is_some() {
+        vec![payer, user, session_key]
+    } else {
+        vec![payer, session_key]
+    };
 
-    let claim_tx = send_tx(&rpc, claim_ix, &payer.pubkey(), &[&payer])
+    // The session key can't send the reward to a token account of its own
+    let foreign_vault = spl_associated_token_account::get_associated_token_address(
+        &session_key.pubkey(),
+        token_mint,
+    );
+    let create_foreign_vault_ix = create_associated_token_account_idempotent(
+        &payer.pubkey(),
+        &session_key.pubkey(),
+        token_mint,
+        &spl_token::ID,
+    );
+    send_tx(
+        &rpc,
+        vec![create_foreign_vault_ix],
+        &payer.pubkey(),
+        &[&payer],
+    )
+    .await
+    .unwrap();
+    assert!(
+        send_tx(
+            &rpc,
+            claim_ix(foreign_vault),
+            &payer.pubkey(),
+            &claim_signers
+        )
         .await
-        .unwrap();
+        .is_err(),
+        "Claim should fail when paid to a vault that isn't the user's"
+    );
+
+    let claim_tx = send_tx(
+        &rpc,
+        claim_ix(user_vault_token_account_pda),
+        &payer.pubkey(),
+        &claim_signers,
+    )
+    .await
+    .unwrap();
     println!("claim post reward tx: {:?}", claim_tx);
 
+    if let Some((_, share_token_account)) = share_accounts {
+        let shares_after = opinions_market
+            .account::<anchor_spl::token::TokenAccount>(share_token_account)
+            .await
+            .unwrap()
+            .amount;
+        assert_eq!(
+            shares_after, 0,
+            "Claim should burn all of the user's shares"
+        );
+        println!("✅ {} shares burnt", user_votes);
+    }
+
     // Verify claim was successful
     let user_post_mint_claim = opinions_market
         .account::<opinions_market::state::UserPostMintClaim>(user_post_mint_claim_pda)
@@ -1395,6 +2468,27 @@ pub async fn test_phenomena_claim_post_reward(
         "Claim should be marked as claimed"
     );
 
+    // Social score moves on the first claim for this post only
+    let expected_score_delta = if position.score_applied {
+        0
+    } else {
+        position.social_score_delta(winning_side)
+    };
+    let social_score_after = opinions_market
+        .account::<opinions_market::state::UserAccount>(user_account_pda)
+        .await
+        .unwrap()
+        .social_score;
+    assert_eq!(
+        social_score_after,
+        social_score_before + expected_score_delta,
+        "Social score should move by the position's delta"
+    );
+    println!(
+        "✅ Social score: {} → {}",
+        social_score_before, social_score_after
+    );
+
     // Verify balances changed correctly
     let post_pot_after = opinions_market
         .account::<anchor_spl::token::TokenAccount>(post_pot_token_account_pda)
@@ -1429,3 +2523,1435 @@ pub async fn test_phenomena_claim_post_reward(
 
     println!("✅ Post reward claimed successfully");
 }
+
+/// Anyone (here the payer) cranks a user's claims in one mint across several settled posts
+pub async fn test_phenomena_claim_post_rewards(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    user: &Pubkey,
+    post_pdas: &[Pubkey],
+    token_mint: &Pubkey,
+    tokens: &HashMap<Pubkey, String>,
+) {
+    let token_name = tokens.get(token_mint).unwrap();
+    println!(
+        "Claiming {} rewards for user {:?} across {} posts",
+        token_name,
+        user,
+        post_pdas.len()
+    );
+
+    let program_id = opinions_market.id();
+    let user_account_pda =
+        Pubkey::find_program_address(&[USER_ACCOUNT_SEED, user.as_ref()], &program_id).0;
+    let user_vault_token_account_pda = Pubkey::find_program_address(
+        &[
+            USER_VAULT_TOKEN_ACCOUNT_SEED,
+            user.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+
+    let mut remaining_accounts = Vec::new();
+    let mut claim_pdas = Vec::new();
+    let mut expected_reward = 0u64;
+
+    for post_pda in post_pdas {
+        let post_account = opinions_market
+            .account::<opinions_market::state::PostAccount>(*post_pda)
+            .await
+            .unwrap();
+        let winning_side = post_account.winning_side.unwrap();
+
+        let position_pda = Pubkey::find_program_address(
+            &[POSITION_SEED, post_pda.as_ref(), user.as_ref()],
+            &program_id,
+        )
+        .0;
+        let post_mint_payout_pda = Pubkey::find_program_address(
+            &[
+                POST_MINT_PAYOUT_SEED,
+                post_pda.as_ref(),
+                token_mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let post_pot_token_account_pda = Pubkey::find_program_address(
+            &[
+                POST_POT_TOKEN_ACCOUNT_SEED,
+                post_pda.as_ref(),
+                token_mint.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+        let post_pot_authority_pda = Pubkey::find_program_address(
+            &[POST_POT_AUTHORITY_SEED, post_pda.as_ref()],
+            &program_id,
+        )
+        .0;
+        let user_post_mint_claim_pda = Pubkey::find_program_address(
+            &[
+                USER_POST_MINT_CLAIM_SEED,
+                post_pda.as_ref(),
+                token_mint.as_ref(),
+                user.as_ref(),
+            ],
+            &program_id,
+        )
+        .0;
+
+        // Already claimed posts are skipped on-chain, the position keeps track
+        let position = opinions_market
+            .account::<opinions_market::state::UserPostPosition>(position_pda)
+            .await
+            .unwrap();
+        let already_claimed =
+            position.claimed_pot_mints & post_account.pot_mint_bit(token_mint).unwrap() != 0;
+        if !already_claimed {
+            let post_mint_payout = opinions_market
+                .account::<opinions_market::state::PostMintPayout>(post_mint_payout_pda)
+                .await
+                .unwrap();
+            expected_reward += post_mint_payout
+                .reward_for(
+                    position.votes_on(winning_side),
+                    position.paid_bling_on(winning_side),
+                )
+                .unwrap();
+        }
+
+        remaining_accounts.extend([
+            AccountMeta::new_readonly(*post_pda, false),
+            AccountMeta::new(position_pda, false),
+            AccountMeta::new_readonly(post_mint_payout_pda, false),
+            AccountMeta::new(post_pot_token_account_pda, false),
+            AccountMeta::new_readonly(post_pot_authority_pda, false),
+            AccountMeta::new(user_post_mint_claim_pda, false),
+        ]);
+        claim_pdas.push(user_post_mint_claim_pda);
+    }
+
+    let user_vault_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+
+    let claim_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::ClaimPostRewards {
+            config: Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0,
+            payer: payer.pubkey(),
+            user: *user,
+            user_account: user_account_pda,
+            user_vault_token_account: user_vault_token_account_pda,
+            token_mint: *token_mint,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        })
+        .accounts(remaining_accounts)
+        .args(opinions_market::instruction::ClaimPostRewards {})
+        .instructions()
+        .unwrap();
+
+    let claim_tx = send_tx(&rpc, claim_ix, &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("claim post rewards tx: {:?}", claim_tx);
+
+    for claim_pda in claim_pdas {
+        let claim = opinions_market
+            .account::<opinions_market::state::UserPostMintClaim>(claim_pda)
+            .await
+            .unwrap();
+        assert!(claim.claimed, "Every claim in the batch should be marked");
+    }
+
+    let user_vault_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        user_vault_after.amount,
+        user_vault_before.amount + expected_reward,
+        "User vault should increase by the sum of the batch's rewards"
+    );
+    println!("✅ Batch claimed {} {}", expected_reward, token_name);
+}
+
+/// Waits out the claim window, then sweeps a post's pot for one mint and closes the
+/// pot, payout and the given users' claim accounts
+pub async fn test_phenomena_sweep_post_pot(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    post_pda: &Pubkey,
+    token_mint: &Pubkey,
+    claimants: &[Pubkey],
+    tokens: &HashMap<Pubkey, String>,
+    config_pda: &Pubkey,
+) {
+    let token_name = tokens.get(token_mint).unwrap();
+    println!("Sweeping post {:?} pot for token {}", post_pda, token_name);
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let post_mint_payout_pda = Pubkey::find_program_address(
+        &[
+            POST_MINT_PAYOUT_SEED,
+            post_pda.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let post_pot_token_account_pda = Pubkey::find_program_address(
+        &[
+            POST_POT_TOKEN_ACCOUNT_SEED,
+            post_pda.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let post_pot_authority_pda =
+        Pubkey::find_program_address(&[POST_POT_AUTHORITY_SEED, post_pda.as_ref()], &program_id).0;
+    let protocol_treasury_token_account_pda = Pubkey::find_program_address(
+        &[PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.as_ref()],
+        &program_id,
+    )
+    .0;
+    let claim_pdas: Vec<Pubkey> = claimants
+        .iter()
+        .map(|user| {
+            Pubkey::find_program_address(
+                &[
+                    USER_POST_MINT_CLAIM_SEED,
+                    post_pda.as_ref(),
+                    token_mint.as_ref(),
+                    user.as_ref(),
+                ],
+                &program_id,
+            )
+            .0
+        })
+        .collect();
+
+    // Claims stay open until the deadline recorded at settlement
+    let payout = opinions_market
+        .account::<opinions_market::state::PostMintPayout>(post_mint_payout_pda)
+        .await
+        .unwrap();
+    assert!(
+        payout.fees_distributed(),
+        "Settlement fees should be distributed before the sweep"
+    );
+    let claim_deadline = payout.claim_deadline;
+    let now = current_chain_timestamp(rpc).await;
+    if claim_deadline > now {
+        wait_for_seconds((claim_deadline - now) as u64 + 1).await;
+    }
+
+    // Each claim refunds whoever paid for it
+    let mut claim_metas = Vec::new();
+    for claim_pda in &claim_pdas {
+        let claim = opinions_market
+            .account::<opinions_market::state::UserPostMintClaim>(*claim_pda)
+            .await
+            .unwrap();
+        claim_metas.push(AccountMeta::new(*claim_pda, false));
+        claim_metas.push(AccountMeta::new(claim.rent_payer, false));
+    }
+
+    let pot_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(post_pot_token_account_pda)
+        .await
+        .unwrap();
+    // Rent goes back to whoever funded the payout, even after a payer rotation
+    let payout_rent_payer_before = rpc.get_balance(&payout.rent_payer).await.unwrap();
+    let treasury_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(protocol_treasury_token_account_pda)
+        .await
+        .unwrap();
+
+    let sweep_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::SweepPostPot {
+            config: *config_pda,
+            payer: payer.pubkey(),
+            post: *post_pda,
+            post_mint_payout: post_mint_payout_pda,
+            post_pot_token_account: post_pot_token_account_pda,
+            post_pot_authority: post_pot_authority_pda,
+            protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
+            pot_rent_payer: post_account.pot_rent_payer(token_mint).unwrap(),
+            payout_rent_payer: payout.rent_payer,
+            token_mint: *token_mint,
+            token_program: spl_token::ID,
+        })
+        .accounts(claim_metas)
+        .args(opinions_market::instruction::SweepPostPot {
+            _post_id_hash: post_account.post_id_hash,
+        })
+        .instructions()
+        .unwrap();
+
+    let sweep_tx = send_tx(&rpc, sweep_ix, &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("sweep post pot tx: {:?}", sweep_tx);
+
+    let treasury_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(protocol_treasury_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        treasury_after.amount,
+        treasury_before.amount + pot_before.amount,
+        "Treasury should receive everything left in the pot"
+    );
+
+    assert!(
+        rpc.get_balance(&payout.rent_payer).await.unwrap() > payout_rent_payer_before,
+        "Payout rent should go back to the account that funded it"
+    );
+
+    for closed in [post_pot_token_account_pda, post_mint_payout_pda]
+        .iter()
+        .chain(claim_pdas.iter())
+    {
+        assert!(
+            rpc.get_account(closed).await.is_err(),
+            "Account {} should be closed",
+            closed
+        );
+    }
+    println!(
+        "✅ Swept {} {} to the treasury and closed the pot",
+        pot_before.amount, token_name
+    );
+}
+
+/// Closes a user's claimed UserPostMintClaim and checks the rent went back to its funder
+pub async fn test_phenomena_close_user_post_mint_claim(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    user: &Pubkey,
+    post_pda: &Pubkey,
+    token_mint: &Pubkey,
+) {
+    println!("Closing claim of user {:?} on post {:?}", user, post_pda);
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let user_post_mint_claim_pda = Pubkey::find_program_address(
+        &[
+            USER_POST_MINT_CLAIM_SEED,
+            post_pda.as_ref(),
+            token_mint.as_ref(),
+            user.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let claim = opinions_market
+        .account::<opinions_market::state::UserPostMintClaim>(user_post_mint_claim_pda)
+        .await
+        .unwrap();
+    assert!(claim.claimed, "Only made claims can be closed");
+
+    let claim_rent = rpc.get_balance(&user_post_mint_claim_pda).await.unwrap();
+    let rent_payer_before = rpc.get_balance(&claim.rent_payer).await.unwrap();
+
+    let close_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::CloseUserPostMintClaim {
+            config: Pubkey::find_program_address(&[CONFIG_SEED], &program_id).0,
+            payer: payer.pubkey(),
+            user: *user,
+            post: *post_pda,
+            user_post_mint_claim: user_post_mint_claim_pda,
+            rent_payer: claim.rent_payer,
+            token_mint: *token_mint,
+        })
+        .args(opinions_market::instruction::CloseUserPostMintClaim {
+            _post_id_hash: post_account.post_id_hash,
+        })
+        .instructions()
+        .unwrap();
+
+    let close_tx = send_tx(&rpc, close_ix, &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("close user post mint claim tx: {:?}", close_tx);
+
+    assert!(
+        rpc.get_account(&user_post_mint_claim_pda).await.is_err(),
+        "Claim account should be closed"
+    );
+    let rent_payer_after = rpc.get_balance(&claim.rent_payer).await.unwrap();
+    // The rent payer may also have paid the tx fee
+    assert!(
+        rent_payer_after + 10_000 >= rent_payer_before + claim_rent,
+        "Rent payer should get the claim's rent back"
+    );
+    println!("✅ Closed claim, {} lamports refunded", claim_rent);
+}
+
+/// Closes a user's position on a fully settled and claimed post and checks the rent went
+/// back to its funder
+pub async fn test_phenomena_close_user_post_position(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    user: &Pubkey,
+    post_pda: &Pubkey,
+) {
+    println!("Closing position of user {:?} on post {:?}", user, post_pda);
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let position_pda = Pubkey::find_program_address(
+        &[POSITION_SEED, post_pda.as_ref(), user.as_ref()],
+        &program_id,
+    )
+    .0;
+    let position = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .unwrap();
+    assert!(
+        position.fully_claimed(&post_account),
+        "Position should have nothing left to claim"
+    );
+
+    let user_account_pda =
+        Pubkey::find_program_address(&[USER_ACCOUNT_SEED, user.as_ref()], &program_id).0;
+    let social_score_before = opinions_market
+        .account::<opinions_market::state::UserAccount>(user_account_pda)
+        .await
+        .unwrap()
+        .social_score;
+
+    let position_rent = rpc.get_balance(&position_pda).await.unwrap();
+    let rent_payer_before = rpc.get_balance(&position.rent_payer).await.unwrap();
+
+    let close_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::CloseUserPostPosition {
+            config: Pubkey::find_program_address(&[CONFIG_SEED], &program_id).0,
+            payer: payer.pubkey(),
+            user: *user,
+            post: *post_pda,
+            user_account: user_account_pda,
+            position: position_pda,
+            rent_payer: position.rent_payer,
+        })
+        .args(opinions_market::instruction::CloseUserPostPosition {
+            _post_id_hash: post_account.post_id_hash,
+        })
+        .instructions()
+        .unwrap();
+
+    let close_tx = send_tx(&rpc, close_ix, &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("close user post position tx: {:?}", close_tx);
+
+    assert!(
+        rpc.get_account(&position_pda).await.is_err(),
+        "Position account should be closed"
+    );
+
+    // A position that never claimed moves the social score when it is closed
+    let expected_score_delta = match (post_account.winning_side, position.score_applied) {
+        (Some(winning_side), false)
+            if post_account.state == opinions_market::state::PostState::Settled =>
+        {
+            position.social_score_delta(winning_side)
+        }
+        _ => 0,
+    };
+    let social_score_after = opinions_market
+        .account::<opinions_market::state::UserAccount>(user_account_pda)
+        .await
+        .unwrap()
+        .social_score;
+    assert_eq!(
+        social_score_after,
+        social_score_before + expected_score_delta,
+        "Social score should move once per post"
+    );
+
+    let rent_payer_after = rpc.get_balance(&position.rent_payer).await.unwrap();
+    // The rent payer may also have paid the tx fee
+    assert!(
+        rent_payer_after + 10_000 >= rent_payer_before + position_rent,
+        "Rent payer should get the position's rent back"
+    );
+    println!("✅ Closed position, {} lamports refunded", position_rent);
+}
+
+pub async fn test_phenomena_commit_vote(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    voter: &Keypair,
+    session_key: &Keypair,
+    post_pda: &Pubkey,
+    side: opinions_market::state::Side,
+    votes: u64,
+    salt: [u8; 32],
+    escrow: u64,
+    token_mint: &Pubkey,
+    config_pda: &Pubkey,
+) {
+    println!(
+        "{:} commits a hidden vote on post {:} with {} escrowed",
+        voter.pubkey(),
+        post_pda,
+        escrow
+    );
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        post_account.vote_mode,
+        opinions_market::state::VoteMode::CommitReveal,
+        "Only commit–reveal posts take commitments"
+    );
+
+    let user_vault_token_account_pda = Pubkey::find_program_address(
+        &[
+            USER_VAULT_TOKEN_ACCOUNT_SEED,
+            voter.pubkey().as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let post_pot_token_account_pda = Pubkey::find_program_address(
+        &[
+            POST_POT_TOKEN_ACCOUNT_SEED,
+            post_pda.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let vote_commitment_pda = Pubkey::find_program_address(
+        &[
+            VOTE_COMMITMENT_SEED,
+            post_pda.as_ref(),
+            voter.pubkey().as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let valid_payment_pda =
+        Pubkey::find_program_address(&[VALID_PAYMENT_SEED, token_mint.as_ref()], &program_id).0;
+    let price_feed = opinions_market
+        .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+        .await
+        .unwrap()
+        .price_feed;
+
+    // Hidden votes have no open curve to quote
+    let quote_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::QuoteVote {
+            config: *config_pda,
+            voter: voter.pubkey(),
+            post: *post_pda,
+            voter_user_account: Pubkey::find_program_address(
+                &[USER_ACCOUNT_SEED, voter.pubkey().as_ref()],
+                &program_id,
+            )
+            .0,
+            position: None,
+            valid_payment: valid_payment_pda,
+            price_feed,
+            token_mint: *token_mint,
+        })
+        .args(opinions_market::instruction::QuoteVote {
+            side,
+            votes,
+            _post_id_hash: post_account.post_id_hash,
+        })
+        .instructions()
+        .unwrap();
+    assert!(
+        simulate_return_data::<opinions_market::state::VoteQuote, _>(
+            &rpc,
+            quote_ix,
+            &payer.pubkey(),
+            &[&payer]
+        )
+        .await
+        .is_err(),
+        "Commit–reveal posts should not be quoted"
+    );
+
+    let commitment =
+        opinions_market::state::VoteCommitment::hash(&voter.pubkey(), side, votes, &salt);
+
+    let commit_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::CommitVote {
+            config: *config_pda,
+            voter: voter.pubkey(),
+            payer: payer.pubkey(),
+            session_key: session_key.pubkey(),
+            session_authority: Pubkey::find_program_address(
+                &[
+                    SESSION_AUTHORITY_SEED,
+                    voter.pubkey().as_ref(),
+                    session_key.pubkey().as_ref(),
+                ],
+                &program_id,
+            )
+            .0,
+            post: *post_pda,
+            vote_commitment: vote_commitment_pda,
+            voter_user_vault_token_account: user_vault_token_account_pda,
+            vault_authority: Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &program_id).0,
+            post_pot_token_account: post_pot_token_account_pda,
+            post_pot_authority: Pubkey::find_program_address(
+                &[POST_POT_AUTHORITY_SEED, post_pda.as_ref()],
+                &program_id,
+            )
+            .0,
+            valid_payment: valid_payment_pda,
+            price_feed,
+            token_mint: *token_mint,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        })
+        .args(opinions_market::instruction::CommitVote {
+            _post_id_hash: post_account.post_id_hash,
+            commitment,
+            escrow,
+        })
+        .instructions()
+        .unwrap();
+    let commit_ix = sign_as_session_key(commit_ix, &session_key.pubkey());
+
+    let voter_vault_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+
+    let commit_tx = send_tx(&rpc, commit_ix, &payer.pubkey(), &[&payer, session_key])
+        .await
+        .unwrap();
+    println!("commit vote tx: {:?}", commit_tx);
+
+    let voter_vault_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        voter_vault_before.amount - voter_vault_after.amount,
+        escrow,
+        "The escrow should leave the voter's vault"
+    );
+
+    let vote_commitment = opinions_market
+        .account::<opinions_market::state::VoteCommitment>(vote_commitment_pda)
+        .await
+        .unwrap();
+    assert_eq!(vote_commitment.commitment, commitment);
+    assert_eq!(vote_commitment.escrow, escrow);
+
+    // Nothing is counted until the reveal
+    let post_account_after = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    assert_eq!(post_account_after.upvotes, post_account.upvotes);
+    assert_eq!(post_account_after.downvotes, post_account.downvotes);
+    assert_eq!(post_account_after.end_time, post_account.end_time);
+    println!("✅ Vote committed, post counts unchanged");
+}
+
+pub async fn test_phenomena_reveal_vote(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    voter: &Pubkey,
+    post_pda: &Pubkey,
+    side: opinions_market::state::Side,
+    votes: u64,
+    salt: [u8; 32],
+    token_mint: &Pubkey,
+    config_pda: &Pubkey,
+) {
+    println!("Revealing {:?}'s vote on post {:?}", voter, post_pda);
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+
+    // The reveal window opens at end_time
+    let now = current_chain_timestamp(rpc).await;
+    if post_account.end_time > now {
+        wait_for_seconds((post_account.end_time - now) as u64).await;
+    }
+
+    let vote_commitment_pda = Pubkey::find_program_address(
+        &[VOTE_COMMITMENT_SEED, post_pda.as_ref(), voter.as_ref()],
+        &program_id,
+    )
+    .0;
+    let vote_commitment = opinions_market
+        .account::<opinions_market::state::VoteCommitment>(vote_commitment_pda)
+        .await
+        .unwrap();
+    let user_vault_token_account_pda = Pubkey::find_program_address(
+        &[
+            USER_VAULT_TOKEN_ACCOUNT_SEED,
+            voter.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let position_pda = Pubkey::find_program_address(
+        &[POSITION_SEED, post_pda.as_ref(), voter.as_ref()],
+        &program_id,
+    )
+    .0;
+    let valid_payment_pda =
+        Pubkey::find_program_address(&[VALID_PAYMENT_SEED, token_mint.as_ref()], &program_id).0;
+    let price_feed = opinions_market
+        .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+        .await
+        .unwrap()
+        .price_feed;
+
+    let reveal_ix = |side: opinions_market::state::Side| {
+        opinions_market
+            .request()
+            .accounts(opinions_market::accounts::RevealVote {
+                config: *config_pda,
+                voter: *voter,
+                payer: payer.pubkey(),
+                post: *post_pda,
+                vote_commitment: vote_commitment_pda,
+                rent_payer: vote_commitment.rent_payer,
+                voter_user_account: Pubkey::find_program_address(
+                    &[USER_ACCOUNT_SEED, voter.as_ref()],
+                    &program_id,
+                )
+                .0,
+                position: position_pda,
+                voter_user_vault_token_account: user_vault_token_account_pda,
+                vault_authority: Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &program_id)
+                    .0,
+                post_pot_token_account: Pubkey::find_program_address(
+                    &[
+                        POST_POT_TOKEN_ACCOUNT_SEED,
+                        post_pda.as_ref(),
+                        token_mint.as_ref(),
+                    ],
+                    &program_id,
+                )
+                .0,
+                post_pot_authority: Pubkey::find_program_address(
+                    &[POST_POT_AUTHORITY_SEED, post_pda.as_ref()],
+                    &program_id,
+                )
+                .0,
+                protocol_token_treasury_token_account: Pubkey::find_program_address(
+                    &[PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.as_ref()],
+                    &program_id,
+                )
+                .0,
+                creator_vault_token_account: Pubkey::find_program_address(
+                    &[
+                        USER_VAULT_TOKEN_ACCOUNT_SEED,
+                        post_account.creator_user.as_ref(),
+                        token_mint.as_ref(),
+                    ],
+                    &program_id,
+                )
+                .0,
+                valid_payment: valid_payment_pda,
+                price_feed,
+                token_mint: *token_mint,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            })
+            .args(opinions_market::instruction::RevealVote {
+                _post_id_hash: post_account.post_id_hash,
+                side,
+                votes,
+                salt,
+            })
+            .instructions()
+            .unwrap()
+    };
+
+    // A reveal that doesn't match the commitment is rejected
+    assert!(
+        send_tx(&rpc, reveal_ix(side.opposite()), &payer.pubkey(), &[&payer])
+            .await
+            .is_err(),
+        "Reveal with the wrong side should fail"
+    );
+
+    let voter_vault_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+
+    let reveal_tx = send_tx(&rpc, reveal_ix(side), &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("reveal vote tx: {:?}", reveal_tx);
+
+    let voter_vault_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+    let refund = voter_vault_after.amount - voter_vault_before.amount;
+    assert!(
+        refund < vote_commitment.escrow,
+        "The vote should be paid out of the escrow"
+    );
+
+    let post_account_after = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let position = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .unwrap();
+    match side {
+        opinions_market::state::Side::Pump => {
+            assert_eq!(post_account_after.upvotes, post_account.upvotes + votes);
+            assert_eq!(position.upvotes, votes);
+        }
+        opinions_market::state::Side::Smack => {
+            assert_eq!(post_account_after.downvotes, post_account.downvotes + votes);
+            assert_eq!(position.downvotes, votes);
+        }
+    }
+    assert!(
+        rpc.get_account(&vote_commitment_pda).await.is_err(),
+        "Commitment account should be closed"
+    );
+    println!("✅ Vote revealed and counted, {} refunded", refund);
+}
+
+pub async fn test_phenomena_close_vote_commitment(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    user: &Pubkey,
+    post_pda: &Pubkey,
+) {
+    println!(
+        "Closing unrevealed commitment of user {:?} on post {:?}",
+        user, post_pda
+    );
+
+    // wait for the reveal window to close
+    wait_for_post_to_expire(rpc, opinions_market, post_pda).await;
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let vote_commitment_pda = Pubkey::find_program_address(
+        &[VOTE_COMMITMENT_SEED, post_pda.as_ref(), user.as_ref()],
+        &program_id,
+    )
+    .0;
+    let vote_commitment = opinions_market
+        .account::<opinions_market::state::VoteCommitment>(vote_commitment_pda)
+        .await
+        .unwrap();
+    let post_pot_token_account_pda = Pubkey::find_program_address(
+        &[
+            POST_POT_TOKEN_ACCOUNT_SEED,
+            post_pda.as_ref(),
+            vote_commitment.token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let pot_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(post_pot_token_account_pda)
+        .await
+        .unwrap();
+
+    let close_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::CloseVoteCommitment {
+            config: Pubkey::find_program_address(&[CONFIG_SEED], &program_id).0,
+            payer: payer.pubkey(),
+            user: *user,
+            post: *post_pda,
+            vote_commitment: vote_commitment_pda,
+            rent_payer: vote_commitment.rent_payer,
+        })
+        .args(opinions_market::instruction::CloseVoteCommitment {
+            _post_id_hash: post_account.post_id_hash,
+        })
+        .instructions()
+        .unwrap();
+
+    let close_tx = send_tx(&rpc, close_ix, &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("close vote commitment tx: {:?}", close_tx);
+
+    assert!(
+        rpc.get_account(&vote_commitment_pda).await.is_err(),
+        "Commitment account should be closed"
+    );
+    let pot_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(post_pot_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        pot_after.amount, pot_before.amount,
+        "The forfeited escrow stays in the pot"
+    );
+    println!(
+        "✅ Unrevealed commitment closed, {} forfeited to the pot",
+        vote_commitment.escrow
+    );
+}
+
+pub async fn test_phenomena_exit_position(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    voter: &Keypair,
+    session_key: &Keypair,
+    post_pda: &Pubkey,
+    side: opinions_market::state::Side,
+    votes: u64,
+    token_mint: &Pubkey,
+    config_pda: &Pubkey,
+) {
+    println!(
+        "{:} sells {} {:?} votes back to post {:}",
+        voter.pubkey(),
+        votes,
+        side,
+        post_pda
+    );
+
+    let program_id = opinions_market.id();
+    let post_account_before = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+
+    let user_vault_token_account_pda = Pubkey::find_program_address(
+        &[
+            USER_VAULT_TOKEN_ACCOUNT_SEED,
+            voter.pubkey().as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let post_pot_token_account_pda = Pubkey::find_program_address(
+        &[
+            POST_POT_TOKEN_ACCOUNT_SEED,
+            post_pda.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let position_pda = Pubkey::find_program_address(
+        &[POSITION_SEED, post_pda.as_ref(), voter.pubkey().as_ref()],
+        &program_id,
+    )
+    .0;
+    let valid_payment_pda =
+        Pubkey::find_program_address(&[VALID_PAYMENT_SEED, token_mint.as_ref()], &program_id).0;
+    let price_feed = opinions_market
+        .account::<opinions_market::state::ValidPayment>(valid_payment_pda)
+        .await
+        .unwrap()
+        .price_feed;
+    let position_before = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .unwrap();
+
+    let exit_ix = |min_refund_in_token: u64| {
+        let ixs = opinions_market
+            .request()
+            .accounts(opinions_market::accounts::ExitPosition {
+                config: *config_pda,
+                voter: voter.pubkey(),
+                payer: payer.pubkey(),
+                session_key: session_key.pubkey(),
+                session_authority: Pubkey::find_program_address(
+                    &[
+                        SESSION_AUTHORITY_SEED,
+                        voter.pubkey().as_ref(),
+                        session_key.pubkey().as_ref(),
+                    ],
+                    &program_id,
+                )
+                .0,
+                post: *post_pda,
+                voter_user_account: Pubkey::find_program_address(
+                    &[USER_ACCOUNT_SEED, voter.pubkey().as_ref()],
+                    &program_id,
+                )
+                .0,
+                position: position_pda,
+                voter_user_vault_token_account: user_vault_token_account_pda,
+                vault_authority: Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &program_id)
+                    .0,
+                post_pot_token_account: post_pot_token_account_pda,
+                post_pot_authority: Pubkey::find_program_address(
+                    &[POST_POT_AUTHORITY_SEED, post_pda.as_ref()],
+                    &program_id,
+                )
+                .0,
+                valid_payment: valid_payment_pda,
+                price_feed,
+                token_mint: *token_mint,
+                token_program: spl_token::ID,
+            })
+            .args(opinions_market::instruction::ExitPosition {
+                side,
+                votes,
+                _post_id_hash: post_account_before.post_id_hash,
+                min_refund_in_token,
+            })
+            .instructions()
+            .unwrap();
+        sign_as_session_key(ixs, &session_key.pubkey())
+    };
+
+    // An impossible minimum must trip the slippage guard
+    assert!(
+        send_tx(
+            &rpc,
+            exit_ix(u64::MAX),
+            &payer.pubkey(),
+            &[&payer, session_key]
+        )
+        .await
+        .is_err(),
+        "Exit should fail when the refund is below min_refund_in_token"
+    );
+
+    let voter_vault_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+    let pot_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(post_pot_token_account_pda)
+        .await
+        .unwrap();
+
+    let exit_tx = send_tx(&rpc, exit_ix(0), &payer.pubkey(), &[&payer, session_key])
+        .await
+        .unwrap();
+    println!("exit position tx: {:?}", exit_tx);
+
+    let voter_vault_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+    let pot_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(post_pot_token_account_pda)
+        .await
+        .unwrap();
+    let refund = voter_vault_after.amount - voter_vault_before.amount;
+    assert!(refund > 0, "Exit should refund something");
+    assert_eq!(
+        pot_before.amount - pot_after.amount,
+        refund,
+        "The refund comes out of the pot"
+    );
+
+    let post_account_after = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let position_after = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .unwrap();
+    match side {
+        opinions_market::state::Side::Pump => {
+            assert_eq!(
+                post_account_after.upvotes,
+                post_account_before.upvotes - votes
+            );
+            assert_eq!(position_after.upvotes, position_before.upvotes - votes);
+        }
+        opinions_market::state::Side::Smack => {
+            assert_eq!(
+                post_account_after.downvotes,
+                post_account_before.downvotes - votes
+            );
+            assert_eq!(position_after.downvotes, position_before.downvotes - votes);
+        }
+    }
+    println!("✅ Position exited, {} refunded from the pot", refund);
+}
+
+/// The admin takes a post down before it settles
+pub async fn test_phenomena_cancel_post(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    admin: &Keypair,
+    post_pda: &Pubkey,
+    config_pda: &Pubkey,
+) {
+    println!("Admin cancelling post {:?}", post_pda);
+
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+
+    let cancel_ix = |admin: Pubkey| {
+        opinions_market
+            .request()
+            .accounts(opinions_market::accounts::CancelPost {
+                config: *config_pda,
+                admin,
+                post: *post_pda,
+            })
+            .args(opinions_market::instruction::CancelPost {
+                _post_id_hash: post_account.post_id_hash,
+            })
+            .instructions()
+            .unwrap()
+    };
+
+    // Only the admin can cancel
+    assert!(
+        send_tx(&rpc, cancel_ix(payer.pubkey()), &payer.pubkey(), &[&payer])
+            .await
+            .is_err(),
+        "A non-admin should not be able to cancel a post"
+    );
+
+    let cancel_tx = send_tx(
+        &rpc,
+        cancel_ix(admin.pubkey()),
+        &payer.pubkey(),
+        &[&payer, &admin],
+    )
+    .await
+    .unwrap();
+    println!("cancel post tx: {:?}", cancel_tx);
+
+    let post_after = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        post_after.state,
+        opinions_market::state::PostState::Cancelled,
+        "Post should be cancelled"
+    );
+    assert!(
+        post_after.refund_deadline > current_chain_timestamp(rpc).await,
+        "Refunds should stay open for the claim window"
+    );
+    println!("✅ Post cancelled");
+}
+
+/// Anyone (here the payer) refunds a user's contribution to a cancelled post's pot in one mint
+pub async fn test_phenomena_refund_position(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    user: &Pubkey,
+    post_pda: &Pubkey,
+    token_mint: &Pubkey,
+    tokens: &HashMap<Pubkey, String>,
+) {
+    let token_name = tokens.get(token_mint).unwrap();
+    println!(
+        "Refunding {} contribution of user {:?} to cancelled post {:?}",
+        token_name, user, post_pda
+    );
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let position_pda = Pubkey::find_program_address(
+        &[POSITION_SEED, post_pda.as_ref(), user.as_ref()],
+        &program_id,
+    )
+    .0;
+    let post_pot_token_account_pda = Pubkey::find_program_address(
+        &[
+            POST_POT_TOKEN_ACCOUNT_SEED,
+            post_pda.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let post_pot_authority_pda =
+        Pubkey::find_program_address(&[POST_POT_AUTHORITY_SEED, post_pda.as_ref()], &program_id).0;
+    let user_vault_token_account_pda = Pubkey::find_program_address(
+        &[
+            USER_VAULT_TOKEN_ACCOUNT_SEED,
+            user.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+
+    let position = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .unwrap();
+    let index = post_account.pot_mint_index(token_mint).unwrap();
+    let contribution = position.pot_contributions[index];
+    assert!(contribution > 0, "User should have something to refund");
+
+    let user_vault_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+
+    let refund_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::RefundPosition {
+            config: Pubkey::find_program_address(&[CONFIG_SEED], &opinions_market.id()).0,
+            payer: payer.pubkey(),
+            user: *user,
+            post: *post_pda,
+            position: Some(position_pda),
+            post_pot_token_account: post_pot_token_account_pda,
+            post_pot_authority: post_pot_authority_pda,
+            user_vault_token_account: user_vault_token_account_pda,
+            share_mint: None,
+            user_share_token_account: None,
+            token_mint: *token_mint,
+            token_program: spl_token::ID,
+        })
+        .args(opinions_market::instruction::RefundPosition {
+            _post_id_hash: post_account.post_id_hash,
+        })
+        .instructions()
+        .unwrap();
+
+    let refund_tx = send_tx(&rpc, refund_ix, &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("refund position tx: {:?}", refund_tx);
+
+    let user_vault_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(user_vault_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        user_vault_after.amount - user_vault_before.amount,
+        contribution,
+        "User vault should get the pot contribution back"
+    );
+
+    // The contribution is cleared, a second refund has nothing to pay
+    let position_after = opinions_market
+        .account::<opinions_market::state::UserPostPosition>(position_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        position_after.pot_contributions[index], 0,
+        "Refunded contribution should be cleared"
+    );
+    let post_account_after = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        post_account_after.pot_contributions[index],
+        post_account.pot_contributions[index] - contribution,
+        "Refunded contribution should leave the post's total"
+    );
+    println!("✅ Refunded {} {}", contribution, token_name);
+}
+
+/// Waits out the refund deadline of a cancelled post, then sweeps what is left of its pot
+/// for one mint to the treasury and closes the pot
+pub async fn test_phenomena_sweep_cancelled_post_pot(
+    rpc: &RpcClient,
+    opinions_market: &Program<&Keypair>,
+    payer: &Keypair,
+    post_pda: &Pubkey,
+    token_mint: &Pubkey,
+    tokens: &HashMap<Pubkey, String>,
+    config_pda: &Pubkey,
+) {
+    let token_name = tokens.get(token_mint).unwrap();
+    println!(
+        "Sweeping cancelled post {:?} pot for token {}",
+        post_pda, token_name
+    );
+
+    let program_id = opinions_market.id();
+    let post_account = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    let post_pot_token_account_pda = Pubkey::find_program_address(
+        &[
+            POST_POT_TOKEN_ACCOUNT_SEED,
+            post_pda.as_ref(),
+            token_mint.as_ref(),
+        ],
+        &program_id,
+    )
+    .0;
+    let post_pot_authority_pda =
+        Pubkey::find_program_address(&[POST_POT_AUTHORITY_SEED, post_pda.as_ref()], &program_id).0;
+    let protocol_treasury_token_account_pda = Pubkey::find_program_address(
+        &[PROTOCOL_TREASURY_TOKEN_ACCOUNT_SEED, token_mint.as_ref()],
+        &program_id,
+    )
+    .0;
+    let pot_rent_payer = post_account.pot_rent_payer(token_mint).unwrap();
+
+    let sweep_ix = opinions_market
+        .request()
+        .accounts(opinions_market::accounts::SweepCancelledPostPot {
+            config: *config_pda,
+            payer: payer.pubkey(),
+            post: *post_pda,
+            post_pot_token_account: post_pot_token_account_pda,
+            post_pot_authority: post_pot_authority_pda,
+            protocol_token_treasury_token_account: protocol_treasury_token_account_pda,
+            pot_rent_payer,
+            token_mint: *token_mint,
+            token_program: spl_token::ID,
+        })
+        .args(opinions_market::instruction::SweepCancelledPostPot {
+            _post_id_hash: post_account.post_id_hash,
+        })
+        .instructions()
+        .unwrap();
+
+    // Refunds stay open until the deadline recorded at cancel_post
+    let now = current_chain_timestamp(rpc).await;
+    if post_account.refund_deadline > now {
+        assert!(
+            send_tx(&rpc, sweep_ix.clone(), &payer.pubkey(), &[&payer])
+                .await
+                .is_err(),
+            "The pot should not be swept before the refund deadline"
+        );
+        wait_for_seconds((post_account.refund_deadline - now) as u64 + 1).await;
+    }
+
+    let pot_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(post_pot_token_account_pda)
+        .await
+        .unwrap();
+    let pot_rent_payer_before = rpc.get_balance(&pot_rent_payer).await.unwrap();
+    let treasury_before = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(protocol_treasury_token_account_pda)
+        .await
+        .unwrap();
+
+    let sweep_tx = send_tx(&rpc, sweep_ix, &payer.pubkey(), &[&payer])
+        .await
+        .unwrap();
+    println!("sweep cancelled post pot tx: {:?}", sweep_tx);
+
+    let treasury_after = opinions_market
+        .account::<anchor_spl::token::TokenAccount>(protocol_treasury_token_account_pda)
+        .await
+        .unwrap();
+    assert_eq!(
+        treasury_after.amount,
+        treasury_before.amount + pot_before.amount,
+        "Treasury should receive everything left in the pot"
+    );
+    assert!(
+        rpc.get_account(&post_pot_token_account_pda).await.is_err(),
+        "Pot should be closed"
+    );
+    assert!(
+        rpc.get_balance(&pot_rent_payer).await.unwrap() > pot_rent_payer_before,
+        "Pot rent should go back to the account that funded it"
+    );
+
+    let post_after = opinions_market
+        .account::<opinions_market::state::PostAccount>(*post_pda)
+        .await
+        .unwrap();
+    assert!(
+        post_after.pot_swept(token_mint),
+        "Pot should be marked swept"
+    );
+    println!(
+        "✅ Swept {} {} left in the cancelled pot to the treasury",
+        pot_before.amount, token_name
+    );
+}
+
+/// The share mint of `side` on a tokenized post and `owner`'s associated token account for it,
+/// created if it doesn't exist yet
+async fn get_or_create_share_token_account(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    post_account: &opinions_market::state::PostAccount,
+    side: opinions_market::state::Side,
+    owner: &Pubkey,
+) -> (Pubkey, Pubkey) {
+    let share_mint = post_account.share_mint(side);
+    let share_token_account =
+        spl_associated_token_account::get_associated_token_address(owner, &share_mint);
+
+    if rpc.get_account(&share_token_account).await.is_err() {
+        let create_ata_ix = create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            owner,
+            &share_mint,
+            &spl_token::ID,
+        );
+        send_tx(&rpc, vec![create_ata_ix], &payer.pubkey(), &[&payer])
+            .await
+            .unwrap();
+        println!("🌟 created share token account {}", share_token_account);
+    }
+
+    (share_mint, share_token_account)
+}
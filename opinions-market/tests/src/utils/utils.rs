@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use anchor_client::anchor_lang::solana_program::example_mocks::solana_sdk::system_instruction;
+use anchor_client::anchor_lang::AnchorDeserialize;
 use anchor_client::Program;
 use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account;
 use anchor_spl::{
@@ -8,6 +9,7 @@ use anchor_spl::{
     token::spl_token,
 };
 
+use base64::{engine::general_purpose, Engine as _};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     instruction::Instruction,
@@ -37,18 +39,39 @@ pub async fn wait_for_seconds(seconds: u64) {
 pub fn sign_message_for_session_registration(
     user: &Keypair,
     session_key: &Pubkey,
+    expires_at: i64,
+    privileges_hash: &[u8; 32],
+    nonce: u64,
 ) -> (String, Vec<u8>, [u8; 64]) {
     // Format message
-    let message = format!("SESSION:{}", session_key);
+    let message = opinions_market::middleware::session::session_message(
+        opinions_market::middleware::session::SESSION_ACTION_REGISTER,
+        &opinions_market::ID,
+        session_key,
+        expires_at,
+        privileges_hash,
+        nonce,
+    );
     let message_bytes = message.as_bytes().to_vec();
     let signature = user.sign_message(&message_bytes).as_array().clone();
     (message, message_bytes, signature)
 }
 
-pub fn create_ed25519_instruction_for_session(user: &Keypair, session_key: &Pubkey) -> Instruction {
+pub fn create_ed25519_instruction_for_session(
+    user: &Keypair,
+    session_key: &Pubkey,
+    expires_at: i64,
+    privileges_hash: &[u8; 32],
+    nonce: u64,
+) -> Instruction {
     // Get the signature and message (reuse existing function)
-    let (message, message_bytes, signature_bytes) =
-        sign_message_for_session_registration(user, session_key);
+    let (message, message_bytes, signature_bytes) = sign_message_for_session_registration(
+        user,
+        session_key,
+        expires_at,
+        privileges_hash,
+        nonce,
+    );
 
     // Use the function that accepts signature bytes directly
     solana_sdk::ed25519_instruction::new_ed25519_instruction_with_signature(
@@ -67,7 +90,8 @@ pub async fn wait_for_post_to_expire(
         .account::<opinions_market::state::PostAccount>(*post_pda)
         .await
         .unwrap();
-    let end = post_account.end_time;
+    // Commit–reveal posts only settle once the reveal window is over
+    let end = post_account.end_time.max(post_account.reveal_end_time);
     let now = current_chain_timestamp(rpc).await;
     if end > now {
         let wait_secs = (end - now) as u64;
@@ -315,4 +339,52 @@ pub async fn send_tx<T: Signers + ?Sized>(
     }
 }
 
-pub const PRIVILEGES_HASH: [u8; 32] = [0u8; 32];
+/// Simulates the instructions and decodes what the last one returned with set_return_data,
+/// e.g. the VoteQuote of quote_vote
+pub async fn simulate_return_data<R: AnchorDeserialize, T: Signers + ?Sized>(
+    rpc: &RpcClient,
+    ixs: Vec<Instruction>,
+    payer: &Pubkey,
+    signer: &T,
+) -> anyhow::Result<R> {
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let message = Message::try_compile(payer, &ixs, &[], blockhash)?;
+    let v0_message = VersionedMessage::V0(message);
+    let tx = VersionedTransaction::try_new(v0_message, signer)?;
+
+    let simulation = rpc.simulate_transaction(&tx).await?.value;
+    if let Some(err) = simulation.err {
+        return Err(anyhow::anyhow!("Simulation failed: {:?}", err));
+    }
+
+    let return_data = simulation
+        .return_data
+        .ok_or_else(|| anyhow::anyhow!("Simulation returned no data"))?;
+    let bytes = general_purpose::STANDARD.decode(return_data.data.0)?;
+    Ok(R::try_from_slice(&bytes)?)
+}
+
+/// Session privileges used by the tests: every session-gated instruction, any mint,
+/// and a spend cap large enough for the whole scenario.
+pub fn session_privileges() -> opinions_market::state::SessionPrivileges {
+    use opinions_market::constants::*;
+
+    opinions_market::state::SessionPrivileges {
+        allowed_instructions: SESSION_PRIVILEGE_CREATE_POST
+            | SESSION_PRIVILEGE_VOTE_ON_POST
+            | SESSION_PRIVILEGE_CLAIM_POST_REWARD,
+        max_bling_spend: 1_000_000 * LAMPORTS_PER_SOL,
+        allowed_mints: [Pubkey::default(); MAX_SESSION_ALLOWED_MINTS],
+    }
+}
+
+/// Marks `session_key` as a signer wherever the instructions pass it, since a session
+/// only acts for its user when the session key itself signs
+pub fn sign_as_session_key(mut ixs: Vec<Instruction>, session_key: &Pubkey) -> Vec<Instruction> {
+    for meta in ixs.iter_mut().flat_map(|ix| ix.accounts.iter_mut()) {
+        if meta.pubkey == *session_key {
+            meta.is_signer = true;
+        }
+    }
+    ixs
+}
@@ -19,7 +19,7 @@ import { useSolanaStore } from "@/lib/stores/solanaStore";
  * Hook to onboard a new user with session registration
  * Flow:
  * 1. Generate ephemeral session keypair
- * 2. Create message: SESSION:{session_key}
+ * 2. Fetch message: SESSION:{program_id}:{session_key}:{expires_at}:{privileges_hash}
  * 3. Prompt user to sign message with their wallet
  * 4. Call onboardUser GraphQL mutation with handle, displayName, and session data
  * 5. Backend creates user, ensures on-chain user exists, and registers session
@@ -99,13 +99,14 @@ export function useOnboardUser() {
         signatureBase58.slice(0, 20) + "..."
       );
 
-      // Step 5: Call GraphQL onboardUser mutation (only send signature, backend has session key)
+      // Step 5: Call GraphQL onboardUser mutation (signature plus the message it covers)
       console.log("📤 useOnboardUser: Calling GraphQL onboardUser mutation...");
       const onboardData = await graphqlRequest<OnboardUserResult>(
         ONBOARD_USER_MUTATION,
         {
           input: {
             sessionSignature: signatureBase58,
+            sessionMessage: base64Message,
           },
         },
         identityToken
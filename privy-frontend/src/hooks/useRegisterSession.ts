@@ -87,7 +87,7 @@ export function useRenewSession() {
         signatureBase58.slice(0, 20) + "..."
       );
 
-      // Step 5: Call GraphQL renewSession mutation (only send signature, backend has session key)
+      // Step 5: Call GraphQL renewSession mutation (signature plus the message it covers)
       console.log(
         "📤 useRenewSession: Calling GraphQL renewSession mutation..."
       );
@@ -96,6 +96,7 @@ export function useRenewSession() {
         {
           input: {
             sessionSignature: signatureBase58,
+            sessionMessage: base64Message,
           },
         },
         identityToken
@@ -286,6 +286,7 @@ export interface TweetVoteInput {
   tweetId: string;
   side: string; // "pump" or "smack"
   tokenMint?: string; // Optional, defaults to BLING
+  maxCostInToken?: string; // Optional slippage guard in token base units, no limit if unset
 }
 
 export interface TweetVoteResult {
@@ -340,6 +341,7 @@ export const SETTLE_POST_MUTATION = `
           protocolFee
           motherFee
           totalPayout
+          policy
         }
       }
     }
@@ -294,3 +294,35 @@ export interface TweetThreadQueryResult {
     replies: TweetNode[];
   };
 }
+
+export const VOTE_QUOTE_QUERY = `
+  query VoteQuote($tweetId: ID!, $side: String!, $votes: Int, $tokenMint: String) {
+    voteQuote(tweetId: $tweetId, side: $side, votes: $votes, tokenMint: $tokenMint) {
+      votes
+      costBling
+      protocolFee
+      creatorPumpFee
+      potIncrement
+      tokenMint
+      protocolFeeToken
+      creatorPumpFeeToken
+      potIncrementToken
+      costInToken
+    }
+  }
+`;
+
+export interface VoteQuoteQueryResult {
+  voteQuote: {
+    votes: string;
+    costBling: string;
+    protocolFee: string;
+    creatorPumpFee: string;
+    potIncrement: string;
+    tokenMint: string;
+    protocolFeeToken: string;
+    creatorPumpFeeToken: string;
+    potIncrementToken: string;
+    costInToken: string; // Pass (plus any tolerance) as maxCostInToken on tweetVote
+  };
+}
@@ -48,6 +48,7 @@ export interface PostMintPayoutNode {
   protocolFee: string;
   motherFee: string;
   totalPayout: string;
+  policy: "Winners" | "RefundLosers" | "ParentRollover" | "TreasurySweep";
 }
 
 export interface PostStateNode {
@@ -9,17 +9,34 @@ export function generateSessionKeypair(): Keypair {
 
 /**
  * Format the session message that users will sign
- * Format: "SESSION:{sessionKeyPubkey}"
+ * Format: "SESSION:{programId}:{sessionKeyPubkey}:{expiresAt}:{privilegesHashHex}"
+ * The program rebuilds this message on-chain, so every field must match exactly.
  */
-export function formatSessionMessage(sessionKeyPubkey: string): string {
-  return `SESSION:${sessionKeyPubkey}`;
+export function formatSessionMessage(
+  programId: string,
+  sessionKeyPubkey: string,
+  expiresAt: number,
+  privilegesHashHex: string
+): string {
+  return `SESSION:${programId}:${sessionKeyPubkey}:${expiresAt}:${privilegesHashHex}`;
 }
 
 /**
- * Verify that a message matches the expected session format
+ * Verify that a message is a session message for the expected program and session key
  */
-export function isValidSessionMessage(message: string, sessionKeyPubkey: string): boolean {
-  const expected = formatSessionMessage(sessionKeyPubkey);
-  return message === expected;
+export function isValidSessionMessage(
+  message: string,
+  programId: string,
+  sessionKeyPubkey: string
+): boolean {
+  const [action, messageProgramId, messageSessionKey, expiresAt, privilegesHash] =
+    message.split(":");
+  return (
+    action === "SESSION" &&
+    messageProgramId === programId &&
+    messageSessionKey === sessionKeyPubkey &&
+    /^\d+$/.test(expiresAt ?? "") &&
+    /^[0-9a-f]{64}$/.test(privilegesHash ?? "")
+  );
 }
 
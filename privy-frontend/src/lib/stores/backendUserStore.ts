@@ -119,7 +119,7 @@ export const useBackendUserStore = create<
         signatureBase58.slice(0, 20) + "..."
       );
 
-      // Step 5: Call GraphQL onboardUser mutation (only send signature, backend has session key)
+      // Step 5: Call GraphQL onboardUser mutation (signature plus the message it covers)
       console.log("📤 onboardUser: Calling GraphQL onboardUser mutation...");
       const data = await graphqlRequest<OnboardUserResult>(
         ONBOARD_USER_MUTATION,
@@ -128,6 +128,7 @@ export const useBackendUserStore = create<
             handle,
             displayName,
             sessionSignature: signatureBase58,
+            sessionMessage: base64Message,
           },
         },
         identityToken